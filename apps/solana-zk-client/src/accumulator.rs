@@ -0,0 +1,164 @@
+//! Rebuilding a verification accumulator shard of the registry from its `StatementVerified` events,
+//! to prove that a statement was verified against an accumulator root.
+
+use solana_zk::merkle::node_hash;
+use solana_zk::state::{ZkvmVerifier, ACCUMULATOR_DEPTH};
+
+pub use solana_zk::events::StatementVerified;
+
+/// Off-chain copy of the verification accumulator tree
+#[derive(Clone, Debug, Default)]
+pub struct AccumulatorTree {
+    leaves: Vec<[u8; 32]>,
+}
+
+impl AccumulatorTree {
+    pub fn new(leaves: Vec<[u8; 32]>) -> Self {
+        Self { leaves }
+    }
+
+    /// Append a statement hash, returning its leaf index
+    pub fn push(&mut self, statement_hash: [u8; 32]) -> u64 {
+        self.leaves.push(statement_hash);
+        self.leaves.len() as u64 - 1
+    }
+
+    pub fn len(&self) -> u64 {
+        self.leaves.len() as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.fold(None).0
+    }
+
+    /// Inclusion proof of the leaf at `index`, for `solana_zk::merkle::verify_statement`
+    pub fn proof(&self, index: u64) -> Option<[[u8; 32]; ACCUMULATOR_DEPTH]> {
+        if index >= self.len() {
+            return None;
+        }
+
+        Some(self.fold(Some(index)).1)
+    }
+
+    // Hash the leaves up to the root, padding each level with the empty subtree hash, and collect
+    // the siblings on the path of the leaf at `index`
+    fn fold(&self, index: Option<u64>) -> ([u8; 32], [[u8; 32]; ACCUMULATOR_DEPTH]) {
+        let mut proof = [[0u8; 32]; ACCUMULATOR_DEPTH];
+        let mut level = self.leaves.clone();
+        let mut zero = [0u8; 32];
+
+        for (depth, sibling) in proof.iter_mut().enumerate() {
+            if level.len() % 2 == 1 {
+                level.push(zero);
+            }
+            if let Some(index) = index {
+                let position = (index >> depth) as usize;
+                *sibling = level.get(position ^ 1).copied().unwrap_or(zero);
+            }
+
+            level = level
+                .chunks(2)
+                .map(|pair| node_hash(&pair[0], &pair[1]))
+                .collect();
+            zero = node_hash(&zero, &zero);
+        }
+
+        (level.first().copied().unwrap_or(zero), proof)
+    }
+
+    /// Rebuild the tree of the accumulator shard `shard` from the events of every verification,
+    /// in any order
+    pub fn from_events(events: &[StatementVerified], shard: u64) -> Option<Self> {
+        let mut events: Vec<&StatementVerified> =
+            events.iter().filter(|event| event.shard == shard).collect();
+        events.sort_by_key(|event| event.leaf_index);
+
+        let mut tree = Self::default();
+        for event in events {
+            // Leaves must be contiguous from the first verification
+            if tree.push(event.statement_hash) != event.leaf_index {
+                return None;
+            }
+        }
+
+        Some(tree)
+    }
+}
+
+/// Statements verified by the verifier under a revocation epoch it has since revoked
+pub fn revoked_statements<'a>(
+    events: &'a [StatementVerified],
+    verifier: &ZkvmVerifier,
+) -> Vec<&'a StatementVerified> {
+    events
+        .iter()
+        .filter(|event| {
+            event.registry == verifier.registry
+                && event.zkvm_selector == verifier.zkvm_selector
+                && event.zkvm_program_id == verifier.zkvm_program_id
+                && verifier.check_not_revoked(event.revocation_epoch).is_err()
+        })
+        .collect()
+}
+
+/// Decode the `StatementVerified` events in the logs of a transaction
+#[cfg(feature = "client")]
+pub fn statement_verified_events(logs: &[String]) -> Vec<StatementVerified> {
+    use anchor_client::anchor_lang::{AnchorDeserialize, Discriminator};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    logs.iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| STANDARD.decode(data).ok())
+        .filter_map(|data| {
+            let event = data.strip_prefix(StatementVerified::DISCRIMINATOR)?;
+            StatementVerified::try_from_slice(event).ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::pubkey::Pubkey;
+
+    fn event(shard: u64, leaf_index: u64, statement_hash: [u8; 32]) -> StatementVerified {
+        StatementVerified {
+            statement_hash,
+            registry: Pubkey::default(),
+            zkvm_selector: 1,
+            zkvm_program_id: Pubkey::default(),
+            revocation_epoch: 0,
+            caller: Pubkey::default(),
+            shard,
+            leaf_index,
+            root: [0u8; 32],
+        }
+    }
+
+    #[test]
+    fn from_events_keeps_the_requested_shard() {
+        let events = [
+            event(1, 1, [3u8; 32]),
+            event(0, 0, [1u8; 32]),
+            event(1, 0, [2u8; 32]),
+        ];
+
+        let tree = AccumulatorTree::from_events(&events, 1).unwrap();
+        assert_eq!(
+            tree.root(),
+            AccumulatorTree::new(vec![[2u8; 32], [3u8; 32]]).root()
+        );
+        assert_eq!(AccumulatorTree::from_events(&events, 0).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn from_events_rejects_missing_leaves() {
+        let events = [event(0, 0, [1u8; 32]), event(0, 2, [2u8; 32])];
+        assert!(AccumulatorTree::from_events(&events, 0).is_none());
+    }
+}
@@ -0,0 +1,99 @@
+//! Reading the audit log of registry admin actions.
+
+use solana_program::pubkey::Pubkey;
+use solana_zk::state::{AuditAction, AuditLog, AuditLogEntry, Role, DEFAULT_REGISTRY_ID};
+
+/// Entries of the audit log still in the ring buffer, oldest first
+pub fn audit_log_entries(audit_log: &AuditLog) -> Vec<AuditLogEntry> {
+    audit_log.entries()
+}
+
+/// Format an audit log entry as a single line, decoding its values according to the action
+pub fn format_audit_log_entry(entry: &AuditLogEntry) -> String {
+    let Some(action) = AuditAction::from_u8(entry.action) else {
+        return format!("slot {}: unknown action {}", entry.slot, entry.action);
+    };
+
+    let (old_value, new_value) = match action {
+        AuditAction::AddZkVerifier
+        | AuditAction::UpdateZkVerifier
+        | AuditAction::ActivateZkVerifier
+        | AuditAction::RemoveZkVerifier
+        | AuditAction::RevokeZkVerifierResults
+        | AuditAction::DeprecateZkVerifier
+        | AuditAction::SetTimelockDelay
+        | AuditAction::MigrateCounter
+        | AuditAction::MigrateZkVerifier => (
+            number(&entry.old_value).to_string(),
+            number(&entry.new_value).to_string(),
+        ),
+        AuditAction::FreezeZkVerifier
+        | AuditAction::EmergencyFreezeZkVerifier
+        | AuditAction::SetZkVerifierPermissioned
+        | AuditAction::SetRegistryPaused => (
+            (number(&entry.old_value) != 0).to_string(),
+            (number(&entry.new_value) != 0).to_string(),
+        ),
+        AuditAction::GrantRole | AuditAction::RevokeRole => {
+            (role(&entry.old_value), role(&entry.new_value))
+        }
+        AuditAction::AddAllowedCaller
+        | AuditAction::RemoveAllowedCaller
+        | AuditAction::CreateRegistry => (String::from("-"), String::from("-")),
+        AuditAction::SetRegistryAdmin | AuditAction::SetRegistryGuardian => {
+            (pubkey(&entry.old_value), pubkey(&entry.new_value))
+        }
+        AuditAction::AddDcapGuest
+        | AuditAction::RemoveDcapGuest
+        | AuditAction::SetDcapCollateralConfig
+        | AuditAction::SetAdminMultisig
+        | AuditAction::SetCanaryProof => (hex(&entry.old_value), hex(&entry.new_value)),
+    };
+
+    let mut line = format!("slot {}: {:?} by {}", entry.slot, action, entry.actor);
+    if entry.registry_id != DEFAULT_REGISTRY_ID {
+        line.push_str(&format!(" registry {}", entry.registry_id));
+    }
+    if entry.zkvm_selector != 0 {
+        line.push_str(&format!(" selector {}", entry.zkvm_selector));
+    }
+    if entry.subject != Pubkey::default() {
+        line.push_str(&format!(" on {}", entry.subject));
+    }
+    line.push_str(&format!(": {} -> {}", old_value, new_value));
+    line
+}
+
+/// Format every entry of the audit log, oldest first
+pub fn format_audit_log(audit_log: &AuditLog) -> String {
+    audit_log_entries(audit_log)
+        .iter()
+        .map(format_audit_log_entry)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn number(value: &[u8; 32]) -> u64 {
+    u64::from_le_bytes(value[..8].try_into().unwrap())
+}
+
+fn role(value: &[u8; 32]) -> String {
+    match number(value) {
+        0 => String::from("none"),
+        1 => format!("{:?}", Role::Admin),
+        2 => format!("{:?}", Role::Guardian),
+        3 => format!("{:?}", Role::Operator),
+        other => format!("unknown role {}", other - 1),
+    }
+}
+
+fn pubkey(value: &[u8; 32]) -> String {
+    Pubkey::new_from_array(*value).to_string()
+}
+
+fn hex(value: &[u8; 32]) -> String {
+    if value == &[0u8; 32] {
+        return String::from("-");
+    }
+    value.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,147 @@
+//! Helpers for verifying ZK proofs of Intel SGX/TDX DCAP quote verification, produced by the
+//! Automata DCAP guest program, through the registry.
+
+use solana_zk::dcap::{
+    JOURNAL_QUOTE_BODY_OFFSET, SGX_ENCLAVE_REPORT_LEN, TD10_REPORT_LEN, TEE_TYPE_SGX, TEE_TYPE_TDX,
+};
+
+pub use solana_zk::dcap::{DcapVerifiedOutput, TcbStatus};
+
+/// Length of the header of a v3/v4 DCAP quote
+pub const QUOTE_HEADER_LEN: usize = 48;
+
+/// Errors raised while checking a DCAP quote against the journal of the DCAP guest program
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DcapError {
+    /// The quote is too short or of an unsupported TEE type
+    MalformedQuote,
+    /// The journal is too short or of an unsupported TEE type
+    MalformedJournal,
+    /// The journal does not attest to the given quote
+    QuoteMismatch,
+    /// The guest vkey is not approved for the selector in the registry
+    GuestNotApproved,
+}
+
+fn report_len(tee_type: u32) -> Option<usize> {
+    match tee_type {
+        TEE_TYPE_SGX => Some(SGX_ENCLAVE_REPORT_LEN),
+        TEE_TYPE_TDX => Some(TD10_REPORT_LEN),
+        _ => None,
+    }
+}
+
+/// Return the report body of a raw v3/v4 DCAP quote
+pub fn quote_body(quote: &[u8]) -> Result<&[u8], DcapError> {
+    if quote.len() < QUOTE_HEADER_LEN {
+        return Err(DcapError::MalformedQuote);
+    }
+
+    // Quote headers are little endian
+    let tee_type = u32::from_le_bytes(quote[4..8].try_into().unwrap());
+    let len = report_len(tee_type).ok_or(DcapError::MalformedQuote)?;
+
+    quote
+        .get(QUOTE_HEADER_LEN..QUOTE_HEADER_LEN + len)
+        .ok_or(DcapError::MalformedQuote)
+}
+
+/// Return the quote body committed in the journal of the DCAP guest program
+pub fn journal_quote_body(journal: &[u8]) -> Result<&[u8], DcapError> {
+    if journal.len() < JOURNAL_QUOTE_BODY_OFFSET {
+        return Err(DcapError::MalformedJournal);
+    }
+
+    // The verified output is big endian
+    let tee_type = u32::from_be_bytes(journal[4..8].try_into().unwrap());
+    let len = report_len(tee_type).ok_or(DcapError::MalformedJournal)?;
+
+    journal
+        .get(JOURNAL_QUOTE_BODY_OFFSET..JOURNAL_QUOTE_BODY_OFFSET + len)
+        .ok_or(DcapError::MalformedJournal)
+}
+
+/// Check that the journal of the DCAP guest program attests to the given raw quote
+pub fn check_quote_matches_journal(quote: &[u8], journal: &[u8]) -> Result<(), DcapError> {
+    if quote_body(quote)? != journal_quote_body(journal)? {
+        return Err(DcapError::QuoteMismatch);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(tee_type: u32, body: &[u8]) -> Vec<u8> {
+        let mut quote = vec![0; QUOTE_HEADER_LEN];
+        quote[4..8].copy_from_slice(&tee_type.to_le_bytes());
+        quote.extend_from_slice(body);
+        // Quote signature data follows the body
+        quote.extend_from_slice(&[0xee; 64]);
+        quote
+    }
+
+    fn journal(tee_type: u32, body: &[u8]) -> Vec<u8> {
+        let mut journal = vec![0; JOURNAL_QUOTE_BODY_OFFSET];
+        journal[4..8].copy_from_slice(&tee_type.to_be_bytes());
+        journal.extend_from_slice(body);
+        // Advisory IDs, timestamp, TCB evaluation data number and collateral hashes follow the body
+        journal.extend_from_slice(&[0xdd; 8 + 4 + 6 * 32]);
+        journal
+    }
+
+    #[test]
+    fn matches_the_quote_body_of_sgx_and_tdx_quotes() {
+        for (tee_type, len) in [
+            (TEE_TYPE_SGX, SGX_ENCLAVE_REPORT_LEN),
+            (TEE_TYPE_TDX, TD10_REPORT_LEN),
+        ] {
+            let body: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            assert_eq!(
+                check_quote_matches_journal(&quote(tee_type, &body), &journal(tee_type, &body)),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_a_journal_of_another_quote() {
+        let body = vec![7; SGX_ENCLAVE_REPORT_LEN];
+        let mut other = body.clone();
+        other[SGX_ENCLAVE_REPORT_LEN - 1] ^= 1;
+
+        assert_eq!(
+            check_quote_matches_journal(
+                &quote(TEE_TYPE_SGX, &body),
+                &journal(TEE_TYPE_SGX, &other)
+            ),
+            Err(DcapError::QuoteMismatch)
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_or_unknown_quotes_and_journals() {
+        let body = vec![7; SGX_ENCLAVE_REPORT_LEN];
+        let sgx_quote = quote(TEE_TYPE_SGX, &body);
+        let sgx_journal = journal(TEE_TYPE_SGX, &body);
+
+        assert_eq!(
+            check_quote_matches_journal(&sgx_quote[..QUOTE_HEADER_LEN + 10], &sgx_journal),
+            Err(DcapError::MalformedQuote)
+        );
+        assert_eq!(
+            check_quote_matches_journal(&sgx_quote, &sgx_journal[..JOURNAL_QUOTE_BODY_OFFSET + 10]),
+            Err(DcapError::MalformedJournal)
+        );
+        assert_eq!(
+            check_quote_matches_journal(&quote(2, &body), &sgx_journal),
+            Err(DcapError::MalformedQuote)
+        );
+        assert_eq!(
+            check_quote_matches_journal(&sgx_quote, &journal(2, &body)),
+            Err(DcapError::MalformedJournal)
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use anchor_client::{
+    solana_client::{
+        client_error::{ClientError as SolanaClientError, ClientErrorKind},
+        rpc_request::{RpcError, RpcResponseErrorData},
+    },
+    solana_sdk::{instruction::InstructionError, transaction::TransactionError},
+    ClientError,
+};
+use solana_program::pubkey::Pubkey;
+use solana_zk::errors::ZkError;
+use std::fmt;
+use std::str::FromStr;
+
+use super::dcap::DcapError;
+use super::selector::ZkvmSelectorType;
+
+pub type Result<T> = std::result::Result<T, SolanaZkClientError>;
+
+/// Errors of the RISC Zero Groth16 verifier program, as listed in its IDL
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Risc0VerifierError {
+    G1CompressionError,
+    G2CompressionError,
+    VerificationError,
+    InvalidPublicInput,
+    ArithmeticError,
+    PairingError,
+}
+
+impl Risc0VerifierError {
+    /// Map an Anchor custom error code of the RISC Zero verifier to its variant
+    pub fn from_code(code: u32) -> Option<Self> {
+        match code {
+            6000 => Some(Self::G1CompressionError),
+            6001 => Some(Self::G2CompressionError),
+            6002 => Some(Self::VerificationError),
+            6003 => Some(Self::InvalidPublicInput),
+            6004 => Some(Self::ArithmeticError),
+            6005 => Some(Self::PairingError),
+            _ => None,
+        }
+    }
+}
+
+/// Errors returned by `SolanaZkClient`
+#[derive(Debug)]
+pub enum SolanaZkClientError {
+    /// The registry program rejected the request, also used for the equivalent client side checks
+    Registry(ZkError),
+    /// The RISC Zero verifier program rejected the proof
+    Risc0Verifier(Risc0VerifierError),
+    /// A verifier program failed with an error that is not known to this client. SP1 verifier errors
+    /// are out of scope and never mapped, like the SP1 verify instructions, see `UnsupportedZkvm`
+    VerifierProgram {
+        program_id: Pubkey,
+        error: InstructionError,
+    },
+    /// The transaction ran out of compute units
+    ComputeBudgetExhausted,
+    /// A DCAP quote or guest journal failed the client side checks
+    Dcap(DcapError),
+    /// The client cannot build verify instructions for this zkVM yet
+    UnsupportedZkvm(ZkvmSelectorType),
+    /// Any other RPC or transaction failure, boxed as it is much larger than the other variants
+    Client(Box<ClientError>),
+}
+
+impl SolanaZkClientError {
+    /// Classify a failed `verify_zkvm_proof` transaction, attributing verifier errors by selector
+    pub fn from_verify_error(
+        err: ClientError,
+        zkvm_selector: ZkvmSelectorType,
+        zkvm_verifier_program: Pubkey,
+    ) -> Self {
+        Self::classify(err, Some((zkvm_selector, zkvm_verifier_program)))
+    }
+
+    fn classify(err: ClientError, zkvm_verifier: Option<(ZkvmSelectorType, Pubkey)>) -> Self {
+        let ClientError::SolanaClientError(solana_err) = &err else {
+            return Self::Client(Box::new(err));
+        };
+
+        let logs = preflight_logs(solana_err);
+        if logs
+            .iter()
+            .any(|log| log.contains("exceeded CUs meter") || log.contains("Computational budget exceeded"))
+        {
+            return Self::ComputeBudgetExhausted;
+        }
+
+        let Some(TransactionError::InstructionError(_, instruction_error)) =
+            solana_err.get_transaction_error()
+        else {
+            return Self::Client(Box::new(err));
+        };
+        if instruction_error == InstructionError::ComputationalBudgetExceeded {
+            return Self::ComputeBudgetExhausted;
+        }
+
+        // The innermost failing program logs its failure first
+        let failed_program = logs.iter().find_map(|log| {
+            let rest = log.strip_prefix("Program ")?;
+            let (program_id, _) = rest.split_once(" failed: ")?;
+            Pubkey::from_str(program_id).ok()
+        });
+
+        match (failed_program, zkvm_verifier, &instruction_error) {
+            (Some(program_id), _, InstructionError::Custom(code)) if program_id == solana_zk::ID => {
+                match ZkError::from_code(*code) {
+                    Some(zk_error) => Self::Registry(zk_error),
+                    None => Self::Client(Box::new(err)),
+                }
+            }
+            (Some(program_id), Some((zkvm_selector, zkvm_verifier_program)), _)
+                if program_id == zkvm_verifier_program =>
+            {
+                let known = match (zkvm_selector, &instruction_error) {
+                    (
+                        ZkvmSelectorType::RiscZero | ZkvmSelectorType::AutomataDcap(_),
+                        InstructionError::Custom(code),
+                    ) => {
+                        Risc0VerifierError::from_code(*code).map(Self::Risc0Verifier)
+                    }
+                    // SP1 is out of scope, its verifier errors stay unmapped
+                    _ => None,
+                };
+                known.unwrap_or_else(|| Self::VerifierProgram {
+                    program_id,
+                    error: instruction_error.clone(),
+                })
+            }
+            _ => Self::Client(Box::new(err)),
+        }
+    }
+}
+
+// Collect the program logs attached to a failed preflight simulation
+fn preflight_logs(err: &SolanaClientError) -> Vec<String> {
+    match &err.kind {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+            ..
+        }) => result.logs.clone().unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+impl fmt::Display for SolanaZkClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Registry(e) => write!(f, "Registry error: {}", e),
+            Self::Risc0Verifier(e) => write!(f, "RISC Zero verifier error: {:?}", e),
+            Self::VerifierProgram { program_id, error } => {
+                write!(f, "Verifier program {} failed: {}", program_id, error)
+            }
+            Self::ComputeBudgetExhausted => write!(f, "Compute budget exhausted"),
+            Self::Dcap(e) => write!(f, "DCAP error: {:?}", e),
+            Self::UnsupportedZkvm(zkvm_selector) => {
+                write!(f, "Verify instructions are not supported for {:?}", zkvm_selector)
+            }
+            Self::Client(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SolanaZkClientError {}
+
+impl From<ClientError> for SolanaZkClientError {
+    fn from(err: ClientError) -> Self {
+        Self::classify(err, None)
+    }
+}
+
+impl From<SolanaClientError> for SolanaZkClientError {
+    fn from(err: SolanaClientError) -> Self {
+        Self::classify(ClientError::SolanaClientError(err), None)
+    }
+}
+
+impl From<ZkError> for SolanaZkClientError {
+    fn from(err: ZkError) -> Self {
+        Self::Registry(err)
+    }
+}
+
+impl From<DcapError> for SolanaZkClientError {
+    fn from(err: DcapError) -> Self {
+        Self::Dcap(err)
+    }
+}
@@ -1,8 +1,12 @@
 // The verify module is always included
 pub mod verify;
+pub mod accumulator;
+pub mod audit;
+pub mod merkle;
 pub use solana_zk::ID;
 
 use solana_program::pubkey::Pubkey;
+use solana_zk::state::{AdminAction, EnclaveKeyType};
 
 // TEMP
 pub const RISC0_VERIFIER_ROUTER_ID: Pubkey =
@@ -10,75 +14,330 @@ pub const RISC0_VERIFIER_ROUTER_ID: Pubkey =
 pub const SUCCINCT_SP1_VERIFIER_ID: Pubkey =
     Pubkey::from_str_const("2LUaFQTJ7F96A5x1z5sXfbDPM2asGnrQ2hsE6zVDMhXZ");
 
-/// Helper method to derive the PDA for a ZKVM verifier account
+/// Helper method to derive the program data account of an upgradeable program
+pub fn derive_program_data_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[program_id.as_ref()],
+        &solana_program::bpf_loader_upgradeable::ID,
+    )
+    .0
+}
+
+/// Helper method to derive the PDA for a registry, the default registry has id `DEFAULT_REGISTRY_ID`
+pub fn derive_registry_pda(registry_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"registry", &registry_id.to_le_bytes()], &ID)
+}
+
+/// Helper method to derive the PDA for a ZKVM verifier account of a registry
 pub fn derive_zkvm_verifier_pda(
+    registry: &Pubkey,
     zkvm_selector: u64,
     zkvm_verifier_program: &Pubkey,
 ) -> (Pubkey, u8) {
     Pubkey::find_program_address(
         &[
             b"zkvm_verifier",
+            registry.as_ref(),
+            zkvm_selector.to_le_bytes().as_ref(),
+            zkvm_verifier_program.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// Helper method to derive the PDA for the usage statistics of a ZKVM verifier account
+pub fn derive_zkvm_verifier_stats_pda(zkvm_verifier_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"zkvm_verifier_stats", zkvm_verifier_account.as_ref()],
+        &ID,
+    )
+}
+
+/// Helper method to derive the PDA approving a DCAP guest program for a zkVM selector of a registry
+pub fn derive_dcap_guest_pda(
+    registry: &Pubkey,
+    zkvm_selector: u64,
+    guest_vkey: &[u8; 32],
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"dcap_guest",
+            registry.as_ref(),
+            zkvm_selector.to_le_bytes().as_ref(),
+            guest_vkey.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// Helper method to derive the PDA holding the canary proof of a zkVM selector of a registry
+pub fn derive_canary_proof_pda(registry: &Pubkey, zkvm_selector: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"canary_proof",
+            registry.as_ref(),
             zkvm_selector.to_le_bytes().as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// Helper method to derive the PDA for a DCAP policy of an application authority
+pub fn derive_dcap_policy_pda(authority: &Pubkey, policy_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"dcap_policy",
+            authority.as_ref(),
+            policy_id.to_le_bytes().as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// Helper method to derive the PDA for an enclave key registered under a DCAP policy
+pub fn derive_enclave_key_pda(
+    dcap_policy: &Pubkey,
+    key_type: EnclaveKeyType,
+    key: &[u8; 32],
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"enclave_key",
+            dcap_policy.as_ref(),
+            &[key_type as u8],
+            key.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// Helper method to derive the PDA for a hybrid TEE-plus-ZK policy of an application authority
+pub fn derive_hybrid_policy_pda(authority: &Pubkey, policy_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"hybrid_policy",
+            authority.as_ref(),
+            policy_id.to_le_bytes().as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// Helper method to derive the PDA for a verified state of an owner
+pub fn derive_verified_state_pda(owner: &Pubkey, state_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"verified_state",
+            owner.as_ref(),
+            state_id.to_le_bytes().as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// Helper method to derive the PDA marking the nonce of a freshness envelope as used by a caller
+pub fn derive_proof_nonce_pda(caller: &Pubkey, nonce: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"proof_nonce", caller.as_ref(), nonce.as_ref()], &ID)
+}
+
+/// Helper method to derive the PDA for a verified journal of an authority
+pub fn derive_verified_journal_pda(authority: &Pubkey, buffer_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"verified_journal",
+            authority.as_ref(),
+            buffer_id.to_le_bytes().as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// Helper method to derive the PDA for a shard of the verification accumulator
+pub fn derive_verification_accumulator_pda(shard: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"verification_accumulator", shard.to_le_bytes().as_ref()],
+        &ID,
+    )
+}
+
+/// Helper method to derive the PDA granting a role to `member`
+pub fn derive_role_pda(member: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"role", member.as_ref()], &ID)
+}
+
+/// Helper method to derive the PDA for the audit log of a registry, admin actions that are not
+/// scoped to a registry are recorded in the audit log of the default registry
+pub fn derive_audit_log_pda(registry: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"audit_log", registry.as_ref()], &ID)
+}
+
+/// Helper method to derive the PDA for the admin multisig
+pub fn derive_admin_multisig_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"admin_multisig"], &ID)
+}
+
+/// Helper method to derive the PDA for an admin proposal
+pub fn derive_admin_proposal_pda(proposal_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"admin_proposal", &proposal_id.to_le_bytes()], &ID)
+}
+
+/// Helper method to derive the PDA of an admin action queued by a single admin of `registry`
+pub fn derive_queued_admin_action_pda(registry: &Pubkey, action: &AdminAction) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"queued_admin_action",
+            registry.as_ref(),
+            action.digest().as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// Helper method to derive the PDA of the verifier counter of the v0 account layout
+pub fn derive_v0_counter_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"counter"], &ID)
+}
+
+/// Helper method to derive the PDA for a ZKVM verifier account of the v0 account layout, before
+/// registries existed
+pub fn derive_v0_zkvm_verifier_pda(
+    zkvm_selector: u64,
+    zkvm_verifier_program: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"zkvm_verifier",
+            &zkvm_selector.to_le_bytes(),
             zkvm_verifier_program.as_ref(),
         ],
         &ID,
     )
 }
 
+/// Helper method to derive the PDA allowing `caller` to use a permissioned verifier
+pub fn derive_allowed_caller_pda(zkvm_verifier_account: &Pubkey, caller: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"allowed_caller", zkvm_verifier_account.as_ref(), caller.as_ref()],
+        &ID,
+    )
+}
+
+/// Helper method to derive the PDA signing `verify_zkvm_proof_and_call` callbacks
+pub fn derive_callback_authority_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[solana_zk::CALLBACK_AUTHORITY_SEED], &ID)
+}
+
+/// Helper method to derive the PDA for the DCAP collateral config
+pub fn derive_dcap_collateral_config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"dcap_collateral"], &ID)
+}
+
 // Other modules and imports are conditionally included
 #[cfg(feature = "client")]
+pub mod dcap;
+#[cfg(feature = "client")]
+pub mod error;
+#[cfg(feature = "client")]
 pub mod selector;
 #[cfg(feature = "client")]
 use selector::ZkvmSelectorType;
 
 #[cfg(feature = "client")]
-use verify::risc0::risc0_verify_instruction_data;
-#[cfg(feature = "client")]
-use verify::succinct::sp1_groth16_verify_instruction_data;
+use dcap::{check_quote_matches_journal, DcapError};
 
 // Conditionally include client-specific imports
 #[cfg(feature = "client")]
 use anchor_client::{
+    solana_client::{rpc_config::RpcProgramAccountsConfig, rpc_filter::RpcFilterType},
     solana_sdk::{
-        commitment_config::CommitmentConfig, signer::Signer, system_program,
+        commitment_config::CommitmentConfig,
+        instruction::{AccountMeta, Instruction},
+        signer::Signer,
+        system_program, sysvar,
     },
     Client, Cluster, Program,
 };
 #[cfg(feature = "client")]
-use anyhow::{Error, Result};
+use error::{Result, SolanaZkClientError};
+#[cfg(feature = "client")]
+use solana_zk::errors::ZkError;
+#[cfg(feature = "client")]
+use solana_zk::freshness::FreshnessEnvelope;
 use solana_zk::{accounts, instruction};
 #[cfg(feature = "client")]
+use solana_zk::state::{
+    default_registry_address, AdminMultisig, AdminProposal, AllowedCaller, AuditLog,
+    AuditLogEntry, CanaryProof, Counter, DcapCollateralConfig, DcapGuest, EnclaveKey,
+    HybridMode, HybridPolicy, QueuedAdminAction, Registry, Role, RoleAssignment,
+    VerificationAccumulator, VerifiedJournal, VerifiedState, ZkvmVerifier, ZkvmVerifierStats,
+    ZkvmVerifierV0, ACCUMULATOR_SHARDS, ADMIN_ACTION_KINDS, DEFAULT_REGISTRY_ID,
+};
+#[cfg(feature = "client")]
 use std::ops::Deref;
 
-/// Client for interacting with the Solana ZK program
+/// Journal bytes sent per transaction, leaving room for the proof and accounts within the packet size
+#[cfg(feature = "client")]
+const JOURNAL_CHUNK_LEN: usize = 512;
+
+/// Client for interacting with the Solana ZK program, scoped to one registry
 #[cfg(feature = "client")]
 pub struct SolanaZkClient<C> {
     program: Program<C>,
-    counter: Pubkey,
+    registry: Pubkey,
+    accumulator_shard: u64,
+    admin_proposal: Option<u64>,
 }
 
 #[cfg(feature = "client")]
 impl<C: Clone + Deref<Target = impl Signer>> SolanaZkClient<C> {
-    /// Create a new client instance
+    /// Create a new client instance for the default registry
     pub fn new(payer: C, cluster: Option<Cluster>) -> Self {
         let cluster = cluster.unwrap_or(Cluster::Localnet);
+        // Spread payers over the accumulator shards so they don't all write the same account
+        let accumulator_shard = payer.pubkey().to_bytes()[0] as u64 % ACCUMULATOR_SHARDS;
         let client = Client::new_with_options(cluster, payer, CommitmentConfig::confirmed());
         let program = client.program(ID).unwrap();
 
         Self {
             program,
-            counter: derive_counter_pda().0,
+            registry: derive_registry_pda(DEFAULT_REGISTRY_ID).0,
+            accumulator_shard,
+            admin_proposal: None,
         }
     }
 
-    /// Initialize a new counter account for tracking ZKVM verifiers
+    /// Scope the client to the registry `registry_id`, verifiers, selectors and DCAP guests are
+    /// then looked up and configured in that registry
+    pub fn with_registry(mut self, registry_id: u64) -> Self {
+        self.registry = derive_registry_pda(registry_id).0;
+        self
+    }
+
+    /// Append the statements verified by this client to the accumulator shard `shard`, instead
+    /// of the one picked from the payer
+    pub fn with_accumulator_shard(mut self, shard: u64) -> Self {
+        self.accumulator_shard = shard;
+        self
+    }
+
+    /// Execute admin actions with the approved proposal `proposal_id` once the admin multisig is
+    /// initialized. Any payer can execute a proposal whose timelock expired, e.g. with
+    /// `grant_role` for an `AdminAction::GrantRole` proposal
+    pub fn with_admin_proposal(mut self, proposal_id: u64) -> Self {
+        self.admin_proposal = Some(proposal_id);
+        self
+    }
+
+    /// Initialize the default registry, the verification accumulator and the audit log
     pub async fn initialize(&self) -> Result<String> {
         let signature = self
             .program
             .request()
             .accounts(accounts::Initialize {
                 payer: self.program.payer(),
-                counter: self.counter,
+                registry: self.registry,
+                verification_accumulator: derive_verification_accumulator_pda(0).0,
+                audit_log: derive_audit_log_pda(&self.registry).0,
                 system_program: system_program::ID,
             })
             .args(instruction::Initialize {})
@@ -88,14 +347,16 @@ impl<C: Clone + Deref<Target = impl Signer>> SolanaZkClient<C> {
         Ok(signature.to_string())
     }
 
-    /// Add a new ZKVM verifier program
+    /// Add a new ZKVM verifier program, once queued with `queue_admin_action` and its timelock
+    /// expired while the admin multisig is not initialized. An `AutomataDcap` selector adds a DCAP
+    /// verifier, which only verifies proofs of approved DCAP guest programs
     pub async fn add_zk_verifier_program(
         &self,
         zkvm_selector: ZkvmSelectorType,
         overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
     ) -> Result<String> {
-        // Ensure the payer is the program's upgrade authority
-        self.require_upgrade_authority().await?;
+        // Ensure the payer holds the admin role on the registry
+        let owner_role = self.require_registry_role(Role::Admin).await?;
 
         let zkvm_selector_u64 = zkvm_selector.to_u64();
         let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
@@ -104,25 +365,42 @@ impl<C: Clone + Deref<Target = impl Signer>> SolanaZkClient<C> {
         };
 
         let (verifier_account, _bump) =
-            derive_zkvm_verifier_pda(zkvm_selector_u64, &zkvm_verifier_program);
+            derive_zkvm_verifier_pda(&self.registry, zkvm_selector_u64, &zkvm_verifier_program);
         let (program_data, _) = Pubkey::find_program_address(
             &[ID.as_ref()],
             &solana_program::bpf_loader_upgradeable::ID,
         );
+        let queued_admin_action = self
+            .queued_admin_action_account(&AdminAction::AddZkVerifier {
+                zkvm_selector: zkvm_selector_u64,
+                zkvm_program_id: zkvm_verifier_program,
+                journal_digest: zkvm_selector.journal_digest(),
+                dcap: zkvm_selector.is_dcap(),
+            })
+            .await;
 
         let signature = self
             .program
             .request()
             .accounts(accounts::AddZkvmVerifier {
                 owner: self.program.payer(),
-                counter: self.counter,
+                owner_role,
+                registry: self.registry,
                 zkvm_verifier_account: verifier_account,
+                zkvm_verifier_stats: derive_zkvm_verifier_stats_pda(&verifier_account).0,
                 program_data,
                 zkvm_verifier_program,
+                zkvm_verifier_program_data: derive_program_data_address(&zkvm_verifier_program),
+                audit_log: derive_audit_log_pda(&self.registry).0,
                 system_program: system_program::ID,
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: self.admin_proposal_account(),
+                queued_admin_action,
             })
             .args(instruction::AddZkVerifierProgram {
                 zkvm_selector: zkvm_selector_u64,
+                journal_digest: zkvm_selector.journal_digest(),
+                dcap: zkvm_selector.is_dcap(),
             })
             .send()
             .await?;
@@ -130,14 +408,15 @@ impl<C: Clone + Deref<Target = impl Signer>> SolanaZkClient<C> {
         Ok(signature.to_string())
     }
 
-    /// Update an existing ZKVM verifier program
+    /// Update an existing ZKVM verifier program, once queued with `queue_admin_action` and its
+    /// timelock expired while the admin multisig is not initialized
     pub async fn update_zk_verifier_program(
         &self,
         zkvm_selector: ZkvmSelectorType,
         overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
     ) -> Result<String> {
-        // Ensure the payer is the program's upgrade authority
-        self.require_upgrade_authority().await?;
+        // Ensure the payer holds the admin role on the registry
+        let owner_role = self.require_registry_role(Role::Admin).await?;
 
         let zkvm_selector_u64 = zkvm_selector.to_u64();
         let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
@@ -146,23 +425,36 @@ impl<C: Clone + Deref<Target = impl Signer>> SolanaZkClient<C> {
         };
 
         let (verifier_account, _bump) =
-            derive_zkvm_verifier_pda(zkvm_selector_u64, &zkvm_verifier_program);
+            derive_zkvm_verifier_pda(&self.registry, zkvm_selector_u64, &zkvm_verifier_program);
         let (program_data, _) = Pubkey::find_program_address(
             &[ID.as_ref()],
             &solana_program::bpf_loader_upgradeable::ID,
         );
+        let queued_admin_action = self
+            .queued_admin_action_account(&AdminAction::UpdateZkVerifier {
+                zkvm_selector: zkvm_selector_u64,
+                zkvm_program_id: zkvm_verifier_program,
+            })
+            .await;
 
         let signature = self
             .program
             .request()
             .accounts(accounts::UpdateZkvmVerifierConfig {
                 owner: self.program.payer(),
+                owner_role,
+                registry: self.registry,
                 zkvm_verifier_account: verifier_account,
                 program_data,
                 zkvm_verifier_program,
+                zkvm_verifier_program_data: derive_program_data_address(&zkvm_verifier_program),
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: self.admin_proposal_account(),
+                queued_admin_action,
+                audit_log: derive_audit_log_pda(&self.registry).0,
             })
             .args(instruction::UpdateZkVerifierProgram {
-                _zkvm_selector: zkvm_selector_u64,
+                zkvm_selector: zkvm_selector_u64,
             })
             .send()
             .await?;
@@ -170,15 +462,16 @@ impl<C: Clone + Deref<Target = impl Signer>> SolanaZkClient<C> {
         Ok(signature.to_string())
     }
 
-    /// Freeze a ZKVM verifier program to prevent further updates
+    /// Freeze a ZKVM verifier program to prevent further updates, unfreezing must be queued with
+    /// `queue_admin_action` first while the admin multisig is not initialized
     pub async fn freeze_zk_verifier_program(
         &self,
         zkvm_selector: ZkvmSelectorType,
         overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
         freeze: bool,
     ) -> Result<String> {
-        // Ensure the payer is the program's upgrade authority
-        self.require_upgrade_authority().await?;
+        // Ensure the payer holds the admin role on the registry
+        let owner_role = self.require_registry_role(Role::Admin).await?;
 
         let zkvm_selector_u64 = zkvm_selector.to_u64();
         let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
@@ -187,23 +480,37 @@ impl<C: Clone + Deref<Target = impl Signer>> SolanaZkClient<C> {
         };
 
         let (verifier_account, _bump) =
-            derive_zkvm_verifier_pda(zkvm_selector_u64, &zkvm_verifier_program);
+            derive_zkvm_verifier_pda(&self.registry, zkvm_selector_u64, &zkvm_verifier_program);
         let (program_data, _) = Pubkey::find_program_address(
             &[ID.as_ref()],
             &solana_program::bpf_loader_upgradeable::ID,
         );
+        let queued_admin_action = self
+            .queued_admin_action_account(&AdminAction::FreezeZkVerifier {
+                zkvm_selector: zkvm_selector_u64,
+                zkvm_program_id: zkvm_verifier_program,
+                freeze,
+            })
+            .await;
 
         let signature = self
             .program
             .request()
             .accounts(accounts::UpdateZkvmVerifierConfig {
                 owner: self.program.payer(),
+                owner_role,
+                registry: self.registry,
                 zkvm_verifier_account: verifier_account,
                 program_data,
                 zkvm_verifier_program,
+                zkvm_verifier_program_data: derive_program_data_address(&zkvm_verifier_program),
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: self.admin_proposal_account(),
+                queued_admin_action,
+                audit_log: derive_audit_log_pda(&self.registry).0,
             })
             .args(instruction::FreezeZkVerifierProgram {
-                _zkvm_selector: zkvm_selector_u64,
+                zkvm_selector: zkvm_selector_u64,
                 freeze,
             })
             .send()
@@ -212,93 +519,2808 @@ impl<C: Clone + Deref<Target = impl Signer>> SolanaZkClient<C> {
         Ok(signature.to_string())
     }
 
-    /// Interface for verifying ZKVM proofs (to be implemented by user)
-    pub async fn verify_zkvm_proof(
-        &self,
-        zkvm_selector: ZkvmSelectorType,
-        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
-        program_vkey: [u8; 32],
-        output_digest: [u8; 32],
-        proof_data: &[u8],
-    ) -> Result<String> {
-        let zkvm_selector_u64 = zkvm_selector.to_u64();
-        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
-            Some(pubkey) => pubkey,
-            None => zkvm_selector.to_zkvm_verifier_id(),
-        };
+    /// Grant `role` to `member`, a member holds at most one role. It must be queued with
+    /// `queue_admin_action` first while the admin multisig is not initialized
+    pub async fn grant_role(&self, member: Pubkey, role: Role) -> Result<String> {
+        // Ensure the payer holds the admin role
+        let owner_role = self.require_role(Role::Admin).await?;
 
-        let (verifier_account, _bump) =
-            derive_zkvm_verifier_pda(zkvm_selector_u64, &zkvm_verifier_program);
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+        let queued_admin_action = self
+            .queued_admin_action_account(&AdminAction::GrantRole { member, role })
+            .await;
 
-        // Check if verifier exists
-        let verifier = self
+        let signature = self
             .program
-            .account::<solana_zk::state::ZkvmVerifier>(verifier_account)
+            .request()
+            .accounts(accounts::GrantRole {
+                owner: self.program.payer(),
+                owner_role,
+                role_assignment: derive_role_pda(&member).0,
+                program_data,
+                audit_log: derive_audit_log_pda(&default_registry_address()).0,
+                system_program: system_program::ID,
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: self.admin_proposal_account(),
+                queued_admin_action,
+            })
+            .args(instruction::GrantRole { member, role })
+            .send()
             .await?;
 
-        // Check if verifier is frozen
-        if verifier.frozen {
-            return Err(Error::msg("ZKVM verifier is frozen"));
-        }
+        Ok(signature.to_string())
+    }
 
-        let instruction_data: Vec<u8> = match zkvm_selector {
-            ZkvmSelectorType::RiscZero => {
-                risc0_verify_instruction_data(&proof_data, program_vkey, output_digest)
-            }
-            ZkvmSelectorType::Succinct => {
-                sp1_groth16_verify_instruction_data(&proof_data, program_vkey, output_digest)
-            }
-        };
+    /// Revoke the role of `member`, immediately so that a compromised member can be removed
+    pub async fn revoke_role(&self, member: Pubkey) -> Result<String> {
+        // Ensure the payer holds the admin role
+        let owner_role = self.require_role(Role::Admin).await?;
+
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+        let queued_admin_action = self
+            .queued_admin_action_account(&AdminAction::RevokeRole { member })
+            .await;
 
         let signature = self
             .program
             .request()
-            .accounts(accounts::VerifyZkProof {
-                zkvm_verifier_account: verifier_account,
-                zkvm_verifier_program,
+            .accounts(accounts::RevokeRole {
+                owner: self.program.payer(),
+                owner_role,
+                role_assignment: derive_role_pda(&member).0,
+                program_data,
+                audit_log: derive_audit_log_pda(&default_registry_address()).0,
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: self.admin_proposal_account(),
+                queued_admin_action,
+            })
+            .args(instruction::RevokeRole { member })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Create the tenant registry `registry_id` administered by `admin`, once queued with
+    /// `queue_admin_action` and its timelock expired while the admin multisig is not initialized
+    pub async fn create_registry(&self, registry_id: u64, admin: Pubkey) -> Result<String> {
+        // Ensure the payer holds the admin role
+        let owner_role = self.require_role(Role::Admin).await?;
+
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+        let queued_admin_action = self
+            .queued_admin_action_account(&AdminAction::CreateRegistry { registry_id, admin })
+            .await;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::CreateRegistry {
+                owner: self.program.payer(),
+                owner_role,
+                audit_log: derive_audit_log_pda(&default_registry_address()).0,
+                registry_audit_log: derive_audit_log_pda(&derive_registry_pda(registry_id).0).0,
+                registry: derive_registry_pda(registry_id).0,
+                program_data,
                 system_program: system_program::ID,
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: self.admin_proposal_account(),
+                queued_admin_action,
             })
-            .args(instruction::VerifyZkvmProof {
-                _zkvm_selector: zkvm_selector_u64,
-                zk_verify_instruction_data: instruction_data,
+            .args(instruction::CreateRegistry { registry_id, admin })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Hand the registry the client is scoped to over to `admin`, signed by the current admin
+    pub async fn set_registry_admin(&self, admin: Pubkey) -> Result<String> {
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::SetRegistryAdmin {
+                admin: self.program.payer(),
+                audit_log: derive_audit_log_pda(&self.registry).0,
+                registry: self.registry,
             })
+            .args(instruction::SetRegistryAdmin { admin })
             .send()
             .await?;
 
         Ok(signature.to_string())
     }
 
-    /// Get the program instance
-    pub fn program(&self) -> &Program<C> {
-        &self.program
+    /// Appoint `guardian` to freeze verifiers and pause the tenant registry the client is scoped
+    /// to, signed by the registry admin. `Pubkey::default()` removes the guardian
+    pub async fn set_registry_guardian(&self, guardian: Pubkey) -> Result<String> {
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::SetRegistryAdmin {
+                admin: self.program.payer(),
+                audit_log: derive_audit_log_pda(&self.registry).0,
+                registry: self.registry,
+            })
+            .args(instruction::SetRegistryGuardian { guardian })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
     }
 
-    /// Get the counter pubkey
-    pub fn counter(&self) -> Pubkey {
-        self.counter
+    /// Pause the registry the client is scoped to, refusing every verification in it. The default
+    /// registry is paused by a guardian or multisig signer, a tenant registry by its admin or
+    /// guardian. Unpausing goes through the timelock
+    pub async fn pause_registry(&self) -> Result<String> {
+        // Guardians pass their role, multisig signers are checked against the multisig
+        let owner_role = self
+            .payer_role()
+            .await?
+            .map(|_| derive_role_pda(&self.program.payer()).0);
+
+        let signature = self
+            .program
+            .request()
+            .accounts(self.set_registry_paused_accounts(owner_role, None))
+            .args(instruction::PauseRegistry {})
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
     }
 
-    /// Get the payer pubkey
-    pub fn payer(&self) -> Pubkey {
-        self.program.payer()
+    /// Unpause the registry the client is scoped to, once queued with `queue_admin_action` and
+    /// its timelock expired while the admin multisig is not initialized
+    pub async fn unpause_registry(&self) -> Result<String> {
+        // Ensure the payer holds the admin role on the registry
+        let owner_role = self.require_registry_role(Role::Admin).await?;
+
+        let registry = self.registry_account().await?;
+        let queued_admin_action = self
+            .queued_admin_action_account(&AdminAction::UnpauseRegistry {
+                registry_id: registry.registry_id,
+            })
+            .await;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(self.set_registry_paused_accounts(owner_role, queued_admin_action))
+            .args(instruction::UnpauseRegistry {})
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
     }
 
-    /// Check if the current payer is the program's upgrade authority
-    pub async fn is_upgrade_authority(&self) -> Result<bool> {
+    // Build the accounts for pausing or unpausing the registry the client is scoped to
+    fn set_registry_paused_accounts(
+        &self,
+        owner_role: Option<Pubkey>,
+        queued_admin_action: Option<Pubkey>,
+    ) -> accounts::SetRegistryPaused {
         let (program_data, _) = Pubkey::find_program_address(
             &[ID.as_ref()],
             &solana_program::bpf_loader_upgradeable::ID,
         );
 
-        // Fetch the program data account
-        let program_data_account = self.program.rpc().get_account(&program_data).await?;
+        accounts::SetRegistryPaused {
+            owner: self.program.payer(),
+            owner_role,
+            audit_log: derive_audit_log_pda(&self.registry).0,
+            registry: self.registry,
+            program_data,
+            admin_multisig: derive_admin_multisig_pda().0,
+            admin_proposal: self.admin_proposal_account(),
+            queued_admin_action,
+        }
+    }
 
-        // Parse the upgrade authority from the account data
-        let data = &program_data_account.data;
-        if data[12] == 1 {
-            let mut authority = [0u8; 32];
-            authority.copy_from_slice(&data[13..45]);
-            let authority_pubkey = Pubkey::new_from_array(authority);
+    /// Revoke every result the verifier recorded so far and freeze it, e.g. after a soundness issue
+    /// is found
+    pub async fn revoke_zk_verifier_results(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+    ) -> Result<String> {
+        // Ensure the payer holds the admin role on the registry
+        let owner_role = self.require_registry_role(Role::Admin).await?;
+
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let (verifier_account, _bump) =
+            derive_zkvm_verifier_pda(&self.registry, zkvm_selector_u64, &zkvm_verifier_program);
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::UpdateZkvmVerifierConfig {
+                owner: self.program.payer(),
+                owner_role,
+                registry: self.registry,
+                zkvm_verifier_account: verifier_account,
+                program_data,
+                zkvm_verifier_program,
+                zkvm_verifier_program_data: derive_program_data_address(&zkvm_verifier_program),
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: self.admin_proposal_account(),
+                queued_admin_action: None,
+                audit_log: derive_audit_log_pda(&self.registry).0,
+            })
+            .args(instruction::RevokeZkVerifierResults {
+                zkvm_selector: zkvm_selector_u64,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Restrict a ZKVM verifier to the callers added with `add_allowed_caller`, or lift the restriction
+    pub async fn set_zk_verifier_permissioned(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        permissioned: bool,
+    ) -> Result<String> {
+        // Ensure the payer holds the operator role on the registry
+        let owner_role = self.require_registry_role(Role::Operator).await?;
+
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let (verifier_account, _bump) =
+            derive_zkvm_verifier_pda(&self.registry, zkvm_selector_u64, &zkvm_verifier_program);
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::UpdateZkvmVerifierConfig {
+                owner: self.program.payer(),
+                owner_role,
+                registry: self.registry,
+                zkvm_verifier_account: verifier_account,
+                program_data,
+                zkvm_verifier_program,
+                zkvm_verifier_program_data: derive_program_data_address(&zkvm_verifier_program),
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: None,
+                queued_admin_action: None,
+                audit_log: derive_audit_log_pda(&self.registry).0,
+            })
+            .args(instruction::SetZkVerifierPermissioned {
+                _zkvm_selector: zkvm_selector_u64,
+                permissioned,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Allow a consumer program or signer to verify through a permissioned ZKVM verifier. Programs
+    /// that are not the top-level instruction are allowed by their caller authority PDA, see
+    /// `solana_zk_consumer::caller_authority`
+    pub async fn add_allowed_caller(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        caller: Pubkey,
+    ) -> Result<String> {
+        // Ensure the payer holds the operator role on the registry
+        let owner_role = self.require_registry_role(Role::Operator).await?;
+
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let (verifier_account, _bump) =
+            derive_zkvm_verifier_pda(&self.registry, zkvm_selector_u64, &zkvm_verifier_program);
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::AddAllowedCaller {
+                owner: self.program.payer(),
+                owner_role,
+                registry: self.registry,
+                zkvm_verifier_account: verifier_account,
+                allowed_caller: derive_allowed_caller_pda(&verifier_account, &caller).0,
+                program_data,
+                audit_log: derive_audit_log_pda(&self.registry).0,
+                system_program: system_program::ID,
+            })
+            .args(instruction::AddAllowedCaller {
+                zkvm_selector: zkvm_selector_u64,
+                caller,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Remove a caller from the allowlist of a permissioned ZKVM verifier
+    pub async fn remove_allowed_caller(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        caller: Pubkey,
+    ) -> Result<String> {
+        // Ensure the payer holds the operator role on the registry
+        let owner_role = self.require_registry_role(Role::Operator).await?;
+
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let (verifier_account, _bump) =
+            derive_zkvm_verifier_pda(&self.registry, zkvm_selector_u64, &zkvm_verifier_program);
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::RemoveAllowedCaller {
+                owner: self.program.payer(),
+                owner_role,
+                registry: self.registry,
+                zkvm_verifier_account: verifier_account,
+                allowed_caller: derive_allowed_caller_pda(&verifier_account, &caller).0,
+                program_data,
+                audit_log: derive_audit_log_pda(&self.registry).0,
+            })
+            .args(instruction::RemoveAllowedCaller {
+                _zkvm_selector: zkvm_selector_u64,
+                _caller: caller,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Remove a ZKVM verifier program, its selector is not reused. It must be queued with
+    /// `queue_admin_action` first while the admin multisig is not initialized
+    pub async fn remove_zk_verifier_program(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+    ) -> Result<String> {
+        // Ensure the payer holds the admin role on the registry
+        let owner_role = self.require_registry_role(Role::Admin).await?;
+
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let queued_admin_action = self
+            .queued_admin_action_account(&AdminAction::RemoveZkVerifier {
+                zkvm_selector: zkvm_selector_u64,
+                zkvm_program_id: zkvm_verifier_program,
+            })
+            .await;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(remove_zk_verifier_accounts(
+                self.registry,
+                self.program.payer(),
+                owner_role,
+                zkvm_selector_u64,
+                zkvm_verifier_program,
+                self.admin_proposal_account(),
+                queued_admin_action,
+            ))
+            .args(instruction::RemoveZkVerifierProgram {
+                zkvm_selector: zkvm_selector_u64,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Store the canary proof of a selector: verify instruction data the verifier program must accept,
+    /// and optionally verify instruction data it must reject. It must be queued with
+    /// `queue_admin_action` first while the admin multisig is not initialized
+    pub async fn init_canary_proof(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        valid_instruction_data: Vec<u8>,
+        invalid_instruction_data: Option<Vec<u8>>,
+    ) -> Result<String> {
+        // Ensure the payer holds the admin role on the registry
+        let owner_role = self.require_registry_role(Role::Admin).await?;
+
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let invalid_instruction_data = invalid_instruction_data.unwrap_or_default();
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+        let queued_admin_action = self
+            .queued_admin_action_account(&AdminAction::SetCanaryProof {
+                zkvm_selector: zkvm_selector_u64,
+                canary_digest: CanaryProof::instruction_data_digest(
+                    &valid_instruction_data,
+                    &invalid_instruction_data,
+                ),
+            })
+            .await;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::InitCanaryProof {
+                owner: self.program.payer(),
+                owner_role,
+                audit_log: derive_audit_log_pda(&self.registry).0,
+                registry: self.registry,
+                canary_proof: derive_canary_proof_pda(&self.registry, zkvm_selector_u64).0,
+                program_data,
+                system_program: system_program::ID,
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: self.admin_proposal_account(),
+                queued_admin_action,
+            })
+            .args(instruction::InitCanaryProof {
+                zkvm_selector: zkvm_selector_u64,
+                valid_instruction_data,
+                invalid_instruction_data,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Replace the canary proof of a selector, once queued with `queue_admin_action` and its
+    /// timelock expired while the admin multisig is not initialized
+    pub async fn update_canary_proof(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        valid_instruction_data: Vec<u8>,
+        invalid_instruction_data: Option<Vec<u8>>,
+    ) -> Result<String> {
+        // Ensure the payer holds the admin role on the registry
+        let owner_role = self.require_registry_role(Role::Admin).await?;
+
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let invalid_instruction_data = invalid_instruction_data.unwrap_or_default();
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+        let queued_admin_action = self
+            .queued_admin_action_account(&AdminAction::SetCanaryProof {
+                zkvm_selector: zkvm_selector_u64,
+                canary_digest: CanaryProof::instruction_data_digest(
+                    &valid_instruction_data,
+                    &invalid_instruction_data,
+                ),
+            })
+            .await;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::UpdateCanaryProof {
+                owner: self.program.payer(),
+                owner_role,
+                audit_log: derive_audit_log_pda(&self.registry).0,
+                registry: self.registry,
+                canary_proof: derive_canary_proof_pda(&self.registry, zkvm_selector_u64).0,
+                program_data,
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: self.admin_proposal_account(),
+                queued_admin_action,
+            })
+            .args(instruction::UpdateCanaryProof {
+                zkvm_selector: zkvm_selector_u64,
+                valid_instruction_data,
+                invalid_instruction_data,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Fetch the canary proof of a selector
+    pub async fn canary_proof(&self, zkvm_selector: ZkvmSelectorType) -> Result<CanaryProof> {
+        let (canary_proof, _bump) = derive_canary_proof_pda(&self.registry, zkvm_selector.to_u64());
+        let account = self.program.account::<CanaryProof>(canary_proof).await?;
+
+        Ok(account)
+    }
+
+    /// Activate a pending ZKVM verifier program, which must accept the known-good canary proof.
+    /// The registry cannot observe a rejected proof, so the known-bad canary proof is only checked
+    /// here: it is simulated against the verifier program first and must fail
+    pub async fn activate_zk_verifier_program(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+    ) -> Result<String> {
+        // Ensure the payer holds the operator role on the registry
+        let owner_role = self.require_registry_role(Role::Operator).await?;
+
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let canary_proof = self.canary_proof(zkvm_selector).await?;
+        if !canary_proof.invalid_instruction_data.is_empty() {
+            let transaction = self
+                .program
+                .request()
+                .instruction(Instruction {
+                    program_id: zkvm_verifier_program,
+                    accounts: vec![AccountMeta::new_readonly(system_program::ID, false)],
+                    data: canary_proof.invalid_instruction_data,
+                })
+                .signed_transaction()
+                .await?;
+            let simulation = self.program.rpc().simulate_transaction(&transaction).await?;
+            if simulation.value.err.is_none() {
+                return Err(ZkError::CanaryProofAccepted.into());
+            }
+        }
+
+        let (verifier_account, _bump) =
+            derive_zkvm_verifier_pda(&self.registry, zkvm_selector_u64, &zkvm_verifier_program);
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::ActivateZkvmVerifier {
+                owner: self.program.payer(),
+                owner_role,
+                audit_log: derive_audit_log_pda(&self.registry).0,
+                registry: self.registry,
+                zkvm_verifier_account: verifier_account,
+                canary_proof: derive_canary_proof_pda(&self.registry, zkvm_selector_u64).0,
+                program_data,
+                zkvm_verifier_program,
+                zkvm_verifier_program_data: derive_program_data_address(&zkvm_verifier_program),
+                system_program: system_program::ID,
+            })
+            .args(instruction::ActivateZkVerifierProgram {
+                zkvm_selector: zkvm_selector_u64,
+            })
+            .send()
+            .await
+            .map_err(|e| {
+                SolanaZkClientError::from_verify_error(e, zkvm_selector, zkvm_verifier_program)
+            })?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Hand registry admin actions over to a multisig of `signers`, `threshold` of which must
+    /// approve each action. Approved actions execute after the delay of their `AdminActionKind`
+    pub async fn init_admin_multisig(
+        &self,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+        delay_slots: [u64; ADMIN_ACTION_KINDS],
+    ) -> Result<String> {
+        // Ensure the payer holds the admin role
+        let owner_role = self.require_role(Role::Admin).await?;
+
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::InitAdminMultisig {
+                owner: self.program.payer(),
+                owner_role,
+                admin_multisig: derive_admin_multisig_pda().0,
+                program_data,
+                audit_log: derive_audit_log_pda(&default_registry_address()).0,
+                system_program: system_program::ID,
+            })
+            .args(instruction::InitAdminMultisig {
+                signers,
+                threshold,
+                delay_slots,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Propose an admin action to the multisig, approved by the payer. Returns the proposal id
+    pub async fn create_admin_proposal(&self, action: AdminAction) -> Result<(String, u64)> {
+        let admin_multisig = self.admin_multisig().await?;
+        let proposal_id = admin_multisig.proposal_count;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::CreateAdminProposal {
+                proposer: self.program.payer(),
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: derive_admin_proposal_pda(proposal_id).0,
+                system_program: system_program::ID,
+            })
+            .args(instruction::CreateAdminProposal { action })
+            .send()
+            .await?;
+
+        Ok((signature.to_string(), proposal_id))
+    }
+
+    /// Approve an admin proposal as the payer
+    pub async fn approve_admin_proposal(&self, proposal_id: u64) -> Result<String> {
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::ApproveAdminProposal {
+                signer: self.program.payer(),
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: derive_admin_proposal_pda(proposal_id).0,
+            })
+            .args(instruction::ApproveAdminProposal {})
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Cancel an admin proposal before it executes, any multisig signer can cancel
+    pub async fn cancel_admin_proposal(&self, proposal_id: u64) -> Result<String> {
+        let admin_proposal = self.admin_proposal(proposal_id).await?;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::CancelAdminProposal {
+                signer: self.program.payer(),
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: derive_admin_proposal_pda(proposal_id).0,
+                proposer: admin_proposal.proposer,
+            })
+            .args(instruction::CancelAdminProposal {})
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Execute an admin proposal whose timelock expired, anyone can execute. A canary proof is
+    /// executed with its instruction data on a client `with_admin_proposal` instead
+    pub async fn execute_admin_proposal(&self, proposal_id: u64) -> Result<String> {
+        let admin_proposal = self.admin_proposal(proposal_id).await?;
+        if admin_proposal.eta_slot == 0 {
+            return Err(ZkError::ProposalNotApproved.into());
+        }
+        if self.program.rpc().get_slot().await? < admin_proposal.eta_slot {
+            return Err(ZkError::TimelockNotExpired.into());
+        }
+
+        // The admin multisig governs the default registry, tenant registries have their own admin
+        let registry = derive_registry_pda(DEFAULT_REGISTRY_ID).0;
+        let proposal = Some(derive_admin_proposal_pda(proposal_id).0);
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+        let update_accounts = |zkvm_selector: u64, zkvm_verifier_program: Pubkey| {
+            accounts::UpdateZkvmVerifierConfig {
+                owner: self.program.payer(),
+                owner_role: None,
+                registry,
+                zkvm_verifier_account: derive_zkvm_verifier_pda(
+                    &registry,
+                    zkvm_selector,
+                    &zkvm_verifier_program,
+                )
+                .0,
+                program_data,
+                zkvm_verifier_program,
+                zkvm_verifier_program_data: derive_program_data_address(&zkvm_verifier_program),
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: proposal,
+                queued_admin_action: None,
+                audit_log: derive_audit_log_pda(&registry).0,
+            }
+        };
+
+        let request = self.program.request();
+        let request = match admin_proposal.action {
+            AdminAction::AddZkVerifier {
+                zkvm_selector,
+                zkvm_program_id,
+                journal_digest,
+                dcap,
+            } => {
+                let (verifier_account, _bump) =
+                    derive_zkvm_verifier_pda(&registry, zkvm_selector, &zkvm_program_id);
+                request
+                    .accounts(accounts::AddZkvmVerifier {
+                        owner: self.program.payer(),
+                        owner_role: None,
+                        registry,
+                        zkvm_verifier_account: verifier_account,
+                        zkvm_verifier_stats: derive_zkvm_verifier_stats_pda(&verifier_account).0,
+                        program_data,
+                        zkvm_verifier_program: zkvm_program_id,
+                        zkvm_verifier_program_data: derive_program_data_address(&zkvm_program_id),
+                        admin_multisig: derive_admin_multisig_pda().0,
+                        admin_proposal: proposal,
+                        queued_admin_action: None,
+                        audit_log: derive_audit_log_pda(&registry).0,
+                        system_program: system_program::ID,
+                    })
+                    .args(instruction::AddZkVerifierProgram {
+                        zkvm_selector,
+                        journal_digest,
+                        dcap,
+                    })
+            }
+            AdminAction::UpdateZkVerifier {
+                zkvm_selector,
+                zkvm_program_id,
+            } => request
+                .accounts(update_accounts(zkvm_selector, zkvm_program_id))
+                .args(instruction::UpdateZkVerifierProgram { zkvm_selector }),
+            AdminAction::FreezeZkVerifier {
+                zkvm_selector,
+                zkvm_program_id,
+                freeze,
+            } => request
+                .accounts(update_accounts(zkvm_selector, zkvm_program_id))
+                .args(instruction::FreezeZkVerifierProgram {
+                    zkvm_selector,
+                    freeze,
+                }),
+            AdminAction::RemoveZkVerifier {
+                zkvm_selector,
+                zkvm_program_id,
+            } => request
+                .accounts(remove_zk_verifier_accounts(
+                    registry,
+                    self.program.payer(),
+                    None,
+                    zkvm_selector,
+                    zkvm_program_id,
+                    proposal,
+                    None,
+                ))
+                .args(instruction::RemoveZkVerifierProgram { zkvm_selector }),
+            AdminAction::UpdateAdminMultisig { .. } => request
+                .accounts(accounts::UpdateAdminMultisig {
+                    executor: self.program.payer(),
+                    admin_multisig: derive_admin_multisig_pda().0,
+                    admin_proposal: derive_admin_proposal_pda(proposal_id).0,
+                    audit_log: derive_audit_log_pda(&default_registry_address()).0,
+                })
+                .args(instruction::UpdateAdminMultisig {}),
+            AdminAction::SetTimelockDelay { .. } => request
+                .accounts(accounts::UpdateAdminMultisig {
+                    executor: self.program.payer(),
+                    admin_multisig: derive_admin_multisig_pda().0,
+                    admin_proposal: derive_admin_proposal_pda(proposal_id).0,
+                    audit_log: derive_audit_log_pda(&default_registry_address()).0,
+                })
+                .args(instruction::SetTimelockDelay {}),
+            AdminAction::GrantRole { member, role } => request
+                .accounts(accounts::GrantRole {
+                    owner: self.program.payer(),
+                    owner_role: None,
+                    role_assignment: derive_role_pda(&member).0,
+                    program_data,
+                    audit_log: derive_audit_log_pda(&default_registry_address()).0,
+                    system_program: system_program::ID,
+                    admin_multisig: derive_admin_multisig_pda().0,
+                    admin_proposal: proposal,
+                    queued_admin_action: None,
+                })
+                .args(instruction::GrantRole { member, role }),
+            AdminAction::RevokeRole { member } => request
+                .accounts(accounts::RevokeRole {
+                    owner: self.program.payer(),
+                    owner_role: None,
+                    role_assignment: derive_role_pda(&member).0,
+                    program_data,
+                    audit_log: derive_audit_log_pda(&default_registry_address()).0,
+                    admin_multisig: derive_admin_multisig_pda().0,
+                    admin_proposal: proposal,
+                    queued_admin_action: None,
+                })
+                .args(instruction::RevokeRole { member }),
+            AdminAction::CreateRegistry { registry_id, admin } => request
+                .accounts(accounts::CreateRegistry {
+                    owner: self.program.payer(),
+                    owner_role: None,
+                    audit_log: derive_audit_log_pda(&default_registry_address()).0,
+                    registry_audit_log: derive_audit_log_pda(&derive_registry_pda(registry_id).0).0,
+                    registry: derive_registry_pda(registry_id).0,
+                    program_data,
+                    system_program: system_program::ID,
+                    admin_multisig: derive_admin_multisig_pda().0,
+                    admin_proposal: proposal,
+                    queued_admin_action: None,
+                })
+                .args(instruction::CreateRegistry { registry_id, admin }),
+            AdminAction::RevokeZkVerifierResults {
+                zkvm_selector,
+                zkvm_program_id,
+            } => request
+                .accounts(update_accounts(zkvm_selector, zkvm_program_id))
+                .args(instruction::RevokeZkVerifierResults { zkvm_selector }),
+            AdminAction::DeprecateZkVerifier {
+                zkvm_selector,
+                zkvm_program_id,
+                sunset_slot,
+                successor_selector,
+                successor_program_id,
+            } => request
+                .accounts(update_accounts(zkvm_selector, zkvm_program_id))
+                .args(instruction::DeprecateZkVerifierProgram {
+                    zkvm_selector,
+                    sunset_slot,
+                    successor_selector,
+                    successor_program_id,
+                }),
+            AdminAction::SetCanaryProof { .. } => {
+                // The proposal only holds the digest of the canary proof, execute it with
+                // `init_canary_proof` or `update_canary_proof` on a client `with_admin_proposal`
+                return Err(ZkError::ProposalActionMismatch.into());
+            }
+            AdminAction::AddDcapGuest {
+                zkvm_selector,
+                guest_vkey,
+            } => request
+                .accounts(accounts::AddDcapGuest {
+                    owner: self.program.payer(),
+                    owner_role: None,
+                    registry,
+                    dcap_guest: derive_dcap_guest_pda(&registry, zkvm_selector, &guest_vkey).0,
+                    program_data,
+                    audit_log: derive_audit_log_pda(&registry).0,
+                    system_program: system_program::ID,
+                    admin_multisig: derive_admin_multisig_pda().0,
+                    admin_proposal: proposal,
+                    queued_admin_action: None,
+                })
+                .args(instruction::AddDcapGuest {
+                    zkvm_selector,
+                    guest_vkey,
+                }),
+            AdminAction::RemoveDcapGuest {
+                zkvm_selector,
+                guest_vkey,
+            } => request
+                .accounts(accounts::RemoveDcapGuest {
+                    owner: self.program.payer(),
+                    owner_role: None,
+                    registry,
+                    dcap_guest: derive_dcap_guest_pda(&registry, zkvm_selector, &guest_vkey).0,
+                    program_data,
+                    audit_log: derive_audit_log_pda(&registry).0,
+                    admin_multisig: derive_admin_multisig_pda().0,
+                    admin_proposal: proposal,
+                    queued_admin_action: None,
+                })
+                .args(instruction::RemoveDcapGuest {
+                    zkvm_selector,
+                    guest_vkey,
+                }),
+            AdminAction::SetDcapCollateralConfig {
+                root_ca_hash,
+                root_crl_hash,
+                pck_crl_hash,
+                tcb_evaluation_data_numbers,
+                max_collateral_age,
+            } => {
+                // The same action creates the collateral config or replaces it
+                if self.dcap_collateral_config().await.is_ok() {
+                    request
+                        .accounts(accounts::UpdateDcapCollateralConfig {
+                            owner: self.program.payer(),
+                            owner_role: None,
+                            dcap_collateral_config: derive_dcap_collateral_config_pda().0,
+                            program_data,
+                            audit_log: derive_audit_log_pda(&default_registry_address()).0,
+                            admin_multisig: derive_admin_multisig_pda().0,
+                            admin_proposal: proposal,
+                            queued_admin_action: None,
+                        })
+                        .args(instruction::UpdateDcapCollateralConfig {
+                            root_ca_hash,
+                            root_crl_hash,
+                            pck_crl_hash,
+                            tcb_evaluation_data_numbers,
+                            max_collateral_age,
+                        })
+                } else {
+                    request
+                        .accounts(accounts::InitDcapCollateralConfig {
+                            owner: self.program.payer(),
+                            owner_role: None,
+                            dcap_collateral_config: derive_dcap_collateral_config_pda().0,
+                            program_data,
+                            audit_log: derive_audit_log_pda(&default_registry_address()).0,
+                            system_program: system_program::ID,
+                            admin_multisig: derive_admin_multisig_pda().0,
+                            admin_proposal: proposal,
+                            queued_admin_action: None,
+                        })
+                        .args(instruction::InitDcapCollateralConfig {
+                            root_ca_hash,
+                            root_crl_hash,
+                            pck_crl_hash,
+                            tcb_evaluation_data_numbers,
+                            max_collateral_age,
+                        })
+                }
+            }
+            AdminAction::UnpauseRegistry { registry_id } => request
+                .accounts(accounts::SetRegistryPaused {
+                    owner: self.program.payer(),
+                    owner_role: None,
+                    audit_log: derive_audit_log_pda(&derive_registry_pda(registry_id).0).0,
+                    registry: derive_registry_pda(registry_id).0,
+                    program_data,
+                    admin_multisig: derive_admin_multisig_pda().0,
+                    admin_proposal: proposal,
+                    queued_admin_action: None,
+                })
+                .args(instruction::UnpauseRegistry {}),
+        };
+
+        let signature = request.send().await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Freeze a ZKVM verifier immediately, bypassing the timelock. Verifiers of the default registry
+    /// are frozen by a guardian or multisig signer, those of a tenant registry by its admin or
+    /// guardian. Unfreezing goes through the timelock
+    pub async fn emergency_freeze_zk_verifier(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+    ) -> Result<String> {
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        // Guardians pass their role, multisig signers are checked against the multisig
+        let owner_role = self
+            .payer_role()
+            .await?
+            .map(|_| derive_role_pda(&self.program.payer()).0);
+
+        let (verifier_account, _bump) =
+            derive_zkvm_verifier_pda(&self.registry, zkvm_selector_u64, &zkvm_verifier_program);
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::UpdateZkvmVerifierConfig {
+                owner: self.program.payer(),
+                owner_role,
+                registry: self.registry,
+                zkvm_verifier_account: verifier_account,
+                program_data,
+                zkvm_verifier_program,
+                zkvm_verifier_program_data: derive_program_data_address(&zkvm_verifier_program),
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: None,
+                queued_admin_action: None,
+                audit_log: derive_audit_log_pda(&self.registry).0,
+            })
+            .args(instruction::EmergencyFreezeZkVerifier {
+                _zkvm_selector: zkvm_selector_u64,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Fetch the admin multisig
+    pub async fn admin_multisig(&self) -> Result<AdminMultisig> {
+        let admin_multisig = self
+            .program
+            .account::<AdminMultisig>(derive_admin_multisig_pda().0)
+            .await?;
+
+        Ok(admin_multisig)
+    }
+
+    /// Fetch an admin proposal
+    pub async fn admin_proposal(&self, proposal_id: u64) -> Result<AdminProposal> {
+        let admin_proposal = self
+            .program
+            .account::<AdminProposal>(derive_admin_proposal_pda(proposal_id).0)
+            .await?;
+
+        Ok(admin_proposal)
+    }
+
+    /// Queue an admin action on the registry as a single admin, before the admin multisig is
+    /// initialized. It executes through its instruction, e.g. `add_zk_verifier_program`, once
+    /// `MIN_TIMELOCK_DELAY_SLOTS` have passed
+    pub async fn queue_admin_action(&self, action: AdminAction) -> Result<String> {
+        // Ensure the payer holds the admin role on the registry
+        let owner_role = self.require_registry_role(Role::Admin).await?;
+
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::QueueAdminAction {
+                owner: self.program.payer(),
+                owner_role,
+                registry: self.registry,
+                queued_admin_action: derive_queued_admin_action_pda(&self.registry, &action).0,
+                program_data,
+                admin_multisig: derive_admin_multisig_pda().0,
+                system_program: system_program::ID,
+            })
+            .args(instruction::QueueAdminAction { action })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Cancel an admin action queued on the registry before it executes
+    pub async fn cancel_queued_admin_action(&self, action: &AdminAction) -> Result<String> {
+        // Ensure the payer holds the admin role on the registry
+        let owner_role = self.require_registry_role(Role::Admin).await?;
+
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::CancelQueuedAdminAction {
+                owner: self.program.payer(),
+                owner_role,
+                registry: self.registry,
+                queued_admin_action: derive_queued_admin_action_pda(&self.registry, action).0,
+                program_data,
+            })
+            .args(instruction::CancelQueuedAdminAction {})
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Fetch the queued admin action of `action` on the registry
+    pub async fn queued_admin_action(&self, action: &AdminAction) -> Result<QueuedAdminAction> {
+        let queued_admin_action = self
+            .program
+            .account::<QueuedAdminAction>(derive_queued_admin_action_pda(&self.registry, action).0)
+            .await?;
+
+        Ok(queued_admin_action)
+    }
+
+    // Get the queued admin action account of `action` on the registry, if it was queued
+    async fn queued_admin_action_account(&self, action: &AdminAction) -> Option<Pubkey> {
+        self.queued_admin_action(action)
+            .await
+            .ok()
+            .map(|_| derive_queued_admin_action_pda(&self.registry, action).0)
+    }
+
+    /// Deprecate a ZKVM verifier program, keeping it usable until `sunset_slot`. It must be queued
+    /// with `queue_admin_action` first while the admin multisig is not initialized
+    pub async fn deprecate_zk_verifier_program(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        sunset_slot: u64,
+        successor_selector: ZkvmSelectorType,
+        overwrite_successor_pubkey: Option<Pubkey>,
+    ) -> Result<String> {
+        // Ensure the payer holds the admin role on the registry
+        let owner_role = self.require_registry_role(Role::Admin).await?;
+
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+        let successor_program_id = match overwrite_successor_pubkey {
+            Some(pubkey) => pubkey,
+            None => successor_selector.to_zkvm_verifier_id(),
+        };
+
+        let (verifier_account, _bump) =
+            derive_zkvm_verifier_pda(&self.registry, zkvm_selector_u64, &zkvm_verifier_program);
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+        let queued_admin_action = self
+            .queued_admin_action_account(&AdminAction::DeprecateZkVerifier {
+                zkvm_selector: zkvm_selector_u64,
+                zkvm_program_id: zkvm_verifier_program,
+                sunset_slot,
+                successor_selector: successor_selector.to_u64(),
+                successor_program_id,
+            })
+            .await;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::UpdateZkvmVerifierConfig {
+                owner: self.program.payer(),
+                owner_role,
+                registry: self.registry,
+                zkvm_verifier_account: verifier_account,
+                program_data,
+                zkvm_verifier_program,
+                zkvm_verifier_program_data: derive_program_data_address(&zkvm_verifier_program),
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: self.admin_proposal_account(),
+                queued_admin_action,
+                audit_log: derive_audit_log_pda(&self.registry).0,
+            })
+            .args(instruction::DeprecateZkVerifierProgram {
+                zkvm_selector: zkvm_selector_u64,
+                sunset_slot,
+                successor_selector: successor_selector.to_u64(),
+                successor_program_id,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Interface for verifying ZKVM proofs (to be implemented by user)
+    pub async fn verify_zkvm_proof(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        program_vkey: [u8; 32],
+        output_digest: [u8; 32],
+        proof_data: &[u8],
+    ) -> Result<String> {
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let verifier = self
+            .check_verifier_usable(zkvm_selector_u64, &zkvm_verifier_program)
+            .await?;
+
+        let instruction_data =
+            zkvm_selector.verify_instruction_data(proof_data, program_vkey, output_digest)?;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(verify_zk_proof_accounts_as_caller(
+                self.registry,
+                zkvm_selector_u64,
+                zkvm_verifier_program,
+                self.program.payer(),
+                verifier.permissioned,
+                self.accumulator_shard,
+            ))
+            .args(instruction::VerifyZkvmProof {
+                zkvm_selector: zkvm_selector_u64,
+                zk_verify_instruction_data: instruction_data,
+            })
+            .send()
+            .await
+            .map_err(|e| {
+                SolanaZkClientError::from_verify_error(e, zkvm_selector, zkvm_verifier_program)
+            })?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Verify a ZKVM proof and call back `callback_program` with the verified statement, signed by
+    /// the registry callback authority. `callback_data` starts with the 8 byte discriminator of the
+    /// callee instruction, the registry inserts the `VerificationResult` right after it, and
+    /// `callback_accounts` are forwarded after the callback authority.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn verify_zkvm_proof_and_call(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        program_vkey: [u8; 32],
+        output_digest: [u8; 32],
+        proof_data: &[u8],
+        callback_program: Pubkey,
+        callback_data: Vec<u8>,
+        callback_accounts: Vec<AccountMeta>,
+    ) -> Result<String> {
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let instruction_data =
+            zkvm_selector.verify_instruction_data(proof_data, program_vkey, output_digest)?;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::VerifyZkProofAndCall {
+                verify: verify_zk_proof_accounts(
+                    self.registry,
+                    zkvm_selector_u64,
+                    zkvm_verifier_program,
+                    self.accumulator_shard,
+                ),
+                callback_program,
+                callback_authority: derive_callback_authority_pda().0,
+            })
+            .accounts(callback_accounts)
+            .args(instruction::VerifyZkvmProofAndCall {
+                zkvm_selector: zkvm_selector_u64,
+                zk_verify_instruction_data: instruction_data,
+                callback_data,
+            })
+            .send()
+            .await
+            .map_err(|e| {
+                SolanaZkClientError::from_verify_error(e, zkvm_selector, zkvm_verifier_program)
+            })?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Verify a ZKVM proof of the raw journal (public values for SP1), the registry computes the
+    /// zkVM-specific output digest on-chain
+    pub async fn verify_zkvm_proof_with_journal(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        program_vkey: [u8; 32],
+        journal: &[u8],
+        proof_data: &[u8],
+    ) -> Result<String> {
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let verifier = self
+            .check_verifier_usable(zkvm_selector_u64, &zkvm_verifier_program)
+            .await?;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(verify_zk_proof_accounts_as_caller(
+                self.registry,
+                zkvm_selector_u64,
+                zkvm_verifier_program,
+                self.program.payer(),
+                verifier.permissioned,
+                self.accumulator_shard,
+            ))
+            .args(instruction::VerifyZkvmProofWithJournal {
+                zkvm_selector: zkvm_selector_u64,
+                zk_verify_instruction_prefix: zkvm_selector.verify_instruction_prefix(proof_data)?,
+                program_vkey,
+                journal: journal.to_vec(),
+            })
+            .send()
+            .await
+            .map_err(|e| {
+                SolanaZkClientError::from_verify_error(e, zkvm_selector, zkvm_verifier_program)
+            })?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Verify a ZKVM proof of the journal and store the journal in a verified journal account of
+    /// the payer, uploading it in chunks first when it does not fit in a single transaction
+    pub async fn store_verified_journal(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        program_vkey: [u8; 32],
+        buffer_id: u64,
+        journal: &[u8],
+        proof_data: &[u8],
+    ) -> Result<String> {
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+        let (verified_journal, _bump) = derive_verified_journal_pda(&self.program.payer(), buffer_id);
+        let zk_verify_instruction_prefix = zkvm_selector.verify_instruction_prefix(proof_data)?;
+
+        let request = self.program.request();
+        let request = if journal.len() <= JOURNAL_CHUNK_LEN {
+            request
+                .accounts(accounts::StoreVerifiedJournal {
+                    verify: verify_zk_proof_accounts(
+                        self.registry,
+                        zkvm_selector_u64,
+                        zkvm_verifier_program,
+                        self.accumulator_shard,
+                    ),
+                    authority: self.program.payer(),
+                    verified_journal,
+                    system_program: system_program::ID,
+                })
+                .args(instruction::StoreVerifiedJournal {
+                    zkvm_selector: zkvm_selector_u64,
+                    zk_verify_instruction_prefix,
+                    program_vkey,
+                    journal: journal.to_vec(),
+                    buffer_id,
+                })
+        } else {
+            self.upload_journal_buffer(buffer_id, journal).await?;
+            request
+                .accounts(accounts::VerifyJournalBuffer {
+                    verify: verify_zk_proof_accounts(
+                        self.registry,
+                        zkvm_selector_u64,
+                        zkvm_verifier_program,
+                        self.accumulator_shard,
+                    ),
+                    authority: self.program.payer(),
+                    verified_journal,
+                })
+                .args(instruction::VerifyJournalBuffer {
+                    zkvm_selector: zkvm_selector_u64,
+                    zk_verify_instruction_prefix,
+                    program_vkey,
+                })
+        };
+
+        let signature = request.send().await.map_err(|e| {
+            SolanaZkClientError::from_verify_error(e, zkvm_selector, zkvm_verifier_program)
+        })?;
+
+        Ok(signature.to_string())
+    }
+
+    // Create a journal buffer of the payer and write the journal into it in chunks
+    async fn upload_journal_buffer(&self, buffer_id: u64, journal: &[u8]) -> Result<()> {
+        let (verified_journal, _bump) = derive_verified_journal_pda(&self.program.payer(), buffer_id);
+
+        self.program
+            .request()
+            .accounts(accounts::InitJournalBuffer {
+                authority: self.program.payer(),
+                verified_journal,
+                system_program: system_program::ID,
+            })
+            .args(instruction::InitJournalBuffer {
+                buffer_id,
+                journal_len: journal.len() as u32,
+            })
+            .send()
+            .await?;
+
+        for (i, chunk) in journal.chunks(JOURNAL_CHUNK_LEN).enumerate() {
+            self.program
+                .request()
+                .accounts(accounts::WriteJournalBuffer {
+                    authority: self.program.payer(),
+                    verified_journal,
+                })
+                .args(instruction::WriteJournalBuffer {
+                    offset: (i * JOURNAL_CHUNK_LEN) as u32,
+                    bytes: chunk.to_vec(),
+                })
+                .send()
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Close a verified journal account of the payer, reclaiming its rent
+    pub async fn close_verified_journal(&self, buffer_id: u64) -> Result<String> {
+        let (verified_journal, _bump) = derive_verified_journal_pda(&self.program.payer(), buffer_id);
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::CloseVerifiedJournal {
+                authority: self.program.payer(),
+                verified_journal,
+            })
+            .args(instruction::CloseVerifiedJournal {})
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Fetch a verified journal account
+    pub async fn verified_journal(&self, authority: &Pubkey, buffer_id: u64) -> Result<VerifiedJournal> {
+        let (verified_journal, _bump) = derive_verified_journal_pda(authority, buffer_id);
+        let account = self.program.account::<VerifiedJournal>(verified_journal).await?;
+
+        Ok(account)
+    }
+
+    /// Check that `value` is the field at `index` of a Merkleized journal verified for the
+    /// registry, selector and vkey, see `merkle::JournalTree` to build the proof
+    pub async fn prove_journal_field(
+        &self,
+        verified_journal: Pubkey,
+        zkvm_selector: ZkvmSelectorType,
+        program_vkey: [u8; 32],
+        index: u32,
+        value: Vec<u8>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<String> {
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::ProveJournalField { verified_journal })
+            .args(instruction::ProveJournalField {
+                registry: self.registry,
+                zkvm_selector: zkvm_selector.to_u64(),
+                program_vkey,
+                index,
+                value,
+                proof,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Build a freshness envelope bound to the most recent slot hash and the payer as caller,
+    /// for the guest program to prefix to its journal
+    pub async fn freshness_envelope(
+        &self,
+        consumer_program: Option<Pubkey>,
+        nonce: [u8; 32],
+    ) -> Result<FreshnessEnvelope> {
+        let slot_hashes = self
+            .program
+            .rpc()
+            .get_account_data(&sysvar::slot_hashes::ID)
+            .await?;
+        let (slot, slot_hash) = slot_hashes_head(&slot_hashes)
+            .ok_or(SolanaZkClientError::Registry(ZkError::StaleProof))?;
+
+        Ok(FreshnessEnvelope {
+            slot,
+            slot_hash,
+            consumer_program: consumer_program.unwrap_or_default(),
+            caller: self.program.payer(),
+            nonce,
+        })
+    }
+
+    /// Verify a ZKVM proof whose journal starts with a freshness envelope, signed by the payer
+    /// as the caller bound in the envelope
+    pub async fn verify_fresh_zkvm_proof(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        program_vkey: [u8; 32],
+        journal: &[u8],
+        proof_data: &[u8],
+    ) -> Result<String> {
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let envelope = FreshnessEnvelope::decode(journal)
+            .map_err(|_| SolanaZkClientError::Registry(ZkError::MalformedFreshnessEnvelope))?;
+        let output_digest = zkvm_selector.journal_digest().digest(journal);
+        let instruction_data =
+            zkvm_selector.verify_instruction_data(proof_data, program_vkey, output_digest)?;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::VerifyFreshZkProof {
+                verify: verify_zk_proof_accounts(
+                    self.registry,
+                    zkvm_selector_u64,
+                    zkvm_verifier_program,
+                    self.accumulator_shard,
+                ),
+                caller: self.program.payer(),
+                proof_nonce: derive_proof_nonce_pda(&self.program.payer(), &envelope.nonce).0,
+                slot_hashes: sysvar::slot_hashes::ID,
+                instructions_sysvar: sysvar::instructions::ID,
+                system_program: system_program::ID,
+            })
+            .args(instruction::VerifyFreshZkvmProof {
+                zkvm_selector: zkvm_selector_u64,
+                zk_verify_instruction_data: instruction_data,
+                journal: journal.to_vec(),
+                nonce: envelope.nonce,
+            })
+            .send()
+            .await
+            .map_err(|e| {
+                SolanaZkClientError::from_verify_error(e, zkvm_selector, zkvm_verifier_program)
+            })?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Close the nonce account of a fresh proof signed by the payer, returning its rent once the
+    /// proof is too old to be replayed
+    pub async fn close_proof_nonce(&self, nonce: [u8; 32]) -> Result<String> {
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::CloseProofNonce {
+                caller: self.program.payer(),
+                proof_nonce: derive_proof_nonce_pda(&self.program.payer(), &nonce).0,
+            })
+            .args(instruction::CloseProofNonce { _nonce: nonce })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Build the registry `verify_zkvm_proof` instructions to place ahead of a consumer program
+    /// instruction in the same transaction. The consumer confirms the proof through the
+    /// instructions sysvar (see `solana-zk-consumer`) rather than verifying it through CPI.
+    pub fn verify_zkvm_proof_instructions(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        program_vkey: [u8; 32],
+        output_digest: [u8; 32],
+        proof_data: &[u8],
+    ) -> Result<Vec<Instruction>> {
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let instruction_data =
+            zkvm_selector.verify_instruction_data(proof_data, program_vkey, output_digest)?;
+
+        let instructions = self
+            .program
+            .request()
+            .accounts(verify_zk_proof_accounts(
+                self.registry,
+                zkvm_selector_u64,
+                zkvm_verifier_program,
+                self.accumulator_shard,
+            ))
+            .args(instruction::VerifyZkvmProof {
+                zkvm_selector: zkvm_selector_u64,
+                zk_verify_instruction_data: instruction_data,
+            })
+            .instructions()?;
+
+        Ok(instructions)
+    }
+
+    /// Approve an Automata DCAP guest program for a zkVM selector, once queued with
+    /// `queue_admin_action` and its timelock expired while the admin multisig is not initialized
+    pub async fn add_dcap_guest(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        guest_vkey: [u8; 32],
+    ) -> Result<String> {
+        // Ensure the payer holds the admin role on the registry
+        let owner_role = self.require_registry_role(Role::Admin).await?;
+
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let (dcap_guest, _bump) =
+            derive_dcap_guest_pda(&self.registry, zkvm_selector_u64, &guest_vkey);
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+        let queued_admin_action = self
+            .queued_admin_action_account(&AdminAction::AddDcapGuest {
+                zkvm_selector: zkvm_selector_u64,
+                guest_vkey,
+            })
+            .await;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::AddDcapGuest {
+                owner: self.program.payer(),
+                owner_role,
+                registry: self.registry,
+                dcap_guest,
+                program_data,
+                audit_log: derive_audit_log_pda(&self.registry).0,
+                system_program: system_program::ID,
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: self.admin_proposal_account(),
+                queued_admin_action,
+            })
+            .args(instruction::AddDcapGuest {
+                zkvm_selector: zkvm_selector_u64,
+                guest_vkey,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Revoke the approval of an Automata DCAP guest program. It must be queued with
+    /// `queue_admin_action` first while the admin multisig is not initialized
+    pub async fn remove_dcap_guest(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        guest_vkey: [u8; 32],
+    ) -> Result<String> {
+        // Ensure the payer holds the admin role on the registry
+        let owner_role = self.require_registry_role(Role::Admin).await?;
+
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let (dcap_guest, _bump) =
+            derive_dcap_guest_pda(&self.registry, zkvm_selector_u64, &guest_vkey);
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+        let queued_admin_action = self
+            .queued_admin_action_account(&AdminAction::RemoveDcapGuest {
+                zkvm_selector: zkvm_selector_u64,
+                guest_vkey,
+            })
+            .await;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::RemoveDcapGuest {
+                owner: self.program.payer(),
+                owner_role,
+                registry: self.registry,
+                dcap_guest,
+                program_data,
+                audit_log: derive_audit_log_pda(&self.registry).0,
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: self.admin_proposal_account(),
+                queued_admin_action,
+            })
+            .args(instruction::RemoveDcapGuest {
+                zkvm_selector: zkvm_selector_u64,
+                guest_vkey,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Verify a ZK proof of DCAP quote verification produced by the Automata DCAP guest program.
+    /// The registry refuses the proof unless `guest_vkey` is approved for the selector
+    pub async fn verify_dcap_quote(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        guest_vkey: [u8; 32],
+        quote: &[u8],
+        journal: &[u8],
+        proof_data: &[u8],
+    ) -> Result<String> {
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        // Fail early if the guest program is not approved in the registry
+        let (dcap_guest, _bump) =
+            derive_dcap_guest_pda(&self.registry, zkvm_selector_u64, &guest_vkey);
+        if self
+            .program
+            .account::<DcapGuest>(dcap_guest)
+            .await
+            .is_err()
+        {
+            return Err(DcapError::GuestNotApproved.into());
+        }
+
+        // Ensure the proof attests to this quote
+        check_quote_matches_journal(quote, journal)?;
+
+        let verifier = self
+            .check_verifier_usable(zkvm_selector_u64, &zkvm_verifier_program)
+            .await?;
+
+        let output_digest = zkvm_selector.journal_digest().digest(journal);
+        let instruction_data =
+            zkvm_selector.verify_instruction_data(proof_data, guest_vkey, output_digest)?;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::VerifyDcapGuestProof {
+                verify: verify_zk_proof_accounts_as_caller(
+                    self.registry,
+                    zkvm_selector_u64,
+                    zkvm_verifier_program,
+                    self.program.payer(),
+                    verifier.permissioned,
+                    self.accumulator_shard,
+                ),
+                dcap_guest,
+            })
+            .args(instruction::VerifyDcapGuestProof {
+                zkvm_selector: zkvm_selector_u64,
+                zk_verify_instruction_data: instruction_data,
+                guest_vkey,
+            })
+            .send()
+            .await
+            .map_err(|e| {
+                SolanaZkClientError::from_verify_error(e, zkvm_selector, zkvm_verifier_program)
+            })?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Create the DCAP collateral config that DCAP journals are checked against, once queued with
+    /// `queue_admin_action` on the default registry while the admin multisig is not initialized
+    pub async fn init_dcap_collateral_config(
+        &self,
+        root_ca_hash: [u8; 32],
+        root_crl_hash: [u8; 32],
+        pck_crl_hash: [u8; 32],
+        tcb_evaluation_data_numbers: Vec<u32>,
+        max_collateral_age: u64,
+    ) -> Result<String> {
+        // Ensure the payer holds the admin role
+        let owner_role = self.require_role(Role::Admin).await?;
+
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+        let queued_admin_action = self
+            .queued_admin_action_account(&AdminAction::SetDcapCollateralConfig {
+                root_ca_hash,
+                root_crl_hash,
+                pck_crl_hash,
+                tcb_evaluation_data_numbers: tcb_evaluation_data_numbers.clone(),
+                max_collateral_age,
+            })
+            .await;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::InitDcapCollateralConfig {
+                owner: self.program.payer(),
+                owner_role,
+                dcap_collateral_config: derive_dcap_collateral_config_pda().0,
+                program_data,
+                audit_log: derive_audit_log_pda(&default_registry_address()).0,
+                system_program: system_program::ID,
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: self.admin_proposal_account(),
+                queued_admin_action,
+            })
+            .args(instruction::InitDcapCollateralConfig {
+                root_ca_hash,
+                root_crl_hash,
+                pck_crl_hash,
+                tcb_evaluation_data_numbers,
+                max_collateral_age,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Update the DCAP collateral config, e.g. after an Intel TCB recovery event. It must be queued
+    /// with `queue_admin_action` on the default registry first while the admin multisig is not
+    /// initialized
+    pub async fn update_dcap_collateral_config(
+        &self,
+        root_ca_hash: [u8; 32],
+        root_crl_hash: [u8; 32],
+        pck_crl_hash: [u8; 32],
+        tcb_evaluation_data_numbers: Vec<u32>,
+        max_collateral_age: u64,
+    ) -> Result<String> {
+        // Ensure the payer holds the admin role
+        let owner_role = self.require_role(Role::Admin).await?;
+
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+        let queued_admin_action = self
+            .queued_admin_action_account(&AdminAction::SetDcapCollateralConfig {
+                root_ca_hash,
+                root_crl_hash,
+                pck_crl_hash,
+                tcb_evaluation_data_numbers: tcb_evaluation_data_numbers.clone(),
+                max_collateral_age,
+            })
+            .await;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::UpdateDcapCollateralConfig {
+                owner: self.program.payer(),
+                owner_role,
+                dcap_collateral_config: derive_dcap_collateral_config_pda().0,
+                program_data,
+                audit_log: derive_audit_log_pda(&default_registry_address()).0,
+                admin_multisig: derive_admin_multisig_pda().0,
+                admin_proposal: self.admin_proposal_account(),
+                queued_admin_action,
+            })
+            .args(instruction::UpdateDcapCollateralConfig {
+                root_ca_hash,
+                root_crl_hash,
+                pck_crl_hash,
+                tcb_evaluation_data_numbers,
+                max_collateral_age,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Fetch the DCAP collateral config
+    pub async fn dcap_collateral_config(&self) -> Result<DcapCollateralConfig> {
+        let config = self
+            .program
+            .account::<DcapCollateralConfig>(derive_dcap_collateral_config_pda().0)
+            .await?;
+
+        Ok(config)
+    }
+
+    /// Create a DCAP policy owned by the payer
+    pub async fn init_dcap_policy(
+        &self,
+        policy_id: u64,
+        allowed_tcb_statuses: u8,
+        min_tcb_evaluation_data_number: u32,
+        measurements: Vec<[u8; 48]>,
+        enclave_key_validity: u64,
+    ) -> Result<String> {
+        let (dcap_policy, _bump) = derive_dcap_policy_pda(&self.program.payer(), policy_id);
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::InitDcapPolicy {
+                authority: self.program.payer(),
+                dcap_policy,
+                system_program: system_program::ID,
+            })
+            .args(instruction::InitDcapPolicy {
+                policy_id,
+                allowed_tcb_statuses,
+                min_tcb_evaluation_data_number,
+                measurements,
+                enclave_key_validity,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Update a DCAP policy owned by the payer
+    pub async fn update_dcap_policy(
+        &self,
+        policy_id: u64,
+        allowed_tcb_statuses: u8,
+        min_tcb_evaluation_data_number: u32,
+        measurements: Vec<[u8; 48]>,
+        enclave_key_validity: u64,
+    ) -> Result<String> {
+        let (dcap_policy, _bump) = derive_dcap_policy_pda(&self.program.payer(), policy_id);
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::UpdateDcapPolicy {
+                authority: self.program.payer(),
+                dcap_policy,
+            })
+            .args(instruction::UpdateDcapPolicy {
+                allowed_tcb_statuses,
+                min_tcb_evaluation_data_number,
+                measurements,
+                enclave_key_validity,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Verify a DCAP proof on-chain, decoding the journal and enforcing the given DCAP policy. The
+    /// journal does not fit in the transaction, it is uploaded to the journal buffer `buffer_id`
+    /// first, which holds the verified journal afterwards
+    #[allow(clippy::too_many_arguments)]
+    pub async fn verify_dcap_quote_with_policy(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        dcap_policy: Pubkey,
+        guest_vkey: [u8; 32],
+        buffer_id: u64,
+        journal: &[u8],
+        proof_data: &[u8],
+    ) -> Result<String> {
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+        let zk_verify_instruction_prefix = zkvm_selector.verify_instruction_prefix(proof_data)?;
+
+        self.upload_journal_buffer(buffer_id, journal).await?;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(verify_dcap_proof_accounts(
+                self.registry,
+                zkvm_selector_u64,
+                zkvm_verifier_program,
+                dcap_policy,
+                &guest_vkey,
+                self.program.payer(),
+                buffer_id,
+                self.accumulator_shard,
+            ))
+            .args(instruction::VerifyDcapProof {
+                zkvm_selector: zkvm_selector_u64,
+                zk_verify_instruction_prefix,
+                guest_vkey,
+            })
+            .send()
+            .await
+            .map_err(|e| {
+                SolanaZkClientError::from_verify_error(e, zkvm_selector, zkvm_verifier_program)
+            })?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Verify a DCAP proof under a DCAP policy and register the enclave key committed in its report
+    /// data, uploading the journal to the journal buffer `buffer_id` first
+    #[allow(clippy::too_many_arguments)]
+    pub async fn register_enclave_key(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        dcap_policy: Pubkey,
+        guest_vkey: [u8; 32],
+        buffer_id: u64,
+        journal: &[u8],
+        proof_data: &[u8],
+        key_type: EnclaveKeyType,
+        key: [u8; 32],
+    ) -> Result<String> {
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+        let instructions = self.register_enclave_key_instructions(
+            zkvm_selector,
+            overwrite_zkvm_verifier_pubkey,
+            dcap_policy,
+            guest_vkey,
+            buffer_id,
+            proof_data,
+            key_type,
+            key,
+        )?;
+
+        self.upload_journal_buffer(buffer_id, journal).await?;
+
+        let signature = instructions
+            .into_iter()
+            .fold(self.program.request(), |request, instruction| {
+                request.instruction(instruction)
+            })
+            .send()
+            .await
+            .map_err(|e| {
+                SolanaZkClientError::from_verify_error(e, zkvm_selector, zkvm_verifier_program)
+            })?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Build the registry `register_enclave_key` instruction for a journal already uploaded to the
+    /// journal buffer `buffer_id`
+    #[allow(clippy::too_many_arguments)]
+    pub fn register_enclave_key_instructions(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        dcap_policy: Pubkey,
+        guest_vkey: [u8; 32],
+        buffer_id: u64,
+        proof_data: &[u8],
+        key_type: EnclaveKeyType,
+        key: [u8; 32],
+    ) -> Result<Vec<Instruction>> {
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+        let zk_verify_instruction_prefix = zkvm_selector.verify_instruction_prefix(proof_data)?;
+
+        let instructions = self
+            .program
+            .request()
+            .accounts(accounts::RegisterEnclaveKey {
+                payer: self.program.payer(),
+                dcap: verify_dcap_proof_accounts(
+                    self.registry,
+                    zkvm_selector_u64,
+                    zkvm_verifier_program,
+                    dcap_policy,
+                    &guest_vkey,
+                    self.program.payer(),
+                    buffer_id,
+                    self.accumulator_shard,
+                ),
+                enclave_key: derive_enclave_key_pda(&dcap_policy, key_type, &key).0,
+                system_program: system_program::ID,
+            })
+            .args(instruction::RegisterEnclaveKey {
+                zkvm_selector: zkvm_selector_u64,
+                zk_verify_instruction_prefix,
+                guest_vkey,
+                key_type,
+                key,
+            })
+            .instructions()?;
+
+        Ok(instructions)
+    }
+
+    /// Close an expired enclave key, returning the rent to whoever registered it
+    pub async fn close_enclave_key(&self, enclave_key: Pubkey) -> Result<String> {
+        let registered = self.program.account::<EnclaveKey>(enclave_key).await?;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::CloseEnclaveKey {
+                enclave_key,
+                payer: registered.payer,
+            })
+            .args(instruction::CloseEnclaveKey {})
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Fetch a registered enclave key
+    pub async fn enclave_key(
+        &self,
+        dcap_policy: &Pubkey,
+        key_type: EnclaveKeyType,
+        key: &[u8; 32],
+    ) -> Result<EnclaveKey> {
+        let (enclave_key, _bump) = derive_enclave_key_pda(dcap_policy, key_type, key);
+        let registered = self.program.account::<EnclaveKey>(enclave_key).await?;
+
+        Ok(registered)
+    }
+
+    /// Create a hybrid TEE-plus-ZK policy owned by the payer
+    pub async fn init_hybrid_policy(
+        &self,
+        policy_id: u64,
+        mode: HybridMode,
+        zkvm_selector: ZkvmSelectorType,
+        program_vkey: [u8; 32],
+        dcap_policy: Pubkey,
+    ) -> Result<String> {
+        let (hybrid_policy, _bump) = derive_hybrid_policy_pda(&self.program.payer(), policy_id);
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::InitHybridPolicy {
+                authority: self.program.payer(),
+                hybrid_policy,
+                system_program: system_program::ID,
+            })
+            .args(instruction::InitHybridPolicy {
+                policy_id,
+                mode,
+                registry: self.registry,
+                zkvm_selector: zkvm_selector.to_u64(),
+                program_vkey,
+                dcap_policy,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Update a hybrid TEE-plus-ZK policy owned by the payer
+    pub async fn update_hybrid_policy(
+        &self,
+        policy_id: u64,
+        mode: HybridMode,
+        zkvm_selector: ZkvmSelectorType,
+        program_vkey: [u8; 32],
+        dcap_policy: Pubkey,
+    ) -> Result<String> {
+        let (hybrid_policy, _bump) = derive_hybrid_policy_pda(&self.program.payer(), policy_id);
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::UpdateHybridPolicy {
+                authority: self.program.payer(),
+                hybrid_policy,
+            })
+            .args(instruction::UpdateHybridPolicy {
+                mode,
+                registry: self.registry,
+                zkvm_selector: zkvm_selector.to_u64(),
+                program_vkey,
+                dcap_policy,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Evaluate a hybrid policy for `output_digest`, with a ZK proof of the policy's program and/or
+    /// an enclave signature. The enclave signature is given as the registered enclave key account
+    /// and the Ed25519 or secp256k1 instruction verifying its signature of `output_digest`. Without
+    /// a proof, only the enclave key and signature are sent.
+    pub async fn verify_hybrid(
+        &self,
+        hybrid_policy: Pubkey,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        output_digest: [u8; 32],
+        proof_data: Option<&[u8]>,
+        enclave_signature: Option<(Pubkey, Instruction)>,
+    ) -> Result<String> {
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let policy = self.program.account::<HybridPolicy>(hybrid_policy).await?;
+        if policy.zkvm_selector != zkvm_selector_u64 {
+            return Err(ZkError::ZkvmSelectorMismatch.into());
+        }
+
+        let (enclave_key, signature_instruction) = enclave_signature.unzip();
+        let mut request = self.program.request();
+        if let Some(signature_instruction) = signature_instruction {
+            request = request.instruction(signature_instruction);
+        }
+
+        let request = match (proof_data, enclave_key) {
+            (Some(proof_data), enclave_key) => request
+                .accounts(accounts::VerifyHybrid {
+                    hybrid_policy,
+                    verify: verify_zk_proof_accounts(
+                        policy.registry,
+                        zkvm_selector_u64,
+                        zkvm_verifier_program,
+                        self.accumulator_shard,
+                    ),
+                    enclave_key,
+                    instructions_sysvar: sysvar::instructions::ID,
+                })
+                .args(instruction::VerifyHybrid {
+                    zkvm_selector: zkvm_selector_u64,
+                    zk_verify_instruction_data: zkvm_selector.verify_instruction_data(
+                        proof_data,
+                        policy.program_vkey,
+                        output_digest,
+                    )?,
+                    output_digest,
+                }),
+            (None, Some(enclave_key)) => request
+                .accounts(accounts::VerifyHybridEnclave {
+                    hybrid_policy,
+                    registry: policy.registry,
+                    enclave_key,
+                    instructions_sysvar: sysvar::instructions::ID,
+                })
+                .args(instruction::VerifyHybridEnclave { output_digest }),
+            (None, None) => return Err(ZkError::HybridPolicyNotSatisfied.into()),
+        };
+
+        let signature = request.send().await.map_err(|e| {
+            SolanaZkClientError::from_verify_error(e, zkvm_selector, zkvm_verifier_program)
+        })?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Fetch a hybrid TEE-plus-ZK policy
+    pub async fn hybrid_policy(&self, authority: &Pubkey, policy_id: u64) -> Result<HybridPolicy> {
+        let (hybrid_policy, _bump) = derive_hybrid_policy_pda(authority, policy_id);
+        let policy = self.program.account::<HybridPolicy>(hybrid_policy).await?;
+
+        Ok(policy)
+    }
+
+    /// Create a verified state owned by the payer, advanced only by proofs of `guest_vkey`
+    pub async fn init_verified_state(
+        &self,
+        state_id: u64,
+        zkvm_selector: ZkvmSelectorType,
+        guest_vkey: [u8; 32],
+        initial_root: [u8; 32],
+    ) -> Result<String> {
+        let (verified_state, _bump) = derive_verified_state_pda(&self.program.payer(), state_id);
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::InitVerifiedState {
+                owner: self.program.payer(),
+                verified_state,
+                system_program: system_program::ID,
+            })
+            .args(instruction::InitVerifiedState {
+                state_id,
+                registry: self.registry,
+                zkvm_selector: zkvm_selector.to_u64(),
+                guest_vkey,
+                initial_root,
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Advance a verified state with a proof whose journal commits to the state owner and id, the
+    /// old root, the new root and the next sequence number
+    pub async fn advance_state(
+        &self,
+        verified_state: Pubkey,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+        journal: &[u8],
+        proof_data: &[u8],
+    ) -> Result<String> {
+        let state = self.program.account::<VerifiedState>(verified_state).await?;
+        let zkvm_selector = ZkvmSelectorType::from_u64(state.zkvm_selector)
+            .ok_or(SolanaZkClientError::Registry(ZkError::InvalidZkvmSelector))?;
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let output_digest = zkvm_selector.journal_digest().digest(journal);
+        let instruction_data =
+            zkvm_selector.verify_instruction_data(proof_data, state.guest_vkey, output_digest)?;
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::AdvanceState {
+                verify: verify_zk_proof_accounts(
+                    state.registry,
+                    state.zkvm_selector,
+                    zkvm_verifier_program,
+                    self.accumulator_shard,
+                ),
+                verified_state,
+            })
+            .args(instruction::AdvanceState {
+                zkvm_selector: state.zkvm_selector,
+                zk_verify_instruction_data: instruction_data,
+                journal: journal.to_vec(),
+            })
+            .send()
+            .await
+            .map_err(|e| {
+                SolanaZkClientError::from_verify_error(e, zkvm_selector, zkvm_verifier_program)
+            })?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Fetch a verified state
+    pub async fn verified_state(&self, owner: &Pubkey, state_id: u64) -> Result<VerifiedState> {
+        let (verified_state, _bump) = derive_verified_state_pda(owner, state_id);
+        let state = self.program.account::<VerifiedState>(verified_state).await?;
+
+        Ok(state)
+    }
+
+    // Refuse frozen, sunset or disallowed verifiers before sending, and warn about deprecated ones
+    async fn check_verifier_usable(
+        &self,
+        zkvm_selector: u64,
+        zkvm_verifier_program: &Pubkey,
+    ) -> Result<ZkvmVerifier> {
+        let (verifier_account, _bump) =
+            derive_zkvm_verifier_pda(&self.registry, zkvm_selector, zkvm_verifier_program);
+
+        // Check if verifier exists
+        let verifier = self
+            .program
+            .account::<ZkvmVerifier>(verifier_account)
+            .await?;
+
+        // Check if the registry is paused, or the verifier frozen or not activated yet
+        if self.registry_account().await?.paused {
+            return Err(ZkError::RegistryPaused.into());
+        }
+        if verifier.frozen {
+            return Err(ZkError::ZkvmProgramFrozen.into());
+        }
+        if verifier.pending {
+            return Err(ZkError::ZkvmProgramPending.into());
+        }
+
+        // Warn if the verifier is deprecated, the program refuses it once the sunset slot has passed
+        if verifier.deprecated {
+            let slot = self.program.rpc().get_slot().await?;
+            if slot > verifier.sunset_slot {
+                return Err(ZkError::ZkvmProgramSunset.into());
+            }
+            log::warn!(
+                "ZKVM verifier {} is deprecated and will be refused after slot {} (current slot {}), migrate to selector {} ({})",
+                verifier.zkvm_program_id,
+                verifier.sunset_slot,
+                slot,
+                verifier.successor_selector,
+                verifier.successor_program_id
+            );
+        }
+
+        // Permissioned verifiers only accept callers on their allowlist, the payer signs as caller
+        if verifier.permissioned {
+            let (allowed_caller, _bump) =
+                derive_allowed_caller_pda(&verifier_account, &self.program.payer());
+            if self
+                .program
+                .account::<AllowedCaller>(allowed_caller)
+                .await
+                .is_err()
+            {
+                return Err(ZkError::CallerNotAllowed.into());
+            }
+        }
+
+        Ok(verifier)
+    }
+
+    /// Fetch the accumulator shard this client appends to, see `accumulator::AccumulatorTree` to
+    /// prove inclusion
+    pub async fn verification_accumulator(&self) -> Result<VerificationAccumulator> {
+        let accumulator = self
+            .program
+            .account::<VerificationAccumulator>(
+                derive_verification_accumulator_pda(self.accumulator_shard).0,
+            )
+            .await?;
+
+        Ok(accumulator)
+    }
+
+    /// Fetch the audit log entries of recent admin actions on the registry the client is scoped to,
+    /// oldest first
+    pub async fn audit_log(&self) -> Result<Vec<AuditLogEntry>> {
+        let audit_log = self
+            .program
+            .account::<AuditLog>(derive_audit_log_pda(&self.registry).0)
+            .await?;
+
+        Ok(audit::audit_log_entries(&audit_log))
+    }
+
+    /// Print the recent admin actions, one per line
+    pub async fn print_audit_log(&self) -> Result<()> {
+        for entry in self.audit_log().await? {
+            println!("{}", audit::format_audit_log_entry(&entry));
+        }
+
+        Ok(())
+    }
+
+    /// List the verified journals whose results were revoked by the verifier
+    pub async fn revoked_verified_journals(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+    ) -> Result<Vec<(Pubkey, VerifiedJournal)>> {
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+        let (verifier_account, _bump) = derive_zkvm_verifier_pda(
+            &self.registry,
+            zkvm_selector.to_u64(),
+            &zkvm_verifier_program,
+        );
+        let verifier = self
+            .program
+            .account::<ZkvmVerifier>(verifier_account)
+            .await?;
+
+        let revoked = self
+            .program
+            .accounts::<VerifiedJournal>(Vec::new())
+            .await?
+            .into_iter()
+            .filter(|(_, journal)| {
+                journal.verified
+                    && journal.registry == verifier.registry
+                    && journal.zkvm_selector == verifier.zkvm_selector
+                    && journal.zkvm_program_id == verifier.zkvm_program_id
+                    && verifier.check_not_revoked(journal.revocation_epoch).is_err()
+            })
+            .collect();
+
+        Ok(revoked)
+    }
+
+    /// Create the usage statistics account of a ZKVM verifier added before statistics were recorded
+    pub async fn init_verifier_stats(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+    ) -> Result<String> {
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let (verifier_account, _bump) = derive_zkvm_verifier_pda(
+            &self.registry,
+            zkvm_selector.to_u64(),
+            &zkvm_verifier_program,
+        );
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::InitVerifierStats {
+                payer: self.program.payer(),
+                zkvm_verifier_account: verifier_account,
+                zkvm_verifier_stats: derive_zkvm_verifier_stats_pda(&verifier_account).0,
+                system_program: system_program::ID,
+            })
+            .args(instruction::InitVerifierStats {})
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Create the audit log of the registry the client is scoped to, for registries created before
+    /// audit logs were kept per registry
+    pub async fn init_audit_log(&self) -> Result<String> {
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::InitAuditLog {
+                payer: self.program.payer(),
+                registry: self.registry,
+                audit_log: derive_audit_log_pda(&self.registry).0,
+                system_program: system_program::ID,
+            })
+            .args(instruction::InitAuditLog {})
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Migrate the accounts of a program deployed with the v0 account layout once `initialize`
+    /// created the default registry: the verifier counter first, then every v0 ZKVM verifier.
+    /// Run it before adding verifiers or initializing the admin multisig
+    pub async fn migrate_v0_accounts(&self) -> Result<Vec<String>> {
+        let mut signatures = Vec::new();
+        if self
+            .program
+            .account::<Counter>(derive_v0_counter_pda().0)
+            .await
+            .is_ok()
+        {
+            signatures.push(self.migrate_v0_counter().await?);
+        }
+
+        // v0 ZKVM verifier accounts share the discriminator of `ZkvmVerifier`, only their size
+        // tells them apart
+        let v0_zkvm_verifiers = self
+            .program
+            .rpc()
+            .get_program_accounts_with_config(
+                &ID,
+                RpcProgramAccountsConfig {
+                    filters: Some(vec![RpcFilterType::DataSize(ZkvmVerifierV0::SPACE as u64)]),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        for (_, account) in v0_zkvm_verifiers {
+            let v0_zkvm_verifier = ZkvmVerifierV0::try_from_account_data(&account.data)
+                .map_err(|_| ZkError::InvalidV0Account)?;
+            let zkvm_selector = ZkvmSelectorType::from_u64(v0_zkvm_verifier.zkvm_selector)
+                .ok_or(ZkError::InvalidZkvmSelector)?;
+            signatures.push(
+                self.migrate_v0_zk_verifier(zkvm_selector, Some(v0_zkvm_verifier.zkvm_program_id))
+                    .await?,
+            );
+        }
+
+        Ok(signatures)
+    }
+
+    /// Move the verifier count of the v0 account layout to the default registry
+    pub async fn migrate_v0_counter(&self) -> Result<String> {
+        // Ensure the payer holds the admin role
+        let owner_role = self.require_role(Role::Admin).await?;
+
+        let registry = derive_registry_pda(DEFAULT_REGISTRY_ID).0;
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::MigrateV0Counter {
+                owner: self.program.payer(),
+                owner_role,
+                audit_log: derive_audit_log_pda(&registry).0,
+                registry,
+                counter: derive_v0_counter_pda().0,
+                program_data,
+                admin_multisig: derive_admin_multisig_pda().0,
+            })
+            .args(instruction::MigrateV0Counter {})
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Move a ZKVM verifier of the v0 account layout to the default registry, once its counter was
+    /// migrated
+    pub async fn migrate_v0_zk_verifier(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+    ) -> Result<String> {
+        // Ensure the payer holds the admin role
+        let owner_role = self.require_role(Role::Admin).await?;
+
+        let zkvm_selector_u64 = zkvm_selector.to_u64();
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let registry = derive_registry_pda(DEFAULT_REGISTRY_ID).0;
+        let (verifier_account, _bump) =
+            derive_zkvm_verifier_pda(&registry, zkvm_selector_u64, &zkvm_verifier_program);
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::MigrateV0ZkvmVerifier {
+                owner: self.program.payer(),
+                owner_role,
+                audit_log: derive_audit_log_pda(&registry).0,
+                registry,
+                v0_zkvm_verifier_account: derive_v0_zkvm_verifier_pda(
+                    zkvm_selector_u64,
+                    &zkvm_verifier_program,
+                )
+                .0,
+                zkvm_verifier_account: verifier_account,
+                zkvm_verifier_stats: derive_zkvm_verifier_stats_pda(&verifier_account).0,
+                program_data,
+                zkvm_verifier_program,
+                zkvm_verifier_program_data: derive_program_data_address(&zkvm_verifier_program),
+                admin_multisig: derive_admin_multisig_pda().0,
+                system_program: system_program::ID,
+            })
+            .args(instruction::MigrateV0ZkVerifier {
+                zkvm_selector: zkvm_selector_u64,
+                journal_digest: zkvm_selector.journal_digest(),
+            })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Create the verification accumulator shard `shard`, shard 0 is created by `initialize`
+    pub async fn init_verification_accumulator(&self, shard: u64) -> Result<String> {
+        let signature = self
+            .program
+            .request()
+            .accounts(accounts::InitVerificationAccumulator {
+                payer: self.program.payer(),
+                verification_accumulator: derive_verification_accumulator_pda(shard).0,
+                system_program: system_program::ID,
+            })
+            .args(instruction::InitVerificationAccumulator { shard })
+            .send()
+            .await?;
+
+        Ok(signature.to_string())
+    }
+
+    /// Fetch the usage statistics of a ZKVM verifier
+    pub async fn verifier_stats(
+        &self,
+        zkvm_selector: ZkvmSelectorType,
+        overwrite_zkvm_verifier_pubkey: Option<Pubkey>,
+    ) -> Result<ZkvmVerifierStats> {
+        let zkvm_verifier_program = match overwrite_zkvm_verifier_pubkey {
+            Some(pubkey) => pubkey,
+            None => zkvm_selector.to_zkvm_verifier_id(),
+        };
+
+        let (verifier_account, _bump) = derive_zkvm_verifier_pda(
+            &self.registry,
+            zkvm_selector.to_u64(),
+            &zkvm_verifier_program,
+        );
+        let (stats_account, _bump) = derive_zkvm_verifier_stats_pda(&verifier_account);
+
+        let stats = self
+            .program
+            .account::<ZkvmVerifierStats>(stats_account)
+            .await?;
+
+        Ok(stats)
+    }
+
+    /// Get the program instance
+    pub fn program(&self) -> &Program<C> {
+        &self.program
+    }
+
+    /// Get the pubkey of the registry the client is scoped to
+    pub fn registry(&self) -> Pubkey {
+        self.registry
+    }
+
+    /// Get the verification accumulator shard the client appends to
+    pub fn accumulator_shard(&self) -> u64 {
+        self.accumulator_shard
+    }
+
+    /// Fetch the registry the client is scoped to
+    pub async fn registry_account(&self) -> Result<Registry> {
+        let registry = self.program.account::<Registry>(self.registry).await?;
+
+        Ok(registry)
+    }
+
+    /// Get the payer pubkey
+    pub fn payer(&self) -> Pubkey {
+        self.program.payer()
+    }
+
+    /// Check if the current payer is the program's upgrade authority
+    pub async fn is_upgrade_authority(&self) -> Result<bool> {
+        let (program_data, _) = Pubkey::find_program_address(
+            &[ID.as_ref()],
+            &solana_program::bpf_loader_upgradeable::ID,
+        );
+
+        // Fetch the program data account
+        let program_data_account = self.program.rpc().get_account(&program_data).await?;
+
+        // Parse the upgrade authority from the account data
+        let data = &program_data_account.data;
+        if data[12] == 1 {
+            let mut authority = [0u8; 32];
+            authority.copy_from_slice(&data[13..45]);
+            let authority_pubkey = Pubkey::new_from_array(authority);
 
             return Ok(&authority_pubkey == &self.program.payer());
         }
@@ -306,19 +3328,239 @@ impl<C: Clone + Deref<Target = impl Signer>> SolanaZkClient<C> {
         Ok(false)
     }
 
+    /// Fetch the role granted to the current payer, if any
+    pub async fn payer_role(&self) -> Result<Option<RoleAssignment>> {
+        let (role_account, _bump) = derive_role_pda(&self.program.payer());
+        let role = self.program.account::<RoleAssignment>(role_account).await.ok();
+
+        Ok(role)
+    }
+
+    /// Require that the current payer holds `role`, either as the program's upgrade authority or
+    /// through a role assignment. Returns the role account to pass to the instruction
+    pub async fn require_role(&self, role: Role) -> Result<Option<Pubkey>> {
+        // Admin actions executed with an approved proposal need no role
+        if role == Role::Admin && self.admin_proposal.is_some() {
+            return Ok(None);
+        }
+        if self.is_upgrade_authority().await? {
+            return Ok(None);
+        }
+        match self.payer_role().await? {
+            Some(assignment) if assignment.role.grants(role) => {
+                Ok(Some(derive_role_pda(&self.program.payer()).0))
+            }
+            _ => Err(ZkError::Unauthorized.into()),
+        }
+    }
+
+    /// Require that the current payer holds `role` on the registry the client is scoped to: the
+    /// admin of a tenant registry holds every role on it, the default registry needs a program
+    /// role. Returns the role account to pass to the instruction
+    pub async fn require_registry_role(&self, role: Role) -> Result<Option<Pubkey>> {
+        let registry = self.registry_account().await?;
+        if registry.is_default() {
+            return self.require_role(role).await;
+        }
+        if registry.admin != self.program.payer() {
+            return Err(ZkError::NotRegistryAdmin.into());
+        }
+        Ok(None)
+    }
+
+    // Get the admin proposal account the client executes admin actions with, if any
+    fn admin_proposal_account(&self) -> Option<Pubkey> {
+        self.admin_proposal
+            .map(|proposal_id| derive_admin_proposal_pda(proposal_id).0)
+    }
+
     /// Require that the current payer is the program's upgrade authority
     pub async fn require_upgrade_authority(&self) -> Result<()> {
         if !self.is_upgrade_authority().await? {
-            return Err(Error::msg(
-                "Current payer is not the program's upgrade authority",
-            ));
+            return Err(ZkError::Unauthorized.into());
         }
         Ok(())
     }
 }
 
-/// Helper method to derive the PDA for the Counter
+// Read the most recent entry of the SlotHashes sysvar: a u64 entry count, then (slot, hash) entries
 #[cfg(feature = "client")]
-fn derive_counter_pda() -> (Pubkey, u8) {
-    Pubkey::find_program_address(&[b"counter"], &ID)
+fn slot_hashes_head(data: &[u8]) -> Option<(u64, [u8; 32])> {
+    let entry = data.get(8..8 + 40)?;
+
+    Some((
+        u64::from_le_bytes(entry[..8].try_into().unwrap()),
+        entry[8..].try_into().unwrap(),
+    ))
+}
+
+/// Helper method to build the accounts for verifying a proof through the registry
+#[cfg(feature = "client")]
+fn verify_zk_proof_accounts(
+    registry: Pubkey,
+    zkvm_selector: u64,
+    zkvm_verifier_program: Pubkey,
+    accumulator_shard: u64,
+) -> accounts::VerifyZkProof {
+    let (verifier_account, _bump) =
+        derive_zkvm_verifier_pda(&registry, zkvm_selector, &zkvm_verifier_program);
+
+    accounts::VerifyZkProof {
+        zkvm_verifier_account: verifier_account,
+        registry,
+        zkvm_verifier_stats: derive_zkvm_verifier_stats_pda(&verifier_account).0,
+        verification_accumulator: derive_verification_accumulator_pda(accumulator_shard).0,
+        zkvm_verifier_program,
+        zkvm_verifier_program_data: derive_program_data_address(&zkvm_verifier_program),
+        system_program: system_program::ID,
+        allowed_caller: None,
+        caller_signer: None,
+        instructions_sysvar: Some(sysvar::instructions::ID),
+    }
+}
+
+/// Helper method to build the accounts for removing a ZKVM verifier, with the approved proposal
+/// once the admin multisig is initialized, or the queued admin action before
+#[cfg(feature = "client")]
+fn remove_zk_verifier_accounts(
+    registry: Pubkey,
+    owner: Pubkey,
+    owner_role: Option<Pubkey>,
+    zkvm_selector: u64,
+    zkvm_verifier_program: Pubkey,
+    admin_proposal: Option<Pubkey>,
+    queued_admin_action: Option<Pubkey>,
+) -> accounts::RemoveZkvmVerifier {
+    let (verifier_account, _bump) =
+        derive_zkvm_verifier_pda(&registry, zkvm_selector, &zkvm_verifier_program);
+    let (program_data, _) =
+        Pubkey::find_program_address(&[ID.as_ref()], &solana_program::bpf_loader_upgradeable::ID);
+
+    accounts::RemoveZkvmVerifier {
+        owner,
+        owner_role,
+        registry,
+        zkvm_verifier_account: verifier_account,
+        zkvm_verifier_stats: derive_zkvm_verifier_stats_pda(&verifier_account).0,
+        program_data,
+        zkvm_verifier_program,
+        admin_multisig: derive_admin_multisig_pda().0,
+        admin_proposal,
+        queued_admin_action,
+        audit_log: derive_audit_log_pda(&registry).0,
+    }
+}
+
+/// Helper method to build the accounts for verifying a ZK proof signed by `caller`, passing its
+/// allowlist entry when the verifier is permissioned
+#[cfg(feature = "client")]
+fn verify_zk_proof_accounts_as_caller(
+    registry: Pubkey,
+    zkvm_selector: u64,
+    zkvm_verifier_program: Pubkey,
+    caller: Pubkey,
+    permissioned: bool,
+    accumulator_shard: u64,
+) -> accounts::VerifyZkProof {
+    let mut accounts = verify_zk_proof_accounts(
+        registry,
+        zkvm_selector,
+        zkvm_verifier_program,
+        accumulator_shard,
+    );
+    accounts.caller_signer = Some(caller);
+    if permissioned {
+        accounts.allowed_caller =
+            Some(derive_allowed_caller_pda(&accounts.zkvm_verifier_account, &caller).0);
+    }
+    accounts
+}
+
+/// Helper method to build the accounts for verifying a DCAP proof under a DCAP policy
+#[cfg(feature = "client")]
+#[allow(clippy::too_many_arguments)]
+fn verify_dcap_proof_accounts(
+    registry: Pubkey,
+    zkvm_selector: u64,
+    zkvm_verifier_program: Pubkey,
+    dcap_policy: Pubkey,
+    guest_vkey: &[u8; 32],
+    authority: Pubkey,
+    buffer_id: u64,
+    accumulator_shard: u64,
+) -> accounts::VerifyDcapProof {
+    accounts::VerifyDcapProof {
+        verify: verify_zk_proof_accounts(
+            registry,
+            zkvm_selector,
+            zkvm_verifier_program,
+            accumulator_shard,
+        ),
+        dcap_guest: derive_dcap_guest_pda(&registry, zkvm_selector, guest_vkey).0,
+        dcap_policy,
+        dcap_collateral_config: derive_dcap_collateral_config_pda().0,
+        authority,
+        verified_journal: derive_verified_journal_pda(&authority, buffer_id).0,
+    }
+}
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use super::*;
+    use anchor_client::anchor_lang::error::ErrorCode;
+    use anchor_client::anchor_lang::prelude::AccountInfo;
+    use anchor_client::anchor_lang::{AnchorDeserialize, Accounts, Discriminator};
+    use anchor_client::solana_sdk::signature::Keypair;
+
+    #[test]
+    fn register_enclave_key_arguments_decode_to_the_enclave_key_pda() {
+        let payer = Keypair::new();
+        let client = SolanaZkClient::new(&payer, None);
+        let dcap_policy = Pubkey::new_unique();
+        let key = [0x42; 32];
+
+        let instructions = client
+            .register_enclave_key_instructions(
+                ZkvmSelectorType::AutomataDcap(3),
+                None,
+                dcap_policy,
+                [0x11; 32],
+                7,
+                &[0xab; 256],
+                EnclaveKeyType::Secp256k1,
+                key,
+            )
+            .unwrap();
+        let enclave_key = derive_enclave_key_pda(&dcap_policy, EnclaveKeyType::Secp256k1, &key).0;
+        let accounts = &instructions[0].accounts;
+        assert_eq!(accounts[accounts.len() - 2].pubkey, enclave_key);
+
+        // The registry decodes the `#[instruction]` arguments before any account, so a well formed
+        // argument list only fails on the missing accounts
+        let data = instructions[0]
+            .data
+            .strip_prefix(instruction::RegisterEnclaveKey::DISCRIMINATOR)
+            .unwrap();
+        let mut no_accounts: &[AccountInfo] = &[];
+        let result = solana_zk::instructions::RegisterEnclaveKey::try_accounts(
+            &solana_zk::ID,
+            &mut no_accounts,
+            data,
+            &mut Default::default(),
+            &mut Default::default(),
+        );
+        let Err(anchor_client::anchor_lang::error::Error::AnchorError(err)) = result else {
+            panic!("expected the missing accounts to fail as an anchor error");
+        };
+        assert_eq!(err.error_code_number, u32::from(ErrorCode::AccountNotEnoughKeys));
+
+        // The seeds the registry derives from the decoded arguments match the client PDA
+        let (_, _, _, key_type, decoded_key): (u64, Vec<u8>, [u8; 32], EnclaveKeyType, [u8; 32]) =
+            AnchorDeserialize::deserialize(&mut &data[..]).unwrap();
+        let (registry_enclave_key, _) = Pubkey::find_program_address(
+            &[b"enclave_key", dcap_policy.as_ref(), &[key_type as u8], &decoded_key],
+            &solana_zk::ID,
+        );
+        assert_eq!(registry_enclave_key, enclave_key);
+    }
 }
@@ -0,0 +1,109 @@
+//! Building Merkleized journals, see `solana_zk::merkle` for the tree layout.
+
+use solana_zk::merkle::{leaf_hash, node_hash};
+
+/// Merkle tree of the fields of a journal
+pub struct JournalTree {
+    // Levels of the tree, from the padded leaves up to the root
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl JournalTree {
+    pub fn new<T: AsRef<[u8]>>(fields: &[T]) -> Self {
+        let mut leaves: Vec<[u8; 32]> = fields
+            .iter()
+            .enumerate()
+            .map(|(index, value)| leaf_hash(index as u32, value.as_ref()))
+            .collect();
+        leaves.resize(fields.len().next_power_of_two(), [0u8; 32]);
+
+        let mut levels = vec![leaves];
+        while levels.last().unwrap().len() > 1 {
+            let level = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| node_hash(&pair[0], &pair[1]))
+                .collect();
+            levels.push(level);
+        }
+
+        Self { levels }
+    }
+
+    /// Merkle root, to be committed by the guest program as its journal
+    pub fn root(&self) -> [u8; 32] {
+        self.levels.last().unwrap()[0]
+    }
+
+    /// Inclusion proof of the field at `index`, for `prove_journal_field`
+    pub fn proof(&self, index: u32) -> Option<Vec<[u8; 32]>> {
+        let mut position = index as usize;
+        if position >= self.levels[0].len() {
+            return None;
+        }
+
+        let proof = self.levels[..self.levels.len() - 1]
+            .iter()
+            .map(|level| {
+                let sibling = level[position ^ 1];
+                position >>= 1;
+                sibling
+            })
+            .collect();
+
+        Some(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_zk::merkle::verify_field;
+
+    fn fields(count: usize) -> Vec<Vec<u8>> {
+        (0..count).map(|i| vec![i as u8; i + 1]).collect()
+    }
+
+    #[test]
+    fn proofs_verify_against_the_root() {
+        for count in [1, 2, 3, 5, 8] {
+            let fields = fields(count);
+            let tree = JournalTree::new(&fields);
+            for (index, value) in fields.iter().enumerate() {
+                let proof = tree.proof(index as u32).unwrap();
+                assert_eq!(
+                    proof.len(),
+                    count.next_power_of_two().trailing_zeros() as usize
+                );
+                assert!(verify_field(&tree.root(), index as u32, value, &proof));
+            }
+        }
+    }
+
+    #[test]
+    fn proofs_do_not_verify_another_field() {
+        let fields = fields(5);
+        let tree = JournalTree::new(&fields);
+        let proof = tree.proof(2).unwrap();
+
+        assert!(!verify_field(&tree.root(), 2, &fields[3], &proof));
+        assert!(!verify_field(&tree.root(), 3, &fields[2], &proof));
+        // An index beyond the depth of the proof cannot alias a field
+        assert!(!verify_field(&tree.root(), 2 + 8, &fields[2], &proof));
+        assert!(!verify_field(
+            &JournalTree::new(&fields[..4]).root(),
+            2,
+            &fields[2],
+            &proof
+        ));
+    }
+
+    #[test]
+    fn proof_is_only_built_for_leaves_of_the_tree() {
+        let tree = JournalTree::new(&fields(3));
+        // The padding leaf is part of the tree but no value hashes to it
+        assert!(tree.proof(3).is_some());
+        assert!(tree.proof(4).is_none());
+    }
+}
@@ -1,14 +1,20 @@
 use anchor_client::solana_sdk::pubkey::Pubkey;
+use solana_zk::state::JournalDigest;
 use super::{
     RISC0_VERIFIER_ROUTER_ID,
     SUCCINCT_SP1_VERIFIER_ID
 };
+use super::error::{Result, SolanaZkClientError};
+use super::verify::risc0::{risc0_verify_instruction_data, risc0_verify_instruction_prefix};
 
 #[derive(Clone, Copy, Debug)]
 #[repr(u64)]
 pub enum ZkvmSelectorType {
     RiscZero = 1,
-    Succinct = 2 
+    Succinct = 2,
+    /// RISC Zero proofs of Automata DCAP guest programs under the selector of a verifier added as a
+    /// DCAP verifier, only verified for approved guests
+    AutomataDcap(u64),
 }
 
 impl ZkvmSelectorType {
@@ -16,13 +22,75 @@ impl ZkvmSelectorType {
         match self {
             ZkvmSelectorType::RiscZero => 1,
             ZkvmSelectorType::Succinct => 2,
+            ZkvmSelectorType::AutomataDcap(zkvm_selector) => *zkvm_selector,
         }
     }
 
+    /// Map a selector to its zkVM. DCAP verifiers are told apart by `ZkvmVerifier::dcap` rather
+    /// than by their selector, so they are never returned
+    pub fn from_u64(zkvm_selector: u64) -> Option<Self> {
+        match zkvm_selector {
+            1 => Some(ZkvmSelectorType::RiscZero),
+            2 => Some(ZkvmSelectorType::Succinct),
+            _ => None,
+        }
+    }
+
+    /// Whether the verifier only verifies proofs of approved Automata DCAP guest programs
+    pub fn is_dcap(&self) -> bool {
+        matches!(self, ZkvmSelectorType::AutomataDcap(_))
+    }
+
     pub fn to_zkvm_verifier_id(&self) -> Pubkey {
         match self {
-            ZkvmSelectorType::RiscZero => RISC0_VERIFIER_ROUTER_ID,
+            ZkvmSelectorType::RiscZero | ZkvmSelectorType::AutomataDcap(_) => RISC0_VERIFIER_ROUTER_ID,
             ZkvmSelectorType::Succinct => SUCCINCT_SP1_VERIFIER_ID,
         }
     }
-}
\ No newline at end of file
+
+    /// How the zkVM derives the output digest from the journal
+    pub fn journal_digest(&self) -> JournalDigest {
+        match self {
+            ZkvmSelectorType::RiscZero | ZkvmSelectorType::AutomataDcap(_) => JournalDigest::Sha256,
+            ZkvmSelectorType::Succinct => JournalDigest::Sha256Bn254,
+        }
+    }
+
+    /// Build the instruction data of the zkVM verifier program. SP1 proofs are refused until the
+    /// instruction layout of the SP1 verifier program is supported
+    pub fn verify_instruction_data(
+        &self,
+        proof_data: &[u8],
+        program_vkey: [u8; 32],
+        output_digest: [u8; 32],
+    ) -> Result<Vec<u8>> {
+        match self {
+            ZkvmSelectorType::RiscZero | ZkvmSelectorType::AutomataDcap(_) => {
+                Ok(risc0_verify_instruction_data(proof_data, program_vkey, output_digest))
+            }
+            ZkvmSelectorType::Succinct => Err(SolanaZkClientError::UnsupportedZkvm(*self)),
+        }
+    }
+
+    /// Build the instruction data of the zkVM verifier program for a raw journal
+    pub fn verify_instruction_data_with_journal(
+        &self,
+        proof_data: &[u8],
+        program_vkey: [u8; 32],
+        journal: &[u8],
+    ) -> Result<Vec<u8>> {
+        let output_digest = self.journal_digest().digest(journal);
+        self.verify_instruction_data(proof_data, program_vkey, output_digest)
+    }
+
+    /// Build the instruction data of the zkVM verifier program up to the vkey and output digest,
+    /// which the registry appends itself when given the raw journal
+    pub fn verify_instruction_prefix(&self, proof_data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            ZkvmSelectorType::RiscZero | ZkvmSelectorType::AutomataDcap(_) => {
+                Ok(risc0_verify_instruction_prefix(proof_data))
+            }
+            ZkvmSelectorType::Succinct => Err(SolanaZkClientError::UnsupportedZkvm(*self)),
+        }
+    }
+}
@@ -1,15 +1,36 @@
+use solana_zk::state::JournalDigest;
+
 pub const RISCZERO_GROTH16_VERIFY_INSTRUCTION_DISCRIMINATOR: [u8; 8] =
     [133, 161, 141, 48, 120, 198, 88, 150];
 
+/// The output digest committed by a RISC Zero receipt is the SHA-256 of its journal
+pub fn risc0_journal_digest(journal: &[u8]) -> [u8; 32] {
+    JournalDigest::Sha256.digest(journal)
+}
+
+/// Instruction data of the RISC Zero verifier up to the image ID and output digest
+pub fn risc0_verify_instruction_prefix(proof_bytes: &[u8]) -> Vec<u8> {
+    let mut instruction_data = Vec::new();
+    instruction_data.extend_from_slice(&RISCZERO_GROTH16_VERIFY_INSTRUCTION_DISCRIMINATOR);
+    instruction_data.extend_from_slice(proof_bytes);
+    instruction_data
+}
+
 pub fn risc0_verify_instruction_data(
     proof_bytes: &[u8],
     program_image_id: [u8; 32],
     output_digest: [u8; 32]
 ) -> Vec<u8> {
-    let mut instruction_data = Vec::new();
-    instruction_data.extend_from_slice(&RISCZERO_GROTH16_VERIFY_INSTRUCTION_DISCRIMINATOR);
-    instruction_data.extend_from_slice(proof_bytes);
+    let mut instruction_data = risc0_verify_instruction_prefix(proof_bytes);
     instruction_data.extend_from_slice(&program_image_id);
     instruction_data.extend_from_slice(&output_digest);
     instruction_data
+}
+
+pub fn risc0_verify_instruction_data_with_journal(
+    proof_bytes: &[u8],
+    program_image_id: [u8; 32],
+    journal: &[u8],
+) -> Vec<u8> {
+    risc0_verify_instruction_data(proof_bytes, program_image_id, risc0_journal_digest(journal))
 }
\ No newline at end of file
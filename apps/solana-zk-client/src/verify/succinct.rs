@@ -1,3 +1,5 @@
+use solana_zk::state::JournalDigest;
+
 // use borsh::{BorshDeserialize, BorshSerialize};
 
 // // 
@@ -8,6 +10,11 @@
 //     pub sp1_public_inputs_hash: Vec<u8>,
 // }
 
+/// SP1 commits to the SHA-256 of the public values, truncated to fit in the BN254 scalar field
+pub fn sp1_public_values_digest(public_values: &[u8]) -> [u8; 32] {
+    JournalDigest::Sha256Bn254.digest(public_values)
+}
+
 pub fn sp1_groth16_verify_instruction_data(
     proof_bytes: &[u8],
     program_vkey: [u8; 32],
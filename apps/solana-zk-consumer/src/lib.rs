@@ -0,0 +1,554 @@
+//! Helpers for consumer programs that confirm a proof verified by a top-level verify instruction
+//! of the registry, in the same transaction, instead of verifying it through CPI.
+//!
+//! A consumer that calls the registry, which calls the verifier router, which calls the Groth16
+//! verifier, exceeds the CPI depth limit of 4. Instead the transaction carries the registry
+//! instruction ahead of the consumer instruction, and the consumer finds it through the
+//! instructions sysvar. As transactions are atomic, the proof is verified if the consumer runs.
+//!
+//! Consumers called back by `verify_zkvm_proof_and_call` instead receive the `VerificationResult`
+//! right after the discriminator of their instruction data, with the registry callback authority as
+//! first signer. Anchor callees take it as their first argument; a result decoded from any later
+//! argument comes from the caller and must not be trusted.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::Discriminator;
+
+use solana_zk::errors::ZkError;
+use solana_zk::instruction::{
+    RegisterEnclaveKey, StoreVerifiedJournal, VerifyDcapGuestProof, VerifyDcapProof,
+    VerifyFreshZkvmProof, VerifyHybrid, VerifyHybridEnclave, VerifyJournalBuffer, VerifyZkvmProof,
+    VerifyZkvmProofAndCall, VerifyZkvmProofWithJournal,
+};
+use solana_zk::state::{JournalDigest, VerificationResult, VERIFICATION_RESULT_LEN};
+use solana_zk::{
+    split_vkey_and_output_digest, CALLBACK_AUTHORITY_SEED, CALLBACK_DISCRIMINATOR_LEN,
+    CALLER_AUTHORITY_SEED,
+};
+
+pub use anchor_lang::solana_program::sysvar::instructions::ID as INSTRUCTIONS_SYSVAR_ID;
+
+/// Output a registry verify instruction proves
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerifiedOutput {
+    /// Output digest at the end of the verify instruction data
+    Digest([u8; 32]),
+    /// Raw journal, digested by the registry with the journal digest of the verifier
+    Journal(Vec<u8>),
+    /// `VerifiedJournal` account whose journal is verified, it records the output digest once verified
+    JournalBuffer(Pubkey),
+}
+
+/// Statement proven by a registry verify instruction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedProof {
+    pub zkvm_selector: u64,
+    pub program_vkey: [u8; 32],
+    pub output: VerifiedOutput,
+}
+
+impl VerifiedProof {
+    /// Whether the proof commits to `output_digest`. A raw journal matches under either journal
+    /// digest, as both are collision resistant. Journal buffers never match here, consumers check
+    /// the `VerifiedJournal` account instead.
+    pub fn commits_to(&self, output_digest: &[u8; 32]) -> bool {
+        match &self.output {
+            VerifiedOutput::Digest(digest) => digest == output_digest,
+            VerifiedOutput::Journal(journal) => [JournalDigest::Sha256, JournalDigest::Sha256Bn254]
+                .iter()
+                .any(|journal_digest| &journal_digest.digest(journal) == output_digest),
+            VerifiedOutput::JournalBuffer(_) => false,
+        }
+    }
+}
+
+/// Statement accepted under a hybrid policy by a registry hybrid instruction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HybridStatement {
+    pub hybrid_policy: Pubkey,
+    pub output_digest: [u8; 32],
+    /// ZK proof verified with the statement, `None` for `verify_hybrid_enclave` which only checks an
+    /// enclave signature
+    pub proof: Option<VerifiedProof>,
+}
+
+/// Decode a registry `verify_zkvm_proof`, `verify_zkvm_proof_with_journal`, `verify_zkvm_proof_and_call`,
+/// `verify_fresh_zkvm_proof`, `verify_journal_buffer`, `store_verified_journal`, `verify_dcap_guest_proof`,
+/// `verify_dcap_proof`, `register_enclave_key` or `verify_hybrid` instruction, `None` for any other
+/// instruction. See `parse_hybrid_instruction` for the hybrid policy of `verify_hybrid`
+pub fn parse_verify_instruction(instruction: &Instruction) -> Option<VerifiedProof> {
+    if instruction.program_id != solana_zk::ID {
+        return None;
+    }
+
+    let data = instruction.data.as_slice();
+    let (zkvm_selector, zk_verify_instruction_data) =
+        if let Some(args) = data.strip_prefix(VerifyZkvmProof::DISCRIMINATOR) {
+            let args = VerifyZkvmProof::try_from_slice(args).ok()?;
+            (args.zkvm_selector, args.zk_verify_instruction_data)
+        } else if let Some(args) = data.strip_prefix(VerifyZkvmProofAndCall::DISCRIMINATOR) {
+            let args = VerifyZkvmProofAndCall::try_from_slice(args).ok()?;
+            (args.zkvm_selector, args.zk_verify_instruction_data)
+        } else if let Some(args) = data.strip_prefix(VerifyFreshZkvmProof::DISCRIMINATOR) {
+            // The registry checks that the output digest is the digest of the journal
+            let args = VerifyFreshZkvmProof::try_from_slice(args).ok()?;
+            (args.zkvm_selector, args.zk_verify_instruction_data)
+        } else if let Some(args) = data.strip_prefix(VerifyDcapGuestProof::DISCRIMINATOR) {
+            let args = VerifyDcapGuestProof::try_from_slice(args).ok()?;
+            (args.zkvm_selector, args.zk_verify_instruction_data)
+        } else if let Some(args) = data.strip_prefix(VerifyHybrid::DISCRIMINATOR) {
+            // The registry checks that the output digest argument is the one of the proof
+            let args = VerifyHybrid::try_from_slice(args).ok()?;
+            (args.zkvm_selector, args.zk_verify_instruction_data)
+        } else if let Some(args) = data.strip_prefix(VerifyZkvmProofWithJournal::DISCRIMINATOR) {
+            let args = VerifyZkvmProofWithJournal::try_from_slice(args).ok()?;
+            return Some(VerifiedProof {
+                zkvm_selector: args.zkvm_selector,
+                program_vkey: args.program_vkey,
+                output: VerifiedOutput::Journal(args.journal),
+            });
+        } else if let Some(args) = data.strip_prefix(StoreVerifiedJournal::DISCRIMINATOR) {
+            let args = StoreVerifiedJournal::try_from_slice(args).ok()?;
+            return Some(VerifiedProof {
+                zkvm_selector: args.zkvm_selector,
+                program_vkey: args.program_vkey,
+                output: VerifiedOutput::Journal(args.journal),
+            });
+        } else if let Some(args) = data.strip_prefix(VerifyJournalBuffer::DISCRIMINATOR) {
+            // The journal buffer is the last account of `verify_journal_buffer`
+            let args = VerifyJournalBuffer::try_from_slice(args).ok()?;
+            return Some(VerifiedProof {
+                zkvm_selector: args.zkvm_selector,
+                program_vkey: args.program_vkey,
+                output: VerifiedOutput::JournalBuffer(instruction.accounts.last()?.pubkey),
+            });
+        } else if let Some(args) = data.strip_prefix(VerifyDcapProof::DISCRIMINATOR) {
+            // The journal buffer is the last account of `verify_dcap_proof`
+            let args = VerifyDcapProof::try_from_slice(args).ok()?;
+            return Some(VerifiedProof {
+                zkvm_selector: args.zkvm_selector,
+                program_vkey: args.guest_vkey,
+                output: VerifiedOutput::JournalBuffer(instruction.accounts.last()?.pubkey),
+            });
+        } else if let Some(args) = data.strip_prefix(RegisterEnclaveKey::DISCRIMINATOR) {
+            // The journal buffer is followed by the enclave key and the system program
+            let args = RegisterEnclaveKey::try_from_slice(args).ok()?;
+            let accounts = &instruction.accounts;
+            return Some(VerifiedProof {
+                zkvm_selector: args.zkvm_selector,
+                program_vkey: args.guest_vkey,
+                output: VerifiedOutput::JournalBuffer(
+                    accounts.get(accounts.len().checked_sub(3)?)?.pubkey,
+                ),
+            });
+        } else {
+            return None;
+        };
+
+    let (program_vkey, output_digest) =
+        split_vkey_and_output_digest(&zk_verify_instruction_data).ok()?;
+
+    Some(VerifiedProof {
+        zkvm_selector,
+        program_vkey,
+        output: VerifiedOutput::Digest(output_digest),
+    })
+}
+
+/// Decode a registry `verify_hybrid` or `verify_hybrid_enclave` instruction, `None` for any other
+/// instruction. The registry only accepts it if the evidence satisfies the mode of the policy
+pub fn parse_hybrid_instruction(instruction: &Instruction) -> Option<HybridStatement> {
+    if instruction.program_id != solana_zk::ID {
+        return None;
+    }
+
+    // The hybrid policy is the first account of both instructions
+    let hybrid_policy = instruction.accounts.first()?.pubkey;
+    let data = instruction.data.as_slice();
+    if data.starts_with(VerifyHybrid::DISCRIMINATOR) {
+        let proof = parse_verify_instruction(instruction)?;
+        let VerifiedOutput::Digest(output_digest) = proof.output else {
+            return None;
+        };
+        Some(HybridStatement {
+            hybrid_policy,
+            output_digest,
+            proof: Some(proof),
+        })
+    } else if let Some(args) = data.strip_prefix(VerifyHybridEnclave::DISCRIMINATOR) {
+        let args = VerifyHybridEnclave::try_from_slice(args).ok()?;
+        Some(HybridStatement {
+            hybrid_policy,
+            output_digest: args.output_digest,
+            proof: None,
+        })
+    } else {
+        None
+    }
+}
+
+/// Find a registry hybrid instruction preceding the current one that accepted `output_digest` under
+/// `hybrid_policy`, returning its index in the transaction
+pub fn find_hybrid_statement(
+    instructions_sysvar: &AccountInfo,
+    hybrid_policy: &Pubkey,
+    output_digest: &[u8; 32],
+) -> Result<u16> {
+    if instructions_sysvar.key != &INSTRUCTIONS_SYSVAR_ID {
+        return err!(ZkError::VerifyInstructionNotFound);
+    }
+
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    for index in 0..current_index {
+        let instruction = load_instruction_at_checked(index as usize, instructions_sysvar)?;
+        match parse_hybrid_instruction(&instruction) {
+            Some(statement)
+                if &statement.hybrid_policy == hybrid_policy
+                    && &statement.output_digest == output_digest =>
+            {
+                return Ok(index)
+            }
+            _ => {}
+        }
+    }
+
+    err!(ZkError::VerifyInstructionNotFound)
+}
+
+/// Find a registry verify instruction preceding the current one that verifies a proof of
+/// `program_vkey` committing to `output_digest`, returning its index in the transaction
+pub fn find_verified_proof(
+    instructions_sysvar: &AccountInfo,
+    zkvm_selector: u64,
+    program_vkey: &[u8; 32],
+    output_digest: &[u8; 32],
+) -> Result<u16> {
+    if instructions_sysvar.key != &INSTRUCTIONS_SYSVAR_ID {
+        return err!(ZkError::VerifyInstructionNotFound);
+    }
+
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    for index in 0..current_index {
+        let instruction = load_instruction_at_checked(index as usize, instructions_sysvar)?;
+        match parse_verify_instruction(&instruction) {
+            Some(proof)
+                if proof.zkvm_selector == zkvm_selector
+                    && &proof.program_vkey == program_vkey
+                    && proof.commits_to(output_digest) =>
+            {
+                return Ok(index)
+            }
+            _ => {}
+        }
+    }
+
+    err!(ZkError::VerifyInstructionNotFound)
+}
+
+/// Address of the PDA `program_id` signs with as `caller_signer` to be identified by the registry,
+/// and allowlisted by permissioned verifiers, when it is not the top-level instruction
+pub fn caller_authority(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CALLER_AUTHORITY_SEED], program_id)
+}
+
+/// Address of the registry PDA signing `verify_zkvm_proof_and_call` callbacks
+pub fn callback_authority() -> Pubkey {
+    Pubkey::find_program_address(&[CALLBACK_AUTHORITY_SEED], &solana_zk::ID).0
+}
+
+/// Check that the current instruction is a `verify_zkvm_proof_and_call` callback, signed by the
+/// registry callback authority after a successful verification
+pub fn check_callback_authority(account: &AccountInfo) -> Result<()> {
+    if !account.is_signer || account.key != &callback_authority() {
+        return err!(ZkError::InvalidCallbackAuthority);
+    }
+
+    Ok(())
+}
+
+/// Read the `VerificationResult` the registry wrote after the discriminator of the instruction data
+/// of a `verify_zkvm_proof_and_call` callback, for callees that decode their own instruction data.
+/// Only trust it once `check_callback_authority` passed.
+pub fn callback_verification_result(instruction_data: &[u8]) -> Result<VerificationResult> {
+    let result = instruction_data
+        .get(CALLBACK_DISCRIMINATOR_LEN..CALLBACK_DISCRIMINATOR_LEN + VERIFICATION_RESULT_LEN)
+        .ok_or_else(|| error!(ZkError::MalformedCallbackData))?;
+
+    VerificationResult::try_from_slice(result).map_err(|_| error!(ZkError::MalformedCallbackData))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::instruction::AccountMeta;
+    use anchor_lang::InstructionData;
+    use solana_zk::state::EnclaveKeyType;
+
+    const VKEY: [u8; 32] = [1; 32];
+    const JOURNAL: &[u8] = b"guest journal";
+
+    fn registry_instruction(data: impl InstructionData) -> Instruction {
+        Instruction {
+            program_id: solana_zk::ID,
+            accounts: Vec::new(),
+            data: data.data(),
+        }
+    }
+
+    fn verify_instruction_data() -> Vec<u8> {
+        let mut data = vec![0; 8];
+        data.extend_from_slice(&VKEY);
+        data.extend_from_slice(&JournalDigest::Sha256.digest(JOURNAL));
+        data
+    }
+
+    fn expected(output: VerifiedOutput) -> Option<VerifiedProof> {
+        Some(VerifiedProof {
+            zkvm_selector: 3,
+            program_vkey: VKEY,
+            output,
+        })
+    }
+
+    #[test]
+    fn parses_entry_points_carrying_the_output_digest() {
+        let digest = VerifiedOutput::Digest(JournalDigest::Sha256.digest(JOURNAL));
+        for instruction in [
+            registry_instruction(VerifyZkvmProof {
+                zkvm_selector: 3,
+                zk_verify_instruction_data: verify_instruction_data(),
+            }),
+            registry_instruction(VerifyZkvmProofAndCall {
+                zkvm_selector: 3,
+                zk_verify_instruction_data: verify_instruction_data(),
+                callback_data: vec![9; 4],
+            }),
+            registry_instruction(VerifyFreshZkvmProof {
+                zkvm_selector: 3,
+                zk_verify_instruction_data: verify_instruction_data(),
+                journal: JOURNAL.to_vec(),
+                nonce: [0; 32],
+            }),
+            registry_instruction(VerifyDcapGuestProof {
+                zkvm_selector: 3,
+                zk_verify_instruction_data: verify_instruction_data(),
+                guest_vkey: VKEY,
+            }),
+        ] {
+            assert_eq!(
+                parse_verify_instruction(&instruction),
+                expected(digest.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn parses_entry_points_carrying_the_journal() {
+        let journal = VerifiedOutput::Journal(JOURNAL.to_vec());
+        for instruction in [
+            registry_instruction(VerifyZkvmProofWithJournal {
+                zkvm_selector: 3,
+                zk_verify_instruction_prefix: vec![0; 8],
+                program_vkey: VKEY,
+                journal: JOURNAL.to_vec(),
+            }),
+            registry_instruction(StoreVerifiedJournal {
+                zkvm_selector: 3,
+                zk_verify_instruction_prefix: vec![0; 8],
+                program_vkey: VKEY,
+                journal: JOURNAL.to_vec(),
+                buffer_id: 0,
+            }),
+        ] {
+            let proof = parse_verify_instruction(&instruction);
+            assert_eq!(proof, expected(journal.clone()));
+
+            let proof = proof.unwrap();
+            assert!(proof.commits_to(&JournalDigest::Sha256.digest(JOURNAL)));
+            assert!(proof.commits_to(&JournalDigest::Sha256Bn254.digest(JOURNAL)));
+            assert!(!proof.commits_to(&JournalDigest::Sha256.digest(b"other journal")));
+        }
+    }
+
+    #[test]
+    fn parses_journal_buffer_entry_point() {
+        let verified_journal = Pubkey::new_unique();
+        let mut instruction = registry_instruction(VerifyJournalBuffer {
+            zkvm_selector: 3,
+            zk_verify_instruction_prefix: vec![0; 8],
+            program_vkey: VKEY,
+        });
+        instruction.accounts = vec![
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new(verified_journal, false),
+        ];
+
+        let proof = parse_verify_instruction(&instruction);
+        assert_eq!(
+            proof,
+            expected(VerifiedOutput::JournalBuffer(verified_journal))
+        );
+        assert!(!proof
+            .unwrap()
+            .commits_to(&JournalDigest::Sha256.digest(JOURNAL)));
+    }
+
+    #[test]
+    fn parses_dcap_entry_points_verifying_a_journal_buffer() {
+        let verified_journal = Pubkey::new_unique();
+        let mut verify = registry_instruction(VerifyDcapProof {
+            zkvm_selector: 3,
+            zk_verify_instruction_prefix: vec![0; 8],
+            guest_vkey: VKEY,
+        });
+        verify.accounts = vec![
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new(verified_journal, false),
+        ];
+        let mut register = registry_instruction(RegisterEnclaveKey {
+            zkvm_selector: 3,
+            zk_verify_instruction_prefix: vec![0; 8],
+            guest_vkey: VKEY,
+            key_type: EnclaveKeyType::Ed25519,
+            key: [2; 32],
+        });
+        register.accounts = vec![
+            AccountMeta::new(Pubkey::new_unique(), true),
+            AccountMeta::new(verified_journal, false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        ];
+
+        for instruction in [verify, register] {
+            assert_eq!(
+                parse_verify_instruction(&instruction),
+                expected(VerifiedOutput::JournalBuffer(verified_journal))
+            );
+        }
+    }
+
+    #[test]
+    fn parses_hybrid_entry_points_with_their_policy() {
+        let hybrid_policy = Pubkey::new_unique();
+        let output_digest = JournalDigest::Sha256.digest(JOURNAL);
+        let accounts = vec![
+            AccountMeta::new_readonly(hybrid_policy, false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        ];
+
+        let mut zk = registry_instruction(VerifyHybrid {
+            zkvm_selector: 3,
+            zk_verify_instruction_data: verify_instruction_data(),
+            output_digest,
+        });
+        zk.accounts = accounts.clone();
+        let proof = expected(VerifiedOutput::Digest(output_digest));
+        assert_eq!(parse_verify_instruction(&zk), proof);
+        assert_eq!(
+            parse_hybrid_instruction(&zk),
+            Some(HybridStatement {
+                hybrid_policy,
+                output_digest,
+                proof,
+            })
+        );
+
+        // An enclave signature alone does not prove a statement to `parse_verify_instruction`
+        let mut enclave = registry_instruction(VerifyHybridEnclave { output_digest });
+        enclave.accounts = accounts;
+        assert_eq!(parse_verify_instruction(&enclave), None);
+        assert_eq!(
+            parse_hybrid_instruction(&enclave),
+            Some(HybridStatement {
+                hybrid_policy,
+                output_digest,
+                proof: None,
+            })
+        );
+
+        // Other verify instructions are not hybrid statements
+        let mut verify = registry_instruction(VerifyZkvmProof {
+            zkvm_selector: 3,
+            zk_verify_instruction_data: verify_instruction_data(),
+        });
+        verify.accounts = vec![AccountMeta::new_readonly(hybrid_policy, false)];
+        assert_eq!(parse_hybrid_instruction(&verify), None);
+    }
+
+    #[test]
+    fn ignores_other_instructions() {
+        let mut instruction = registry_instruction(VerifyZkvmProof {
+            zkvm_selector: 3,
+            zk_verify_instruction_data: verify_instruction_data(),
+        });
+        instruction.program_id = Pubkey::new_unique();
+        assert_eq!(parse_verify_instruction(&instruction), None);
+
+        let instruction = registry_instruction(solana_zk::instruction::Initialize {});
+        assert_eq!(parse_verify_instruction(&instruction), None);
+    }
+
+    fn verification_result(output_digest: [u8; 32]) -> VerificationResult {
+        VerificationResult {
+            registry: Pubkey::new_unique(),
+            zkvm_selector: 3,
+            zkvm_program_id: Pubkey::new_unique(),
+            program_vkey: VKEY,
+            output_digest,
+            slot: 42,
+            revocation_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn callback_result_follows_the_discriminator() {
+        let result = verification_result([5; 32]);
+        let mut callback_data = vec![7; CALLBACK_DISCRIMINATOR_LEN];
+        callback_data.extend_from_slice(b"callee args");
+
+        let data = solana_zk::callback_instruction_data(&callback_data, &result).unwrap();
+        assert_eq!(data.len(), callback_data.len() + VERIFICATION_RESULT_LEN);
+        assert_eq!(
+            data[..CALLBACK_DISCRIMINATOR_LEN],
+            [7; CALLBACK_DISCRIMINATOR_LEN]
+        );
+        assert!(data.ends_with(b"callee args"));
+        assert_eq!(callback_verification_result(&data).unwrap(), result);
+    }
+
+    #[test]
+    fn forged_result_in_callback_data_is_not_read() {
+        let result = verification_result([5; 32]);
+        let forged = verification_result([6; 32]);
+
+        // The caller places a forged result where a trailing argument would be decoded from
+        let mut callback_data = vec![7; CALLBACK_DISCRIMINATOR_LEN];
+        forged.serialize(&mut callback_data).unwrap();
+
+        let data = solana_zk::callback_instruction_data(&callback_data, &result).unwrap();
+        assert_eq!(callback_verification_result(&data).unwrap(), result);
+
+        // A callback_data without discriminator cannot push the forged result into place either
+        let mut callback_data = Vec::new();
+        forged.serialize(&mut callback_data).unwrap();
+        let data = solana_zk::callback_instruction_data(&callback_data, &result).unwrap();
+        assert_eq!(callback_verification_result(&data).unwrap(), result);
+    }
+
+    #[test]
+    fn callback_data_requires_a_discriminator() {
+        let result = verification_result([5; 32]);
+        assert_eq!(
+            solana_zk::callback_instruction_data(&[7; 4], &result).unwrap_err(),
+            error!(ZkError::MalformedCallbackData)
+        );
+        assert_eq!(
+            callback_verification_result(&[7; CALLBACK_DISCRIMINATOR_LEN]).unwrap_err(),
+            error!(ZkError::MalformedCallbackData)
+        );
+    }
+}
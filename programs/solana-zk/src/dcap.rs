@@ -0,0 +1,273 @@
+//! Decoding of the journal committed by the Automata DCAP guest program.
+//!
+//! The journal is laid out as follows, all integers big endian:
+//!
+//! | field                          | size                        |
+//! |--------------------------------|-----------------------------|
+//! | verified output length         | 2                           |
+//! | quote version                  | 2                           |
+//! | TEE type                       | 4                           |
+//! | TCB status                     | 1                           |
+//! | FMSPC                          | 6                           |
+//! | quote body                     | 384 (SGX), 584 (TDX)        |
+//! | advisory IDs, comma separated  | rest of the verified output |
+//! | verification timestamp         | 8                           |
+//! | TCB evaluation data number     | 4                           |
+//! | collateral hashes              | 6 * 32                      |
+
+use anchor_lang::prelude::*;
+
+use super::errors::ZkError;
+
+pub const TEE_TYPE_SGX: u32 = 0x0000_0000;
+pub const TEE_TYPE_TDX: u32 = 0x0000_0081;
+
+/// Length of the report body of an SGX quote
+pub const SGX_ENCLAVE_REPORT_LEN: usize = 384;
+/// Length of the report body of a TDX 1.0 quote
+pub const TD10_REPORT_LEN: usize = 584;
+
+/// Offset of the quote body in the journal
+pub const JOURNAL_QUOTE_BODY_OFFSET: usize = 2 + 2 + 4 + 1 + 6;
+
+/// TCB status of the platform, as evaluated by the DCAP guest against the TCB info collateral
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TcbStatus {
+    UpToDate,
+    SwHardeningNeeded,
+    ConfigurationAndSwHardeningNeeded,
+    ConfigurationNeeded,
+    OutOfDate,
+    OutOfDateConfigurationNeeded,
+    Revoked,
+    Unrecognized,
+}
+
+impl TcbStatus {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::UpToDate),
+            1 => Some(Self::SwHardeningNeeded),
+            2 => Some(Self::ConfigurationAndSwHardeningNeeded),
+            3 => Some(Self::ConfigurationNeeded),
+            4 => Some(Self::OutOfDate),
+            5 => Some(Self::OutOfDateConfigurationNeeded),
+            6 => Some(Self::Revoked),
+            7 => Some(Self::Unrecognized),
+            _ => None,
+        }
+    }
+
+    /// Bit of this status in a `DcapPolicy::allowed_tcb_statuses` mask
+    pub fn mask(&self) -> u8 {
+        1 << (*self as u8)
+    }
+}
+
+/// Hashes of the collateral the DCAP guest verified the quote against
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DcapCollateralHashes {
+    pub tcb_info: [u8; 32],
+    pub qe_identity: [u8; 32],
+    pub root_ca: [u8; 32],
+    pub signing_ca: [u8; 32],
+    pub root_crl: [u8; 32],
+    pub pck_crl: [u8; 32],
+}
+
+/// Typed view of the journal committed by the DCAP guest program
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DcapVerifiedOutput {
+    pub quote_version: u16,
+    pub tee_type: u32,
+    pub tcb_status: TcbStatus,
+    pub fmspc: [u8; 6],
+    /// MRENCLAVE (SGX, zero padded) or MRTD (TDX)
+    pub measurement: [u8; 48],
+    /// MRSIGNER (SGX only, zeroed for TDX)
+    pub mr_signer: [u8; 32],
+    pub report_data: [u8; 64],
+    pub advisory_ids: Vec<String>,
+    /// Unix timestamp at which the guest verified the quote
+    pub timestamp: u64,
+    pub tcb_evaluation_data_number: u32,
+    pub collateral: DcapCollateralHashes,
+}
+
+impl DcapVerifiedOutput {
+    /// Decode the journal committed by the DCAP guest program
+    pub fn decode(journal: &[u8]) -> Result<Self> {
+        let mut reader = Reader(journal);
+
+        let output_len = u16::from_be_bytes(reader.take()?) as usize;
+        let mut output = Reader(reader.take_slice(output_len)?);
+
+        let quote_version = u16::from_be_bytes(output.take()?);
+        let tee_type = u32::from_be_bytes(output.take()?);
+        let tcb_status = TcbStatus::from_u8(u8::from_be_bytes(output.take()?))
+            .ok_or(error!(ZkError::MalformedDcapJournal))?;
+        let fmspc = output.take()?;
+
+        let mut measurement = [0u8; 48];
+        let mut mr_signer = [0u8; 32];
+        let mut report_data = [0u8; 64];
+        match tee_type {
+            TEE_TYPE_SGX => {
+                let body = output.take_slice(SGX_ENCLAVE_REPORT_LEN)?;
+                measurement[..32].copy_from_slice(&body[64..96]);
+                mr_signer.copy_from_slice(&body[128..160]);
+                report_data.copy_from_slice(&body[320..384]);
+            }
+            TEE_TYPE_TDX => {
+                let body = output.take_slice(TD10_REPORT_LEN)?;
+                measurement.copy_from_slice(&body[136..184]);
+                report_data.copy_from_slice(&body[520..584]);
+            }
+            _ => return err!(ZkError::MalformedDcapJournal),
+        }
+
+        let advisory_ids = match output.0 {
+            [] => Vec::new(),
+            rest => std::str::from_utf8(rest)
+                .map_err(|_| error!(ZkError::MalformedDcapJournal))?
+                .split(',')
+                .map(String::from)
+                .collect(),
+        };
+
+        let timestamp = u64::from_be_bytes(reader.take()?);
+        let tcb_evaluation_data_number = u32::from_be_bytes(reader.take()?);
+        let collateral = DcapCollateralHashes {
+            tcb_info: reader.take()?,
+            qe_identity: reader.take()?,
+            root_ca: reader.take()?,
+            signing_ca: reader.take()?,
+            root_crl: reader.take()?,
+            pck_crl: reader.take()?,
+        };
+
+        if !reader.0.is_empty() {
+            return err!(ZkError::MalformedDcapJournal);
+        }
+
+        Ok(Self {
+            quote_version,
+            tee_type,
+            tcb_status,
+            fmspc,
+            measurement,
+            mr_signer,
+            report_data,
+            advisory_ids,
+            timestamp,
+            tcb_evaluation_data_number,
+            collateral,
+        })
+    }
+}
+
+// Minimal cursor over the journal bytes
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take_slice(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            return err!(ZkError::MalformedDcapJournal);
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take_slice(N)?.try_into().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Journal of a quote whose body byte `i` is `i`, so every field decodes to its own offsets
+    fn journal(tee_type: u32, body_len: usize, advisory_ids: &[u8]) -> Vec<u8> {
+        let mut output = Vec::new();
+        output.extend_from_slice(&4u16.to_be_bytes());
+        output.extend_from_slice(&tee_type.to_be_bytes());
+        output.push(TcbStatus::SwHardeningNeeded as u8);
+        output.extend_from_slice(&[0x00, 0x90, 0x6e, 0xd5, 0x00, 0x00]);
+        output.extend((0..body_len).map(|i| i as u8));
+        output.extend_from_slice(advisory_ids);
+
+        let mut journal = (output.len() as u16).to_be_bytes().to_vec();
+        journal.extend_from_slice(&output);
+        journal.extend_from_slice(&1_700_000_000u64.to_be_bytes());
+        journal.extend_from_slice(&17u32.to_be_bytes());
+        for i in 0..6 {
+            journal.extend_from_slice(&[i; 32]);
+        }
+        journal
+    }
+
+    fn body_bytes<const N: usize>(start: usize) -> [u8; N] {
+        core::array::from_fn(|i| (start + i) as u8)
+    }
+
+    #[test]
+    fn decodes_sgx_journal_at_enclave_report_offsets() {
+        let output = DcapVerifiedOutput::decode(&journal(
+            TEE_TYPE_SGX,
+            SGX_ENCLAVE_REPORT_LEN,
+            b"INTEL-SA-00615,INTEL-SA-00657",
+        ))
+        .unwrap();
+
+        assert_eq!(output.quote_version, 4);
+        assert_eq!(output.tee_type, TEE_TYPE_SGX);
+        assert_eq!(output.tcb_status, TcbStatus::SwHardeningNeeded);
+        assert_eq!(output.fmspc, [0x00, 0x90, 0x6e, 0xd5, 0x00, 0x00]);
+        assert_eq!(output.measurement[..32], body_bytes::<32>(64));
+        assert_eq!(output.measurement[32..], [0; 16]);
+        assert_eq!(output.mr_signer, body_bytes::<32>(128));
+        assert_eq!(output.report_data, body_bytes::<64>(320));
+        assert_eq!(output.advisory_ids, ["INTEL-SA-00615", "INTEL-SA-00657"]);
+        assert_eq!(output.timestamp, 1_700_000_000);
+        assert_eq!(output.tcb_evaluation_data_number, 17);
+        assert_eq!(output.collateral.tcb_info, [0; 32]);
+        assert_eq!(output.collateral.pck_crl, [5; 32]);
+    }
+
+    #[test]
+    fn decodes_tdx_journal_at_td_report_offsets() {
+        let output =
+            DcapVerifiedOutput::decode(&journal(TEE_TYPE_TDX, TD10_REPORT_LEN, b"")).unwrap();
+
+        assert_eq!(output.tee_type, TEE_TYPE_TDX);
+        assert_eq!(output.measurement, body_bytes::<48>(136));
+        assert_eq!(output.mr_signer, [0; 32]);
+        assert_eq!(output.report_data, body_bytes::<64>(520));
+        assert!(output.advisory_ids.is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_journals() {
+        let valid = journal(TEE_TYPE_SGX, SGX_ENCLAVE_REPORT_LEN, b"");
+
+        // Truncated or with trailing bytes
+        assert!(DcapVerifiedOutput::decode(&valid[..valid.len() - 1]).is_err());
+        let mut trailing = valid.clone();
+        trailing.push(0);
+        assert!(DcapVerifiedOutput::decode(&trailing).is_err());
+
+        // Unknown TEE type, or an SGX body too short for a TDX report
+        assert!(DcapVerifiedOutput::decode(&journal(2, SGX_ENCLAVE_REPORT_LEN, b"")).is_err());
+        assert!(
+            DcapVerifiedOutput::decode(&journal(TEE_TYPE_TDX, SGX_ENCLAVE_REPORT_LEN, b""))
+                .is_err()
+        );
+
+        // Unknown TCB status
+        let mut unknown_status = valid;
+        unknown_status[2 + 2 + 4] = 8;
+        assert!(DcapVerifiedOutput::decode(&unknown_status).is_err());
+    }
+}
@@ -0,0 +1,357 @@
+//! Helpers for consumer programs to check signatures of enclaves registered through a verified
+//! DCAP proof, turning one ZK verification into many cheap native signature checks.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::solana_program::{ed25519_program, secp256k1_program};
+
+use super::errors::ZkError;
+use super::state::{EnclaveKey, EnclaveKeyType};
+
+/// Size of an `Ed25519SignatureOffsets` entry
+const ED25519_OFFSETS_LEN: usize = 14;
+/// Size of a `SecpSignatureOffsets` entry
+const SECP256K1_OFFSETS_LEN: usize = 11;
+
+/// Check that an instruction preceding the current one has the Ed25519 or secp256k1 native
+/// program verify a signature of `message` by the enclave key
+pub fn check_enclave_signature(
+    instructions_sysvar: &AccountInfo,
+    enclave_key: &EnclaveKey,
+    message: &[u8],
+) -> Result<()> {
+    if enclave_key.expires_at <= Clock::get()?.unix_timestamp {
+        return err!(ZkError::EnclaveKeyExpired);
+    }
+
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    for index in 0..current_index {
+        let instruction = load_instruction_at_checked(index as usize, instructions_sysvar)?;
+
+        let signed = match enclave_key.key_type {
+            EnclaveKeyType::Ed25519 if instruction.program_id == ed25519_program::ID => {
+                ed25519_instruction_signs(&instruction.data, &enclave_key.key, message)
+            }
+            EnclaveKeyType::Secp256k1 if instruction.program_id == secp256k1_program::ID => {
+                secp256k1_instruction_signs(
+                    &instruction.data,
+                    index as u8,
+                    &enclave_key.key[..20],
+                    message,
+                )
+            }
+            _ => false,
+        };
+
+        if signed {
+            return Ok(());
+        }
+    }
+
+    err!(ZkError::EnclaveSignatureNotFound)
+}
+
+// Only signatures whose public key and message are inlined in the Ed25519 instruction are considered
+fn ed25519_instruction_signs(data: &[u8], key: &[u8; 32], message: &[u8]) -> bool {
+    let Some(&count) = data.first() else {
+        return false;
+    };
+
+    (0..count as usize).any(|i| {
+        // Offsets start after the 1 byte signature count and 1 byte padding
+        let start = 2 + i * ED25519_OFFSETS_LEN;
+        let Some(offsets) = data.get(start..start + ED25519_OFFSETS_LEN) else {
+            return false;
+        };
+        let read = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+
+        let (public_key_offset, public_key_index) = (read(4) as usize, read(6));
+        let (message_offset, message_size, message_index) =
+            (read(8) as usize, read(10) as usize, read(12));
+        if public_key_index != u16::MAX || message_index != u16::MAX {
+            return false;
+        }
+
+        data.get(public_key_offset..public_key_offset + 32) == Some(&key[..])
+            && data.get(message_offset..message_offset + message_size) == Some(message)
+    })
+}
+
+// Only signatures whose address and message are inlined in the secp256k1 instruction are considered
+fn secp256k1_instruction_signs(
+    data: &[u8],
+    instruction_index: u8,
+    eth_address: &[u8],
+    message: &[u8],
+) -> bool {
+    let Some(&count) = data.first() else {
+        return false;
+    };
+
+    (0..count as usize).any(|i| {
+        // Offsets start after the 1 byte signature count
+        let start = 1 + i * SECP256K1_OFFSETS_LEN;
+        let Some(offsets) = data.get(start..start + SECP256K1_OFFSETS_LEN) else {
+            return false;
+        };
+        let read = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]) as usize;
+
+        let (eth_address_offset, eth_address_index) = (read(3), offsets[5]);
+        let (message_offset, message_size, message_index) = (read(6), read(8), offsets[10]);
+        if eth_address_index != instruction_index || message_index != instruction_index {
+            return false;
+        }
+
+        data.get(eth_address_offset..eth_address_offset + 20) == Some(eth_address)
+            && data.get(message_offset..message_offset + message_size) == Some(message)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+    use anchor_lang::solana_program::sysvar::instructions::{
+        construct_instructions_data, BorrowedInstruction, ID as INSTRUCTIONS_SYSVAR_ID,
+    };
+
+    const KEY: [u8; 32] = [7; 32];
+    const MESSAGE: &[u8] = b"enclave attested message";
+
+    // Ed25519 instruction with a single signature, laid out like `new_ed25519_instruction`
+    fn ed25519_instruction(key: &[u8; 32], message: &[u8], index: u16) -> Vec<u8> {
+        let public_key_offset: u16 = 2 + ED25519_OFFSETS_LEN as u16;
+        let signature_offset = public_key_offset + 32;
+        let message_offset = signature_offset + 64;
+
+        let mut data = vec![1, 0];
+        for value in [
+            signature_offset,
+            index,
+            public_key_offset,
+            index,
+            message_offset,
+            message.len() as u16,
+            index,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(key);
+        data.extend_from_slice(&[0; 64]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    // Secp256k1 instruction with a single signature, laid out like `new_secp256k1_instruction`
+    fn secp256k1_instruction(eth_address: &[u8], message: &[u8], index: u8) -> Vec<u8> {
+        let eth_address_offset: u16 = 1 + SECP256K1_OFFSETS_LEN as u16;
+        let signature_offset = eth_address_offset + 20;
+        let message_offset = signature_offset + 65;
+
+        let mut data = vec![1];
+        data.extend_from_slice(&signature_offset.to_le_bytes());
+        data.push(index);
+        data.extend_from_slice(&eth_address_offset.to_le_bytes());
+        data.push(index);
+        data.extend_from_slice(&message_offset.to_le_bytes());
+        data.extend_from_slice(&(message.len() as u16).to_le_bytes());
+        data.push(index);
+        data.extend_from_slice(eth_address);
+        data.extend_from_slice(&[0; 65]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    const NOW: i64 = 1_000;
+
+    // Serve a clock at `NOW` to `Clock::get`
+    struct ClockStubs;
+
+    impl SyscallStubs for ClockStubs {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            let clock = Clock {
+                unix_timestamp: NOW,
+                ..Clock::default()
+            };
+            unsafe { std::ptr::write(var_addr as *mut Clock, clock) };
+            0
+        }
+    }
+
+    fn enclave_key(key_type: EnclaveKeyType, expires_at: i64) -> EnclaveKey {
+        EnclaveKey {
+            dcap_policy: Pubkey::default(),
+            key_type,
+            key: KEY,
+            tee_type: 0,
+            measurement: [0; 48],
+            mr_signer: [0; 32],
+            expires_at,
+            payer: Pubkey::default(),
+        }
+    }
+
+    // Instructions sysvar data of a transaction running `current_index`
+    fn instructions_sysvar_data(instructions: &[(Pubkey, Vec<u8>)], current_index: u16) -> Vec<u8> {
+        let borrowed: Vec<BorrowedInstruction> = instructions
+            .iter()
+            .map(|(program_id, data)| BorrowedInstruction {
+                program_id,
+                accounts: Vec::new(),
+                data,
+            })
+            .collect();
+        let mut data = construct_instructions_data(&borrowed);
+        let len = data.len();
+        data[len - 2..].copy_from_slice(&current_index.to_le_bytes());
+        data
+    }
+
+    fn check(enclave_key: &EnclaveKey, mut sysvar_data: Vec<u8>) -> Result<()> {
+        set_syscall_stubs(Box::new(ClockStubs));
+        let mut lamports = 0;
+        let owner = Pubkey::default();
+        let info = AccountInfo::new(
+            &INSTRUCTIONS_SYSVAR_ID,
+            false,
+            false,
+            &mut lamports,
+            &mut sysvar_data,
+            &owner,
+            false,
+            0,
+        );
+        check_enclave_signature(&info, enclave_key, MESSAGE)
+    }
+
+    #[test]
+    fn enclave_signature_is_found_in_a_preceding_instruction() {
+        let program = Pubkey::new_unique();
+        let ed25519 = (
+            ed25519_program::ID,
+            ed25519_instruction(&KEY, MESSAGE, u16::MAX),
+        );
+        let secp256k1 = (
+            secp256k1_program::ID,
+            secp256k1_instruction(&KEY[..20], MESSAGE, 1),
+        );
+        let sysvar_data = instructions_sysvar_data(&[ed25519, secp256k1, (program, Vec::new())], 2);
+
+        assert!(check(
+            &enclave_key(EnclaveKeyType::Ed25519, NOW + 1),
+            sysvar_data.clone()
+        )
+        .is_ok());
+        assert!(check(
+            &enclave_key(EnclaveKeyType::Secp256k1, NOW + 1),
+            sysvar_data
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn enclave_signature_must_precede_and_use_the_native_program_of_the_key() {
+        let program = Pubkey::new_unique();
+        let ed25519 = (
+            ed25519_program::ID,
+            ed25519_instruction(&KEY, MESSAGE, u16::MAX),
+        );
+        let enclave_key = enclave_key(EnclaveKeyType::Ed25519, NOW + 1);
+
+        // The signature instruction runs after the current one
+        let sysvar_data = instructions_sysvar_data(&[(program, Vec::new()), ed25519.clone()], 0);
+        assert_eq!(
+            check(&enclave_key, sysvar_data).unwrap_err(),
+            error!(ZkError::EnclaveSignatureNotFound)
+        );
+
+        // The signature data is not verified by the Ed25519 program
+        let sysvar_data = instructions_sysvar_data(
+            &[(secp256k1_program::ID, ed25519.1), (program, Vec::new())],
+            1,
+        );
+        assert_eq!(
+            check(&enclave_key, sysvar_data).unwrap_err(),
+            error!(ZkError::EnclaveSignatureNotFound)
+        );
+    }
+
+    #[test]
+    fn expired_enclave_key_is_rejected() {
+        let sysvar_data = instructions_sysvar_data(
+            &[
+                (
+                    ed25519_program::ID,
+                    ed25519_instruction(&KEY, MESSAGE, u16::MAX),
+                ),
+                (Pubkey::new_unique(), Vec::new()),
+            ],
+            1,
+        );
+        assert_eq!(
+            check(&enclave_key(EnclaveKeyType::Ed25519, NOW), sysvar_data).unwrap_err(),
+            error!(ZkError::EnclaveKeyExpired)
+        );
+    }
+
+    #[test]
+    fn ed25519_offsets_match_inline_key_and_message() {
+        let data = ed25519_instruction(&KEY, MESSAGE, u16::MAX);
+        assert!(ed25519_instruction_signs(&data, &KEY, MESSAGE));
+        assert!(!ed25519_instruction_signs(&data, &[8; 32], MESSAGE));
+        assert!(!ed25519_instruction_signs(&data, &KEY, b"another message"));
+    }
+
+    #[test]
+    fn ed25519_rejects_data_in_other_instructions() {
+        let data = ed25519_instruction(&KEY, MESSAGE, 0);
+        assert!(!ed25519_instruction_signs(&data, &KEY, MESSAGE));
+    }
+
+    #[test]
+    fn ed25519_rejects_truncated_data() {
+        let data = ed25519_instruction(&KEY, MESSAGE, u16::MAX);
+        let truncated = &data[..data.len() - 1];
+        assert!(!ed25519_instruction_signs(truncated, &KEY, MESSAGE));
+        assert!(!ed25519_instruction_signs(&data[..10], &KEY, MESSAGE));
+        assert!(!ed25519_instruction_signs(&[], &KEY, MESSAGE));
+    }
+
+    #[test]
+    fn secp256k1_offsets_match_inline_address_and_message() {
+        let address = &KEY[..20];
+        let data = secp256k1_instruction(address, MESSAGE, 2);
+        assert!(secp256k1_instruction_signs(&data, 2, address, MESSAGE));
+        assert!(!secp256k1_instruction_signs(&data, 2, &[8; 20], MESSAGE));
+        assert!(!secp256k1_instruction_signs(
+            &data,
+            2,
+            address,
+            b"another message"
+        ));
+    }
+
+    #[test]
+    fn secp256k1_rejects_data_in_other_instructions() {
+        let address = &KEY[..20];
+        let data = secp256k1_instruction(address, MESSAGE, 1);
+        assert!(!secp256k1_instruction_signs(&data, 2, address, MESSAGE));
+    }
+
+    #[test]
+    fn secp256k1_rejects_truncated_data() {
+        let address = &KEY[..20];
+        let data = secp256k1_instruction(address, MESSAGE, 2);
+        let truncated = &data[..data.len() - 1];
+        assert!(!secp256k1_instruction_signs(truncated, 2, address, MESSAGE));
+        assert!(!secp256k1_instruction_signs(
+            &data[..8],
+            2,
+            address,
+            MESSAGE
+        ));
+        assert!(!secp256k1_instruction_signs(&[], 2, address, MESSAGE));
+    }
+}
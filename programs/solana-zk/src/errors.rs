@@ -8,9 +8,330 @@ pub enum ZkError {
     #[msg("Invalid zkvm selector")]
     InvalidZkvmSelector,
 
+    // Never returned: a verifier program rejecting a proof aborts the transaction with its own error
+    // through the CPI. Kept so that the codes of the following variants do not change
     #[msg("ZK Proof verification failed")]
     FailedZkProofVerification,
 
     #[msg("zkVM Program frozen")]
     ZkvmProgramFrozen,
-}
\ No newline at end of file
+
+    #[msg("zkVM Program has passed its sunset slot")]
+    ZkvmProgramSunset,
+
+    #[msg("Sunset slot must be in the future")]
+    InvalidSunsetSlot,
+
+    #[msg("zkVM selector does not match the configured verifier program")]
+    ZkvmSelectorMismatch,
+
+    #[msg("zkVM Program was upgraded since it was configured")]
+    ZkvmProgramUpgraded,
+
+    #[msg("Malformed proof, verify instruction data is too short")]
+    MalformedProof,
+
+    #[msg("Not enough compute units left to verify the proof")]
+    ComputeBudgetExhausted,
+
+    #[msg("Output digest does not match the journal")]
+    OutputDigestMismatch,
+
+    #[msg("DCAP guest program is not approved for this selector")]
+    DcapGuestNotApproved,
+
+    #[msg("Malformed DCAP journal")]
+    MalformedDcapJournal,
+
+    #[msg("DCAP TCB status is not allowed by the policy")]
+    DcapTcbStatusNotAllowed,
+
+    #[msg("DCAP TCB evaluation data number is below the policy minimum")]
+    DcapTcbEvaluationTooOld,
+
+    #[msg("DCAP measurement is not allowed by the policy")]
+    DcapMeasurementNotAllowed,
+
+    #[msg("Too many measurements in the DCAP policy")]
+    TooManyDcapPolicyMeasurements,
+
+    #[msg("DCAP root CA does not match the collateral config")]
+    DcapRootCaMismatch,
+
+    #[msg("DCAP CRLs do not match the collateral config")]
+    DcapCrlMismatch,
+
+    #[msg("DCAP TCB evaluation data number is not accepted by the collateral config")]
+    DcapTcbEvaluationNotAccepted,
+
+    #[msg("DCAP collateral is older than the maximum collateral age")]
+    DcapCollateralExpired,
+
+    #[msg("Too many TCB evaluation data numbers in the collateral config")]
+    TooManyTcbEvaluationDataNumbers,
+
+    #[msg("Enclave key does not match the DCAP report data")]
+    EnclaveKeyMismatch,
+
+    #[msg("Enclave key has expired")]
+    EnclaveKeyExpired,
+
+    #[msg("Enclave key has not expired yet")]
+    EnclaveKeyNotExpired,
+
+    #[msg("No matching enclave signature instruction found")]
+    EnclaveSignatureNotFound,
+
+    #[msg("Enclave key was registered under a different DCAP policy")]
+    EnclaveKeyPolicyMismatch,
+
+    #[msg("Program vkey does not match the hybrid policy")]
+    HybridVkeyMismatch,
+
+    #[msg("Provided evidence does not satisfy the hybrid policy")]
+    HybridPolicyNotSatisfied,
+
+    #[msg("No preceding registry instruction verifies the expected proof")]
+    VerifyInstructionNotFound,
+
+    #[msg("The last CPI did not return a registry verification result")]
+    MissingVerificationResult,
+
+    #[msg("The callback program cannot be the registry")]
+    InvalidCallbackProgram,
+
+    #[msg("The callback was not signed by the registry callback authority")]
+    InvalidCallbackAuthority,
+
+    #[msg("Malformed state transition journal")]
+    MalformedStateTransitionJournal,
+
+    #[msg("Proof is not for the guest program of the verified state")]
+    StateGuestMismatch,
+
+    #[msg("State transition does not start from the current root")]
+    StateRootMismatch,
+
+    #[msg("State transition does not follow the current sequence")]
+    StateSequenceMismatch,
+
+    #[msg("Malformed freshness envelope")]
+    MalformedFreshnessEnvelope,
+
+    #[msg("Proof is not bound to a recent slot")]
+    StaleProof,
+
+    #[msg("Slot hash does not match the SlotHashes sysvar")]
+    SlotHashMismatch,
+
+    #[msg("Proof is bound to another caller")]
+    FreshnessCallerMismatch,
+
+    #[msg("Proof is bound to another consumer program")]
+    FreshnessConsumerMismatch,
+
+    #[msg("Nonce does not match the freshness envelope")]
+    FreshnessNonceMismatch,
+
+    #[msg("Journal does not decode to the expected output")]
+    MalformedJournal,
+
+    #[msg("Journal is too large to be stored")]
+    JournalTooLarge,
+
+    #[msg("Write is out of the bounds of the journal buffer")]
+    JournalBufferOutOfBounds,
+
+    #[msg("Journal is already verified and can no longer be written")]
+    JournalAlreadyVerified,
+
+    #[msg("Journal is not verified")]
+    JournalNotVerified,
+
+    #[msg("Journal was verified under another statement")]
+    JournalStatementMismatch,
+
+    #[msg("Journal field is not included in the verified Merkle root")]
+    InvalidJournalFieldProof,
+
+    #[msg("Verification accumulator is full")]
+    AccumulatorFull,
+
+    #[msg("Verification result was revoked")]
+    VerificationResultRevoked,
+
+    #[msg("Caller is not allowed to use this permissioned verifier")]
+    CallerNotAllowed,
+
+    #[msg("Admin multisig requires between 1 and the number of unique signers approvals")]
+    InvalidMultisigConfig,
+
+    #[msg("Signer is not a member of the admin multisig")]
+    NotMultisigSigner,
+
+    #[msg("Admin action requires an approved multisig proposal")]
+    MultisigApprovalRequired,
+
+    #[msg("Proposal does not authorize this admin action")]
+    ProposalActionMismatch,
+
+    #[msg("Proposal has not reached the multisig threshold")]
+    ProposalNotApproved,
+
+    #[msg("Proposal has already been executed")]
+    ProposalAlreadyExecuted,
+
+    #[msg("Proposal was created for a previous set of multisig signers")]
+    StaleProposal,
+
+    #[msg("Proposal timelock has not expired yet")]
+    TimelockNotExpired,
+
+    #[msg("Signer is not the admin of the registry")]
+    NotRegistryAdmin,
+
+    #[msg("Account belongs to a different registry")]
+    RegistryMismatch,
+
+    #[msg("ZKVM verifier program is pending activation with the canary proof")]
+    ZkvmProgramPending,
+
+    #[msg("ZKVM verifier program is not pending activation")]
+    ZkvmProgramNotPending,
+
+    #[msg("Canary proof is malformed or too large")]
+    InvalidCanaryProof,
+
+    #[msg("ZKVM verifier program accepted the known-bad canary proof")]
+    CanaryProofAccepted,
+
+    #[msg("Enclave key expiry does not fit a unix timestamp")]
+    InvalidEnclaveKeyExpiry,
+
+    #[msg("DCAP verification timestamp is in the future")]
+    DcapCollateralFromFuture,
+
+    #[msg("State transition is for another verified state")]
+    StateIdMismatch,
+
+    #[msg("Proof nonce is still within the freshness window")]
+    ProofNonceNotExpired,
+
+    #[msg("Callback data must start with an instruction discriminator")]
+    MalformedCallbackData,
+
+    #[msg("Proofs of a DCAP verifier must be verified through a DCAP instruction")]
+    DcapVerifierReserved,
+
+    #[msg("Verification accumulator shard is out of range")]
+    InvalidAccumulatorShard,
+
+    #[msg("Timelock delay is below the minimum of the admin action or overflows the slot")]
+    InvalidTimelockDelay,
+
+    #[msg("Admin action must be queued and wait for its timelock")]
+    AdminActionNotQueued,
+
+    #[msg("Registry is paused")]
+    RegistryPaused,
+
+    #[msg("Account is not a v0 account of the program")]
+    InvalidV0Account,
+
+    #[msg("Registry already has zkVM verifiers configured")]
+    RegistryNotEmpty,
+
+    #[msg("Signer is neither the admin nor the guardian of the registry")]
+    NotRegistryGuardian,
+}
+
+impl ZkError {
+    /// Every variant, in declaration order, so that error codes can be mapped back
+    pub const ALL: &'static [ZkError] = &[
+        ZkError::Unauthorized,
+        ZkError::InvalidZkvmSelector,
+        ZkError::FailedZkProofVerification,
+        ZkError::ZkvmProgramFrozen,
+        ZkError::ZkvmProgramSunset,
+        ZkError::InvalidSunsetSlot,
+        ZkError::ZkvmSelectorMismatch,
+        ZkError::ZkvmProgramUpgraded,
+        ZkError::MalformedProof,
+        ZkError::ComputeBudgetExhausted,
+        ZkError::OutputDigestMismatch,
+        ZkError::DcapGuestNotApproved,
+        ZkError::MalformedDcapJournal,
+        ZkError::DcapTcbStatusNotAllowed,
+        ZkError::DcapTcbEvaluationTooOld,
+        ZkError::DcapMeasurementNotAllowed,
+        ZkError::TooManyDcapPolicyMeasurements,
+        ZkError::DcapRootCaMismatch,
+        ZkError::DcapCrlMismatch,
+        ZkError::DcapTcbEvaluationNotAccepted,
+        ZkError::DcapCollateralExpired,
+        ZkError::TooManyTcbEvaluationDataNumbers,
+        ZkError::EnclaveKeyMismatch,
+        ZkError::EnclaveKeyExpired,
+        ZkError::EnclaveKeyNotExpired,
+        ZkError::EnclaveSignatureNotFound,
+        ZkError::EnclaveKeyPolicyMismatch,
+        ZkError::HybridVkeyMismatch,
+        ZkError::HybridPolicyNotSatisfied,
+        ZkError::VerifyInstructionNotFound,
+        ZkError::MissingVerificationResult,
+        ZkError::InvalidCallbackProgram,
+        ZkError::InvalidCallbackAuthority,
+        ZkError::MalformedStateTransitionJournal,
+        ZkError::StateGuestMismatch,
+        ZkError::StateRootMismatch,
+        ZkError::StateSequenceMismatch,
+        ZkError::MalformedFreshnessEnvelope,
+        ZkError::StaleProof,
+        ZkError::SlotHashMismatch,
+        ZkError::FreshnessCallerMismatch,
+        ZkError::FreshnessConsumerMismatch,
+        ZkError::FreshnessNonceMismatch,
+        ZkError::MalformedJournal,
+        ZkError::JournalTooLarge,
+        ZkError::JournalBufferOutOfBounds,
+        ZkError::JournalAlreadyVerified,
+        ZkError::JournalNotVerified,
+        ZkError::JournalStatementMismatch,
+        ZkError::InvalidJournalFieldProof,
+        ZkError::AccumulatorFull,
+        ZkError::VerificationResultRevoked,
+        ZkError::CallerNotAllowed,
+        ZkError::InvalidMultisigConfig,
+        ZkError::NotMultisigSigner,
+        ZkError::MultisigApprovalRequired,
+        ZkError::ProposalActionMismatch,
+        ZkError::ProposalNotApproved,
+        ZkError::ProposalAlreadyExecuted,
+        ZkError::StaleProposal,
+        ZkError::TimelockNotExpired,
+        ZkError::NotRegistryAdmin,
+        ZkError::RegistryMismatch,
+        ZkError::ZkvmProgramPending,
+        ZkError::ZkvmProgramNotPending,
+        ZkError::InvalidCanaryProof,
+        ZkError::CanaryProofAccepted,
+        ZkError::InvalidEnclaveKeyExpiry,
+        ZkError::DcapCollateralFromFuture,
+        ZkError::StateIdMismatch,
+        ZkError::ProofNonceNotExpired,
+        ZkError::MalformedCallbackData,
+        ZkError::DcapVerifierReserved,
+        ZkError::InvalidAccumulatorShard,
+        ZkError::InvalidTimelockDelay,
+        ZkError::AdminActionNotQueued,
+        ZkError::RegistryPaused,
+        ZkError::InvalidV0Account,
+        ZkError::RegistryNotEmpty,
+        ZkError::NotRegistryGuardian,
+    ];
+
+    /// Map a custom program error code back to the `ZkError` it was raised from
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.iter().copied().find(|e| u32::from(*e) == code)
+    }
+}
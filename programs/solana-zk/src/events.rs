@@ -0,0 +1,125 @@
+//! Events emitted by the registry for off-chain clients.
+
+use anchor_lang::prelude::*;
+
+use super::state::{AdminAction, HybridMode, Role};
+
+/// A verified state advanced to a new root
+#[event]
+pub struct StateAdvanced {
+    pub verified_state: Pubkey,
+    pub sequence: u64,
+    pub old_root: [u8; 32],
+    pub new_root: [u8; 32],
+    pub slot: u64,
+}
+
+/// A statement was verified and appended to the verification accumulator
+#[event]
+pub struct StatementVerified {
+    pub statement_hash: [u8; 32],
+    pub registry: Pubkey,
+    pub zkvm_selector: u64,
+    pub zkvm_program_id: Pubkey,
+    pub revocation_epoch: u64,
+    /// Signer, caller authority or top-level consumer program that directly requested the
+    /// verification, default if unidentified
+    pub caller: Pubkey,
+    /// Accumulator shard the statement was appended to, at `leaf_index`
+    pub shard: u64,
+    pub leaf_index: u64,
+    pub root: [u8; 32],
+}
+
+/// A statement was accepted under a hybrid policy, through the evidence flagged as verified
+#[event]
+pub struct HybridStatementVerified {
+    pub hybrid_policy: Pubkey,
+    pub mode: HybridMode,
+    pub output_digest: [u8; 32],
+    pub zk_verified: bool,
+    pub tee_verified: bool,
+    pub enclave_key: Pubkey,
+    pub slot: u64,
+}
+
+/// Results of a verifier recorded before `revocation_epoch` were revoked
+#[event]
+pub struct VerifierResultsRevoked {
+    pub registry: Pubkey,
+    pub zkvm_selector: u64,
+    pub zkvm_program_id: Pubkey,
+    pub revocation_epoch: u64,
+}
+
+/// An admin proposal reached the multisig threshold and can be executed from `eta_slot`
+#[event]
+pub struct AdminActionQueued {
+    pub proposal_id: u64,
+    pub action: AdminAction,
+    pub eta_slot: u64,
+}
+
+/// A queued admin proposal was cancelled before execution
+#[event]
+pub struct AdminActionCancelled {
+    pub proposal_id: u64,
+    pub action: AdminAction,
+    pub cancelled_by: Pubkey,
+}
+
+/// A queued admin proposal was executed
+#[event]
+pub struct AdminActionExecuted {
+    pub proposal_id: u64,
+    pub action: AdminAction,
+}
+
+/// A registry admin queued an admin action without the multisig, it can be executed from `eta_slot`
+#[event]
+pub struct QueuedAdminActionCreated {
+    pub registry: Pubkey,
+    pub action: AdminAction,
+    pub eta_slot: u64,
+    pub queued_by: Pubkey,
+}
+
+/// An admin action queued without the multisig was cancelled before execution
+#[event]
+pub struct QueuedAdminActionCancelled {
+    pub registry: Pubkey,
+    pub action: AdminAction,
+    pub cancelled_by: Pubkey,
+}
+
+/// A verifier was frozen through the emergency path, bypassing the timelock
+#[event]
+pub struct VerifierEmergencyFrozen {
+    pub registry: Pubkey,
+    pub zkvm_selector: u64,
+    pub zkvm_program_id: Pubkey,
+    pub frozen_by: Pubkey,
+}
+
+/// A registry was paused, every verification in it is refused until it is unpaused
+#[event]
+pub struct RegistryPaused {
+    pub registry: Pubkey,
+    pub paused_by: Pubkey,
+}
+
+/// A role was granted to `member`
+#[event]
+pub struct RoleGranted {
+    pub member: Pubkey,
+    pub role: Role,
+    pub granted_by: Pubkey,
+}
+
+/// The role of `member` was revoked
+#[event]
+pub struct RoleRevoked {
+    pub member: Pubkey,
+    pub role: Role,
+    pub revoked_by: Pubkey,
+}
@@ -0,0 +1,207 @@
+//! Freshness envelope binding a proof to a recent slot, a consumer program, a caller and a nonce,
+//! so that a verified statement cannot be replayed later or submitted by someone else.
+//!
+//! The envelope prefixes the journal, integers little endian:
+//!
+//! | field            | size |
+//! |------------------|------|
+//! | slot             | 8    |
+//! | slot hash        | 32   |
+//! | consumer program | 32   |
+//! | caller           | 32   |
+//! | nonce            | 32   |
+//!
+//! A zero slot hash only binds the slot, a default consumer program binds no consumer.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+use super::errors::ZkError;
+
+/// Length of the freshness envelope at the start of the journal
+pub const FRESHNESS_ENVELOPE_LEN: usize = 8 + 32 + 32 + 32 + 32;
+
+/// Oldest slot accepted without a slot hash, matching the history kept by the SlotHashes sysvar
+pub const MAX_FRESHNESS_AGE_SLOTS: u64 = 512;
+
+// Size of an entry of the SlotHashes sysvar: slot and hash
+const SLOT_HASH_ENTRY_LEN: usize = 8 + 32;
+
+/// Solana context a proof is bound to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FreshnessEnvelope {
+    pub slot: u64,
+    pub slot_hash: [u8; 32],
+    pub consumer_program: Pubkey,
+    pub caller: Pubkey,
+    pub nonce: [u8; 32],
+}
+
+impl FreshnessEnvelope {
+    /// Decode the envelope at the start of the journal
+    pub fn decode(journal: &[u8]) -> Result<Self> {
+        if journal.len() < FRESHNESS_ENVELOPE_LEN {
+            return err!(ZkError::MalformedFreshnessEnvelope);
+        }
+
+        Ok(Self {
+            slot: u64::from_le_bytes(journal[..8].try_into().unwrap()),
+            slot_hash: journal[8..40].try_into().unwrap(),
+            consumer_program: Pubkey::new_from_array(journal[40..72].try_into().unwrap()),
+            caller: Pubkey::new_from_array(journal[72..104].try_into().unwrap()),
+            nonce: journal[104..136].try_into().unwrap(),
+        })
+    }
+
+    /// Encode the envelope, to be prefixed to the journal by the guest program
+    pub fn encode(&self) -> [u8; FRESHNESS_ENVELOPE_LEN] {
+        let mut envelope = [0u8; FRESHNESS_ENVELOPE_LEN];
+        envelope[..8].copy_from_slice(&self.slot.to_le_bytes());
+        envelope[8..40].copy_from_slice(&self.slot_hash);
+        envelope[40..72].copy_from_slice(self.consumer_program.as_ref());
+        envelope[72..104].copy_from_slice(self.caller.as_ref());
+        envelope[104..].copy_from_slice(&self.nonce);
+        envelope
+    }
+
+    /// Check the envelope against the current slot, the SlotHashes sysvar, the program of the
+    /// current top-level instruction and the signing caller
+    pub fn check(
+        &self,
+        clock: &Clock,
+        slot_hashes: &AccountInfo,
+        instructions_sysvar: &AccountInfo,
+        caller: &Pubkey,
+    ) -> Result<()> {
+        if &self.caller != caller {
+            return err!(ZkError::FreshnessCallerMismatch);
+        }
+
+        if self.consumer_program != Pubkey::default() {
+            let current_index = load_current_index_checked(instructions_sysvar)?;
+            let current = load_instruction_at_checked(current_index as usize, instructions_sysvar)?;
+            if current.program_id != self.consumer_program {
+                return err!(ZkError::FreshnessConsumerMismatch);
+            }
+        }
+
+        if self.slot > clock.slot || clock.slot - self.slot > MAX_FRESHNESS_AGE_SLOTS {
+            return err!(ZkError::StaleProof);
+        }
+
+        if self.slot_hash != [0u8; 32] {
+            match find_slot_hash(&slot_hashes.try_borrow_data()?, self.slot) {
+                Some(slot_hash) if slot_hash == self.slot_hash => {}
+                Some(_) => return err!(ZkError::SlotHashMismatch),
+                None => return err!(ZkError::StaleProof),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// The SlotHashes sysvar is too large to deserialize on-chain, binary search its entries instead.
+// It holds a u64 entry count followed by (slot, hash) entries in descending slot order.
+fn find_slot_hash(data: &[u8], slot: u64) -> Option<[u8; 32]> {
+    let count = u64::from_le_bytes(data.get(..8)?.try_into().ok()?) as usize;
+    let entry = |i: usize| -> Option<(u64, [u8; 32])> {
+        let start = 8 + i * SLOT_HASH_ENTRY_LEN;
+        let entry = data.get(start..start + SLOT_HASH_ENTRY_LEN)?;
+        Some((
+            u64::from_le_bytes(entry[..8].try_into().unwrap()),
+            entry[8..].try_into().unwrap(),
+        ))
+    };
+
+    let (mut low, mut high) = (0, count);
+    while low < high {
+        let mid = (low + high) / 2;
+        let (entry_slot, hash) = entry(mid)?;
+        match entry_slot.cmp(&slot) {
+            std::cmp::Ordering::Equal => return Some(hash),
+            std::cmp::Ordering::Greater => low = mid + 1,
+            std::cmp::Ordering::Less => high = mid,
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope() -> FreshnessEnvelope {
+        FreshnessEnvelope {
+            slot: 1_234,
+            slot_hash: [1; 32],
+            consumer_program: Pubkey::new_from_array([2; 32]),
+            caller: Pubkey::new_from_array([3; 32]),
+            nonce: [4; 32],
+        }
+    }
+
+    // SlotHashes sysvar data with entries in descending slot order
+    fn slot_hashes(slots: &[u64]) -> Vec<u8> {
+        let mut data = (slots.len() as u64).to_le_bytes().to_vec();
+        for slot in slots {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[*slot as u8; 32]);
+        }
+        data
+    }
+
+    #[test]
+    fn envelope_round_trips_at_the_start_of_the_journal() {
+        let mut journal = envelope().encode().to_vec();
+        journal.extend_from_slice(b"guest output");
+        assert_eq!(FreshnessEnvelope::decode(&journal).unwrap(), envelope());
+    }
+
+    #[test]
+    fn envelope_fields_are_at_documented_offsets() {
+        let encoded = envelope().encode();
+        assert_eq!(encoded[..8], 1_234u64.to_le_bytes());
+        assert_eq!(encoded[8..40], [1; 32]);
+        assert_eq!(encoded[40..72], [2; 32]);
+        assert_eq!(encoded[72..104], [3; 32]);
+        assert_eq!(encoded[104..], [4; 32]);
+    }
+
+    #[test]
+    fn envelope_rejects_short_journal() {
+        let encoded = envelope().encode();
+        assert_eq!(
+            FreshnessEnvelope::decode(&encoded[..FRESHNESS_ENVELOPE_LEN - 1]).unwrap_err(),
+            error!(ZkError::MalformedFreshnessEnvelope)
+        );
+    }
+
+    #[test]
+    fn slot_hash_search_finds_every_entry() {
+        let slots = [109, 107, 104, 103, 100];
+        let data = slot_hashes(&slots);
+        for slot in slots {
+            assert_eq!(find_slot_hash(&data, slot), Some([slot as u8; 32]));
+        }
+    }
+
+    #[test]
+    fn slot_hash_search_misses_skipped_and_out_of_range_slots() {
+        let data = slot_hashes(&[109, 107, 104, 103, 100]);
+        for slot in [99, 101, 105, 108, 110] {
+            assert_eq!(find_slot_hash(&data, slot), None);
+        }
+        assert_eq!(find_slot_hash(&slot_hashes(&[]), 100), None);
+    }
+
+    #[test]
+    fn slot_hash_search_rejects_truncated_data() {
+        let data = slot_hashes(&[109, 107, 104]);
+        assert_eq!(find_slot_hash(&data[..data.len() - 1], 104), None);
+        assert_eq!(find_slot_hash(&data[..4], 109), None);
+    }
+}
@@ -1,5 +1,15 @@
-use super::state::{Counter, ZkvmVerifier};
+use super::state::{
+    AdminAction, AdminMultisig, AdminProposal, AllowedCaller, AuditLog, CanaryProof, Counter,
+    DcapCollateralConfig, DcapGuest, DcapPolicy, EnclaveKey, EnclaveKeyType, HybridPolicy,
+    ProofNonce, QueuedAdminAction, Registry, RoleAssignment, VerificationAccumulator,
+    VerifiedJournal, VerifiedState, ZkvmVerifier, ZkvmVerifierStats, default_registry_address,
+    DEFAULT_REGISTRY_ID,
+};
+use super::errors::ZkError;
+use super::CALLBACK_AUTHORITY_SEED;
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions as instructions_sysvar;
+use anchor_lang::solana_program::sysvar::slot_hashes;
 use anchor_lang::solana_program::{bpf_loader_upgradeable, system_program};
 
 #[derive(Accounts)]
@@ -8,13 +18,31 @@ pub struct Initialize<'info> {
     pub payer: Signer<'info>,
 
     #[account(
-        init, 
-        payer = payer, 
-        space = 8 + 8,
-        seeds = [b"counter"],
+        init,
+        payer = payer,
+        space = 8 + Registry::INIT_SPACE,
+        seeds = [b"registry", DEFAULT_REGISTRY_ID.to_le_bytes().as_ref()],
         bump,
     )]
-    pub counter: Account<'info, Counter>,
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<VerificationAccumulator>(),
+        seeds = [b"verification_accumulator", 0u64.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub verification_accumulator: AccountLoader<'info, VerificationAccumulator>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<AuditLog>(),
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
 
     pub system_program: Program<'info, System>,
 }
@@ -28,15 +56,27 @@ pub struct AddZkvmVerifier<'info> {
     #[account(mut, signer)]
     pub owner: Signer<'info>,
 
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
     #[account(mut)]
-    pub counter: Account<'info, Counter>,
+    pub registry: Account<'info, Registry>,
 
     #[account(
         init,
         payer = owner,
-        space = 8 + 8 + 32 + 1,
+        space = 8 + ZkvmVerifier::INIT_SPACE,
         seeds = [
             b"zkvm_verifier",
+            registry.key().as_ref(),
             zkvm_selector.to_le_bytes().as_ref(),
             zkvm_verifier_program.key().as_ref(),
         ],
@@ -44,6 +84,18 @@ pub struct AddZkvmVerifier<'info> {
     )]
     pub zkvm_verifier_account: Account<'info, ZkvmVerifier>,
 
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + ZkvmVerifierStats::INIT_SPACE,
+        seeds = [
+            b"zkvm_verifier_stats",
+            zkvm_verifier_account.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub zkvm_verifier_stats: Account<'info, ZkvmVerifierStats>,
+
     /// CHECK: This is the program data account for the current program
     #[account(
         constraint = program_data.key() == Pubkey::find_program_address(
@@ -56,6 +108,89 @@ pub struct AddZkvmVerifier<'info> {
     /// CHECK: This is the address of the ZKVM Verifier Program. Currently, there isn't any defined standards to structure the program.
     pub zkvm_verifier_program: AccountInfo<'info>,
 
+    /// CHECK: This is the program data account of the ZKVM Verifier Program, used to detect upgrades. It is empty for non-upgradeable programs.
+    #[account(
+        constraint = zkvm_verifier_program_data.key() == Pubkey::find_program_address(
+            &[zkvm_verifier_program.key().as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub zkvm_verifier_program_data: AccountInfo<'info>,
+
+    /// CHECK: This is the admin multisig, once initialized admin actions require an approved proposal
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+
+    /// Approved proposal for this admin action, required once the admin multisig is initialized
+    #[account(mut)]
+    pub admin_proposal: Option<Account<'info, AdminProposal>>,
+
+    /// Queued admin action whose timelock expired, required before the admin multisig is
+    /// initialized for every action but freezing
+    #[account(mut, close = owner, has_one = registry)]
+    pub queued_admin_action: Option<Account<'info, QueuedAdminAction>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    shard: u64
+)]
+pub struct InitVerificationAccumulator<'info> {
+    #[account(mut, signer)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<VerificationAccumulator>(),
+        seeds = [b"verification_accumulator", shard.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub verification_accumulator: AccountLoader<'info, VerificationAccumulator>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitVerifierStats<'info> {
+    #[account(mut, signer)]
+    pub payer: Signer<'info>,
+
+    pub zkvm_verifier_account: Account<'info, ZkvmVerifier>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ZkvmVerifierStats::INIT_SPACE,
+        seeds = [
+            b"zkvm_verifier_stats",
+            zkvm_verifier_account.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub zkvm_verifier_stats: Account<'info, ZkvmVerifierStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitAuditLog<'info> {
+    #[account(mut, signer)]
+    pub payer: Signer<'info>,
+
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<AuditLog>(),
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -67,10 +202,24 @@ pub struct UpdateZkvmVerifierConfig<'info> {
     #[account(mut, signer)]
     pub owner: Signer<'info>,
 
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    pub registry: Account<'info, Registry>,
+
     #[account(
         mut,
         seeds = [
             b"zkvm_verifier",
+            registry.key().as_ref(),
             zkvm_selector.to_le_bytes().as_ref(),
             zkvm_verifier_program.key().as_ref(),
         ],
@@ -89,6 +238,28 @@ pub struct UpdateZkvmVerifierConfig<'info> {
 
     /// CHECK: This is the address of the ZKVM Verifier Program. Currently, there isn't any defined standards to structure the program.
     pub zkvm_verifier_program: AccountInfo<'info>,
+
+    /// CHECK: This is the program data account of the ZKVM Verifier Program, used to detect upgrades. It is empty for non-upgradeable programs.
+    #[account(
+        constraint = zkvm_verifier_program_data.key() == Pubkey::find_program_address(
+            &[zkvm_verifier_program.key().as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub zkvm_verifier_program_data: AccountInfo<'info>,
+
+    /// CHECK: This is the admin multisig, once initialized admin actions require an approved proposal
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+
+    /// Approved proposal for this admin action, required once the admin multisig is initialized
+    #[account(mut)]
+    pub admin_proposal: Option<Account<'info, AdminProposal>>,
+
+    /// Queued admin action whose timelock expired, required before the admin multisig is
+    /// initialized for every action but freezing
+    #[account(mut, close = owner, has_one = registry)]
+    pub queued_admin_action: Option<Account<'info, QueuedAdminAction>>,
 }
 
 #[derive(Accounts)]
@@ -100,6 +271,7 @@ pub struct VerifyZkProof<'info> {
     #[account(
         seeds = [
             b"zkvm_verifier",
+            zkvm_verifier_account.registry.as_ref(),
             zkvm_selector.to_le_bytes().as_ref(),
             zkvm_verifier_program.key().as_ref(),
         ],
@@ -107,9 +279,1608 @@ pub struct VerifyZkProof<'info> {
     )]
     pub zkvm_verifier_account: Account<'info, ZkvmVerifier>,
 
+    /// Registry of the verifier, its verifications are refused while it is paused
+    #[account(address = zkvm_verifier_account.registry)]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"zkvm_verifier_stats",
+            zkvm_verifier_account.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub zkvm_verifier_stats: Account<'info, ZkvmVerifierStats>,
+
+    /// Any shard of the verification accumulator, they are all created by the registry
+    #[account(mut)]
+    pub verification_accumulator: AccountLoader<'info, VerificationAccumulator>,
+
     /// CHECK: This is the address of the ZKVM Verifier Program. Currently, there isn't any defined standards to structure the program.
     pub zkvm_verifier_program: AccountInfo<'info>,
 
+    /// CHECK: This is the program data account of the ZKVM Verifier Program, used to detect upgrades. It is empty for non-upgradeable programs.
+    #[account(
+        constraint = zkvm_verifier_program_data.key() == Pubkey::find_program_address(
+            &[zkvm_verifier_program.key().as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub zkvm_verifier_program_data: AccountInfo<'info>,
+
     #[account(address = system_program::ID)]
     pub system_program: Program<'info, System>,
+
+    /// Allowlist entry of the caller, required by permissioned verifiers
+    pub allowed_caller: Option<Account<'info, AllowedCaller>>,
+
+    /// Signer identifying the caller, a wallet when the registry is invoked directly or the
+    /// `CALLER_AUTHORITY_SEED` PDA of the consumer program under CPI
+    pub caller_signer: Option<Signer<'info>>,
+
+    /// CHECK: This is the instructions sysvar, used to identify the consumer program when invoked through CPI
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: Option<AccountInfo<'info>>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64,
+    zk_verify_instruction_data: Vec<u8>
+)]
+pub struct VerifyZkProofAndCall<'info> {
+    pub verify: VerifyZkProof<'info>,
+
+    /// CHECK: This is the consumer program called back with the verified statement, it cannot be the registry itself.
+    #[account(
+        executable,
+        constraint = callback_program.key() != crate::ID @ ZkError::InvalidCallbackProgram
+    )]
+    pub callback_program: AccountInfo<'info>,
+
+    /// CHECK: This PDA signs the callback so that the callee can tell it follows a successful verification.
+    #[account(
+        seeds = [CALLBACK_AUTHORITY_SEED],
+        bump,
+    )]
+    pub callback_authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64,
+    guest_vkey: [u8; 32]
+)]
+pub struct AddDcapGuest<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DcapGuest::INIT_SPACE,
+        seeds = [
+            b"dcap_guest",
+            registry.key().as_ref(),
+            zkvm_selector.to_le_bytes().as_ref(),
+            guest_vkey.as_ref(),
+        ],
+        bump,
+    )]
+    pub dcap_guest: Account<'info, DcapGuest>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: This is the admin multisig, once initialized admin actions require an approved proposal
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+
+    /// Approved proposal for this admin action, required once the admin multisig is initialized
+    #[account(mut)]
+    pub admin_proposal: Option<Account<'info, AdminProposal>>,
+
+    /// Queued admin action whose timelock expired, required before the admin multisig is
+    /// initialized for every action but freezing
+    #[account(mut, close = owner, has_one = registry)]
+    pub queued_admin_action: Option<Account<'info, QueuedAdminAction>>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64,
+    guest_vkey: [u8; 32]
+)]
+pub struct RemoveDcapGuest<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"dcap_guest",
+            registry.key().as_ref(),
+            zkvm_selector.to_le_bytes().as_ref(),
+            guest_vkey.as_ref(),
+        ],
+        bump,
+    )]
+    pub dcap_guest: Account<'info, DcapGuest>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    /// CHECK: This is the admin multisig, once initialized admin actions require an approved proposal
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+
+    /// Approved proposal for this admin action, required once the admin multisig is initialized
+    #[account(mut)]
+    pub admin_proposal: Option<Account<'info, AdminProposal>>,
+
+    /// Queued admin action whose timelock expired, required before the admin multisig is
+    /// initialized for every action but freezing
+    #[account(mut, close = owner, has_one = registry)]
+    pub queued_admin_action: Option<Account<'info, QueuedAdminAction>>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    policy_id: u64
+)]
+pub struct InitDcapPolicy<'info> {
+    #[account(mut, signer)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + DcapPolicy::INIT_SPACE,
+        seeds = [
+            b"dcap_policy",
+            authority.key().as_ref(),
+            policy_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub dcap_policy: Account<'info, DcapPolicy>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateDcapPolicy<'info> {
+    #[account(signer)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority)]
+    pub dcap_policy: Account<'info, DcapPolicy>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64,
+    zk_verify_instruction_data: Vec<u8>,
+    guest_vkey: [u8; 32]
+)]
+pub struct VerifyDcapGuestProof<'info> {
+    pub verify: VerifyZkProof<'info>,
+
+    #[account(
+        seeds = [
+            b"dcap_guest",
+            verify.zkvm_verifier_account.registry.as_ref(),
+            zkvm_selector.to_le_bytes().as_ref(),
+            guest_vkey.as_ref(),
+        ],
+        bump,
+    )]
+    pub dcap_guest: Account<'info, DcapGuest>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64,
+    zk_verify_instruction_prefix: Vec<u8>,
+    guest_vkey: [u8; 32]
+)]
+pub struct VerifyDcapProof<'info> {
+    pub verify: VerifyZkProof<'info>,
+
+    #[account(
+        seeds = [
+            b"dcap_guest",
+            verify.zkvm_verifier_account.registry.as_ref(),
+            zkvm_selector.to_le_bytes().as_ref(),
+            guest_vkey.as_ref(),
+        ],
+        bump,
+    )]
+    pub dcap_guest: Account<'info, DcapGuest>,
+
+    #[account(
+        seeds = [
+            b"dcap_policy",
+            dcap_policy.authority.as_ref(),
+            dcap_policy.policy_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub dcap_policy: Account<'info, DcapPolicy>,
+
+    #[account(
+        seeds = [b"dcap_collateral"],
+        bump,
+    )]
+    pub dcap_collateral_config: Account<'info, DcapCollateralConfig>,
+
+    #[account(signer)]
+    pub authority: Signer<'info>,
+
+    /// Journal of the DCAP guest program, too large for the transaction and uploaded beforehand
+    /// with `init_journal_buffer` and `write_journal_buffer`
+    #[account(mut, has_one = authority)]
+    pub verified_journal: Account<'info, VerifiedJournal>,
+}
+
+#[derive(Accounts)]
+pub struct InitDcapCollateralConfig<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", default_registry_address().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + DcapCollateralConfig::INIT_SPACE,
+        seeds = [b"dcap_collateral"],
+        bump,
+    )]
+    pub dcap_collateral_config: Account<'info, DcapCollateralConfig>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: This is the admin multisig, once initialized admin actions require an approved proposal
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+
+    /// Approved proposal for this admin action, required once the admin multisig is initialized
+    #[account(mut)]
+    pub admin_proposal: Option<Account<'info, AdminProposal>>,
+
+    /// Queued admin action whose timelock expired, required before the admin multisig is
+    /// initialized, program actions are queued on the default registry
+    #[account(
+        mut,
+        close = owner,
+        constraint = queued_admin_action.registry == default_registry_address() @ ZkError::RegistryMismatch,
+    )]
+    pub queued_admin_action: Option<Account<'info, QueuedAdminAction>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateDcapCollateralConfig<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", default_registry_address().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    #[account(
+        mut,
+        seeds = [b"dcap_collateral"],
+        bump,
+    )]
+    pub dcap_collateral_config: Account<'info, DcapCollateralConfig>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    /// CHECK: This is the admin multisig, once initialized admin actions require an approved proposal
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+
+    /// Approved proposal for this admin action, required once the admin multisig is initialized
+    #[account(mut)]
+    pub admin_proposal: Option<Account<'info, AdminProposal>>,
+
+    /// Queued admin action whose timelock expired, required before the admin multisig is
+    /// initialized, program actions are queued on the default registry
+    #[account(
+        mut,
+        close = owner,
+        constraint = queued_admin_action.registry == default_registry_address() @ ZkError::RegistryMismatch,
+    )]
+    pub queued_admin_action: Option<Account<'info, QueuedAdminAction>>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64,
+    zk_verify_instruction_prefix: Vec<u8>,
+    guest_vkey: [u8; 32],
+    key_type: EnclaveKeyType,
+    key: [u8; 32]
+)]
+pub struct RegisterEnclaveKey<'info> {
+    #[account(mut, signer)]
+    pub payer: Signer<'info>,
+
+    pub dcap: VerifyDcapProof<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + EnclaveKey::INIT_SPACE,
+        seeds = [
+            b"enclave_key",
+            dcap.dcap_policy.key().as_ref(),
+            &[key_type as u8],
+            key.as_ref(),
+        ],
+        bump,
+    )]
+    pub enclave_key: Account<'info, EnclaveKey>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEnclaveKey<'info> {
+    #[account(mut, close = payer, has_one = payer)]
+    pub enclave_key: Account<'info, EnclaveKey>,
+
+    /// CHECK: This is the account that paid for the enclave key and receives the rent back
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    policy_id: u64
+)]
+pub struct InitHybridPolicy<'info> {
+    #[account(mut, signer)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + HybridPolicy::INIT_SPACE,
+        seeds = [
+            b"hybrid_policy",
+            authority.key().as_ref(),
+            policy_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub hybrid_policy: Account<'info, HybridPolicy>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateHybridPolicy<'info> {
+    #[account(signer)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority)]
+    pub hybrid_policy: Account<'info, HybridPolicy>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64,
+    zk_verify_instruction_data: Vec<u8>
+)]
+pub struct VerifyHybrid<'info> {
+    pub hybrid_policy: Account<'info, HybridPolicy>,
+
+    pub verify: VerifyZkProof<'info>,
+
+    pub enclave_key: Option<Account<'info, EnclaveKey>>,
+
+    /// CHECK: This is the instructions sysvar, used to find the enclave signature instruction
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyHybridEnclave<'info> {
+    pub hybrid_policy: Account<'info, HybridPolicy>,
+
+    /// Registry of the hybrid policy, its statements are refused while it is paused
+    #[account(address = hybrid_policy.registry)]
+    pub registry: Account<'info, Registry>,
+
+    pub enclave_key: Account<'info, EnclaveKey>,
+
+    /// CHECK: This is the instructions sysvar, used to find the enclave signature instruction
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    state_id: u64
+)]
+pub struct InitVerifiedState<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + VerifiedState::INIT_SPACE,
+        seeds = [
+            b"verified_state",
+            owner.key().as_ref(),
+            state_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub verified_state: Account<'info, VerifiedState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64,
+    zk_verify_instruction_data: Vec<u8>
+)]
+pub struct AdvanceState<'info> {
+    pub verify: VerifyZkProof<'info>,
+
+    #[account(mut)]
+    pub verified_state: Account<'info, VerifiedState>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64,
+    zk_verify_instruction_data: Vec<u8>,
+    journal: Vec<u8>,
+    nonce: [u8; 32]
+)]
+pub struct VerifyFreshZkProof<'info> {
+    pub verify: VerifyZkProof<'info>,
+
+    #[account(mut, signer)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        init,
+        payer = caller,
+        space = 8 + ProofNonce::INIT_SPACE,
+        seeds = [
+            b"proof_nonce",
+            caller.key().as_ref(),
+            nonce.as_ref(),
+        ],
+        bump,
+    )]
+    pub proof_nonce: Account<'info, ProofNonce>,
+
+    /// CHECK: This is the SlotHashes sysvar, used to check the slot hash of the freshness envelope
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: AccountInfo<'info>,
+
+    /// CHECK: This is the instructions sysvar, used to find the consumer program
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    nonce: [u8; 32]
+)]
+pub struct CloseProofNonce<'info> {
+    #[account(mut, signer)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        close = caller,
+        seeds = [
+            b"proof_nonce",
+            caller.key().as_ref(),
+            nonce.as_ref(),
+        ],
+        bump,
+    )]
+    pub proof_nonce: Account<'info, ProofNonce>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    buffer_id: u64,
+    journal_len: u32
+)]
+pub struct InitJournalBuffer<'info> {
+    #[account(mut, signer)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VerifiedJournal::INIT_SPACE + journal_len as usize,
+        seeds = [
+            b"verified_journal",
+            authority.key().as_ref(),
+            buffer_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub verified_journal: Account<'info, VerifiedJournal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WriteJournalBuffer<'info> {
+    #[account(signer)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority)]
+    pub verified_journal: Account<'info, VerifiedJournal>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64,
+    zk_verify_instruction_prefix: Vec<u8>
+)]
+pub struct VerifyJournalBuffer<'info> {
+    pub verify: VerifyZkProof<'info>,
+
+    #[account(signer)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, has_one = authority)]
+    pub verified_journal: Account<'info, VerifiedJournal>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64,
+    zk_verify_instruction_prefix: Vec<u8>,
+    program_vkey: [u8; 32],
+    journal: Vec<u8>,
+    buffer_id: u64
+)]
+pub struct StoreVerifiedJournal<'info> {
+    pub verify: VerifyZkProof<'info>,
+
+    #[account(mut, signer)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VerifiedJournal::INIT_SPACE + journal.len(),
+        seeds = [
+            b"verified_journal",
+            authority.key().as_ref(),
+            buffer_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub verified_journal: Account<'info, VerifiedJournal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVerifiedJournal<'info> {
+    #[account(mut, signer)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, close = authority, has_one = authority)]
+    pub verified_journal: Account<'info, VerifiedJournal>,
+}
+
+#[derive(Accounts)]
+pub struct ProveJournalField<'info> {
+    pub verified_journal: Account<'info, VerifiedJournal>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64,
+    caller: Pubkey
+)]
+pub struct AddAllowedCaller<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    pub registry: Account<'info, Registry>,
+
+    #[account(has_one = registry @ ZkError::RegistryMismatch)]
+    pub zkvm_verifier_account: Account<'info, ZkvmVerifier>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + AllowedCaller::INIT_SPACE,
+        seeds = [
+            b"allowed_caller",
+            zkvm_verifier_account.key().as_ref(),
+            caller.as_ref(),
+        ],
+        bump,
+    )]
+    pub allowed_caller: Account<'info, AllowedCaller>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64,
+    caller: Pubkey
+)]
+pub struct RemoveAllowedCaller<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    pub registry: Account<'info, Registry>,
+
+    #[account(has_one = registry @ ZkError::RegistryMismatch)]
+    pub zkvm_verifier_account: Account<'info, ZkvmVerifier>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"allowed_caller",
+            zkvm_verifier_account.key().as_ref(),
+            caller.as_ref(),
+        ],
+        bump,
+    )]
+    pub allowed_caller: Account<'info, AllowedCaller>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64
+)]
+pub struct RemoveZkvmVerifier<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"zkvm_verifier",
+            registry.key().as_ref(),
+            zkvm_selector.to_le_bytes().as_ref(),
+            zkvm_verifier_program.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub zkvm_verifier_account: Account<'info, ZkvmVerifier>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"zkvm_verifier_stats",
+            zkvm_verifier_account.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub zkvm_verifier_stats: Account<'info, ZkvmVerifierStats>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    /// CHECK: This is the address of the ZKVM Verifier Program, only used to derive the verifier account
+    pub zkvm_verifier_program: AccountInfo<'info>,
+
+    /// CHECK: This is the admin multisig, once initialized admin actions require an approved proposal
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+
+    /// Approved proposal for this admin action, required once the admin multisig is initialized
+    #[account(mut)]
+    pub admin_proposal: Option<Account<'info, AdminProposal>>,
+
+    /// Queued admin action whose timelock expired, required before the admin multisig is
+    /// initialized for every action but freezing
+    #[account(mut, close = owner, has_one = registry)]
+    pub queued_admin_action: Option<Account<'info, QueuedAdminAction>>,
+}
+
+#[derive(Accounts)]
+pub struct InitAdminMultisig<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", default_registry_address().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + AdminMultisig::INIT_SPACE,
+        seeds = [b"admin_multisig"],
+        bump,
+    )]
+    pub admin_multisig: Account<'info, AdminMultisig>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAdminProposal<'info> {
+    #[account(mut, signer)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_multisig"],
+        bump,
+    )]
+    pub admin_multisig: Account<'info, AdminMultisig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + AdminProposal::INIT_SPACE,
+        seeds = [
+            b"admin_proposal",
+            admin_multisig.proposal_count.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub admin_proposal: Account<'info, AdminProposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveAdminProposal<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"admin_multisig"],
+        bump,
+    )]
+    pub admin_multisig: Account<'info, AdminMultisig>,
+
+    #[account(mut)]
+    pub admin_proposal: Account<'info, AdminProposal>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAdminMultisig<'info> {
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_multisig"],
+        bump,
+    )]
+    pub admin_multisig: Account<'info, AdminMultisig>,
+
+    #[account(mut)]
+    pub admin_proposal: Account<'info, AdminProposal>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", default_registry_address().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+}
+
+#[derive(Accounts)]
+pub struct CancelAdminProposal<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"admin_multisig"],
+        bump,
+    )]
+    pub admin_multisig: Account<'info, AdminMultisig>,
+
+    #[account(
+        mut,
+        close = proposer,
+        has_one = proposer,
+    )]
+    pub admin_proposal: Account<'info, AdminProposal>,
+
+    /// CHECK: This is the proposer of the proposal, receiving the rent back
+    #[account(mut)]
+    pub proposer: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    action: AdminAction
+)]
+pub struct QueueAdminAction<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + QueuedAdminAction::INIT_SPACE,
+        seeds = [
+            b"queued_admin_action",
+            registry.key().as_ref(),
+            action.digest().as_ref(),
+        ],
+        bump,
+    )]
+    pub queued_admin_action: Account<'info, QueuedAdminAction>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    /// CHECK: This is the admin multisig, once initialized admin actions require an approved proposal
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelQueuedAdminAction<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = registry,
+    )]
+    pub queued_admin_action: Account<'info, QueuedAdminAction>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    member: Pubkey
+)]
+pub struct GrantRole<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", default_registry_address().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + RoleAssignment::INIT_SPACE,
+        seeds = [
+            b"role",
+            member.as_ref(),
+        ],
+        bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: This is the admin multisig, once initialized admin actions require an approved proposal
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+
+    /// Approved proposal for this admin action, required once the admin multisig is initialized
+    #[account(mut)]
+    pub admin_proposal: Option<Account<'info, AdminProposal>>,
+
+    /// Queued admin action whose timelock expired, required before the admin multisig is
+    /// initialized, program actions are queued on the default registry
+    #[account(
+        mut,
+        close = owner,
+        constraint = queued_admin_action.registry == default_registry_address() @ ZkError::RegistryMismatch,
+    )]
+    pub queued_admin_action: Option<Account<'info, QueuedAdminAction>>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    member: Pubkey
+)]
+pub struct RevokeRole<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", default_registry_address().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"role",
+            member.as_ref(),
+        ],
+        bump,
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    /// CHECK: This is the admin multisig, once initialized admin actions require an approved proposal
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+
+    /// Approved proposal for this admin action, required once the admin multisig is initialized
+    #[account(mut)]
+    pub admin_proposal: Option<Account<'info, AdminProposal>>,
+
+    /// Queued admin action whose timelock expired, required before the admin multisig is
+    /// initialized, program actions are queued on the default registry
+    #[account(
+        mut,
+        close = owner,
+        constraint = queued_admin_action.registry == default_registry_address() @ ZkError::RegistryMismatch,
+    )]
+    pub queued_admin_action: Option<Account<'info, QueuedAdminAction>>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    registry_id: u64
+)]
+pub struct CreateRegistry<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", default_registry_address().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Registry::INIT_SPACE,
+        seeds = [
+            b"registry",
+            registry_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<AuditLog>(),
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub registry_audit_log: AccountLoader<'info, AuditLog>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: This is the admin multisig, once initialized admin actions require an approved proposal
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+
+    /// Approved proposal for this admin action, required once the admin multisig is initialized
+    #[account(mut)]
+    pub admin_proposal: Option<Account<'info, AdminProposal>>,
+
+    /// Queued admin action whose timelock expired, required before the admin multisig is
+    /// initialized, program actions are queued on the default registry
+    #[account(
+        mut,
+        close = owner,
+        constraint = queued_admin_action.registry == default_registry_address() @ ZkError::RegistryMismatch,
+    )]
+    pub queued_admin_action: Option<Account<'info, QueuedAdminAction>>,
+}
+
+#[derive(Accounts)]
+pub struct SetRegistryAdmin<'info> {
+    #[account(signer)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    #[account(mut)]
+    pub registry: Account<'info, Registry>,
+}
+
+#[derive(Accounts)]
+pub struct SetRegistryPaused<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    #[account(mut)]
+    pub registry: Account<'info, Registry>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    /// CHECK: This is the admin multisig, once initialized admin actions require an approved proposal
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+
+    /// Approved proposal for this admin action, required once the admin multisig is initialized
+    #[account(mut)]
+    pub admin_proposal: Option<Account<'info, AdminProposal>>,
+
+    /// Queued admin action whose timelock expired, required to unpause the default registry
+    /// before the admin multisig is initialized
+    #[account(mut, close = owner, has_one = registry)]
+    pub queued_admin_action: Option<Account<'info, QueuedAdminAction>>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64
+)]
+pub struct InitCanaryProof<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + CanaryProof::INIT_SPACE,
+        seeds = [
+            b"canary_proof",
+            registry.key().as_ref(),
+            zkvm_selector.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub canary_proof: Account<'info, CanaryProof>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: This is the admin multisig, once initialized admin actions require an approved proposal
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+
+    /// Approved proposal for this admin action, required once the admin multisig is initialized
+    #[account(mut)]
+    pub admin_proposal: Option<Account<'info, AdminProposal>>,
+
+    /// Queued admin action whose timelock expired, required before the admin multisig is
+    /// initialized for every action but freezing
+    #[account(mut, close = owner, has_one = registry)]
+    pub queued_admin_action: Option<Account<'info, QueuedAdminAction>>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64
+)]
+pub struct UpdateCanaryProof<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"canary_proof",
+            registry.key().as_ref(),
+            zkvm_selector.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub canary_proof: Account<'info, CanaryProof>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    /// CHECK: This is the admin multisig, once initialized admin actions require an approved proposal
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+
+    /// Approved proposal for this admin action, required once the admin multisig is initialized
+    #[account(mut)]
+    pub admin_proposal: Option<Account<'info, AdminProposal>>,
+
+    /// Queued admin action whose timelock expired, required before the admin multisig is
+    /// initialized for every action but freezing
+    #[account(mut, close = owner, has_one = registry)]
+    pub queued_admin_action: Option<Account<'info, QueuedAdminAction>>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64
+)]
+pub struct ActivateZkvmVerifier<'info> {
+    #[account(signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    pub registry: Account<'info, Registry>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"zkvm_verifier",
+            registry.key().as_ref(),
+            zkvm_selector.to_le_bytes().as_ref(),
+            zkvm_verifier_program.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub zkvm_verifier_account: Account<'info, ZkvmVerifier>,
+
+    #[account(
+        seeds = [
+            b"canary_proof",
+            registry.key().as_ref(),
+            zkvm_selector.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub canary_proof: Account<'info, CanaryProof>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    /// CHECK: This is the address of the ZKVM Verifier Program. Currently, there isn't any defined standards to structure the program.
+    pub zkvm_verifier_program: AccountInfo<'info>,
+
+    /// CHECK: This is the program data account of the ZKVM Verifier Program, used to detect upgrades. It is empty for non-upgradeable programs.
+    #[account(
+        constraint = zkvm_verifier_program_data.key() == Pubkey::find_program_address(
+            &[zkvm_verifier_program.key().as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub zkvm_verifier_program_data: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateV0Counter<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    #[account(
+        mut,
+        seeds = [b"registry", DEFAULT_REGISTRY_ID.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Counter of the v0 account layout, closed once migrated
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"counter"],
+        bump,
+    )]
+    pub counter: Account<'info, Counter>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    /// CHECK: This is the admin multisig, migrations run before it is initialized
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(
+    zkvm_selector: u64
+)]
+pub struct MigrateV0ZkvmVerifier<'info> {
+    #[account(mut, signer)]
+    pub owner: Signer<'info>,
+
+    /// Role granted to the owner, not needed by the program upgrade authority
+    #[account(seeds = [b"role", owner.key().as_ref()], bump)]
+    pub owner_role: Option<Account<'info, RoleAssignment>>,
+
+    #[account(
+        mut,
+        seeds = [b"audit_log", registry.key().as_ref()],
+        bump,
+    )]
+    pub audit_log: AccountLoader<'info, AuditLog>,
+
+    #[account(
+        seeds = [b"registry", DEFAULT_REGISTRY_ID.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// CHECK: This is the zkVM verifier account of the v0 account layout, it is decoded and closed by the instruction
+    #[account(
+        mut,
+        seeds = [
+            b"zkvm_verifier",
+            zkvm_selector.to_le_bytes().as_ref(),
+            zkvm_verifier_program.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub v0_zkvm_verifier_account: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + ZkvmVerifier::INIT_SPACE,
+        seeds = [
+            b"zkvm_verifier",
+            registry.key().as_ref(),
+            zkvm_selector.to_le_bytes().as_ref(),
+            zkvm_verifier_program.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub zkvm_verifier_account: Account<'info, ZkvmVerifier>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + ZkvmVerifierStats::INIT_SPACE,
+        seeds = [
+            b"zkvm_verifier_stats",
+            zkvm_verifier_account.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub zkvm_verifier_stats: Account<'info, ZkvmVerifierStats>,
+
+    /// CHECK: This is the program data account for the current program
+    #[account(
+        constraint = program_data.key() == Pubkey::find_program_address(
+            &[crate::ID.as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub program_data: AccountInfo<'info>,
+
+    /// CHECK: This is the address of the ZKVM Verifier Program. Currently, there isn't any defined standards to structure the program.
+    pub zkvm_verifier_program: AccountInfo<'info>,
+
+    /// CHECK: This is the program data account of the ZKVM Verifier Program, used to detect upgrades. It is empty for non-upgradeable programs.
+    #[account(
+        constraint = zkvm_verifier_program_data.key() == Pubkey::find_program_address(
+            &[zkvm_verifier_program.key().as_ref()],
+            &bpf_loader_upgradeable::id()
+        ).0
+    )]
+    pub zkvm_verifier_program_data: AccountInfo<'info>,
+
+    /// CHECK: This is the admin multisig, migrations run before it is initialized
+    #[account(seeds = [b"admin_multisig"], bump)]
+    pub admin_multisig: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
 }
@@ -1,133 +1,2510 @@
 #![allow(unexpected_cfgs)]
 
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::solana_program::compute_units::sol_remaining_compute_units;
+use anchor_lang::solana_program::instruction::{
+    get_stack_height, Instruction, TRANSACTION_LEVEL_STACK_HEIGHT,
+};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+pub mod dcap;
+pub mod enclave;
 pub mod errors;
+pub mod events;
+pub mod freshness;
 pub mod instructions;
+pub mod merkle;
 pub mod state;
+#[cfg(feature = "cpi")]
+pub mod verify_cpi;
 
+use dcap::DcapVerifiedOutput;
 use errors::*;
+use events::{
+    AdminActionCancelled, AdminActionExecuted, AdminActionQueued, HybridStatementVerified,
+    QueuedAdminActionCancelled, QueuedAdminActionCreated, RegistryPaused, RoleGranted, RoleRevoked, StateAdvanced,
+    StatementVerified, VerifierEmergencyFrozen, VerifierResultsRevoked,
+};
+use freshness::FreshnessEnvelope;
 use instructions::*;
+use state::{
+    audit_value, AdminAction, AdminMultisig, AdminProposal, AuditAction, AuditLog, AuditLogEntry,
+    CanaryProof, DcapGuest, EnclaveKey, EnclaveKeyType, HybridMode, HybridPolicy, HybridVerificationResult, JournalDigest, QueuedAdminAction, Registry, Role, RoleAssignment, StateTransition,
+    VerificationResult, ZkvmVerifierV0, ACCUMULATOR_SHARDS, ADMIN_ACTION_KINDS, DEFAULT_REGISTRY_ID,
+    VERIFICATION_RESULT_LEN,
+    MAX_ACCEPTED_TCB_EVALUATION_DATA_NUMBERS,
+    MAX_DCAP_POLICY_MEASUREMENTS,
+    MAX_VERIFIED_JOURNAL_LEN,
+};
 
 declare_id!("3rp28FnaSDUsrwDHiggLFY12dVKvRovNbSs8iAKEFKmv");
 
+/// Smallest well-formed verify instruction: 8 byte discriminator, 32 byte vkey and 32 byte output digest.
+/// Verify instructions end with the vkey followed by the output digest.
+pub const MIN_ZK_VERIFY_INSTRUCTION_DATA_LEN: usize = 8 + 32 + 32;
+
+/// Compute units below which no Groth16 verifier can complete (the pairing check alone costs ~85k)
+pub const MIN_ZK_VERIFY_COMPUTE_UNITS: u64 = 100_000;
+
+/// Seed of the PDA signing `verify_zkvm_proof_and_call` callbacks
+pub const CALLBACK_AUTHORITY_SEED: &[u8] = b"callback_authority";
+
+/// Length of the instruction discriminator starting the callback data of `verify_zkvm_proof_and_call`
+pub const CALLBACK_DISCRIMINATOR_LEN: usize = 8;
+
+/// Seed of the PDA a consumer program signs with as `caller_signer`, to be identified as the caller
+/// when it is not invoked by the top-level instruction
+pub const CALLER_AUTHORITY_SEED: &[u8] = b"caller_authority";
+
 #[program]
 pub mod solana_zk {
-    use anchor_lang::solana_program::instruction::Instruction;
-
     use super::*;
 
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        let counter = &mut ctx.accounts.counter;
-        counter.count = 0;
+        // The default registry is governed by the program admins rather than a registry admin
+        let registry = &mut ctx.accounts.registry;
+        registry.registry_id = DEFAULT_REGISTRY_ID;
+        registry.admin = Pubkey::default();
+        registry.count = 0;
+        registry.paused = false;
+        registry.guardian = Pubkey::default();
+
+        ctx.accounts.verification_accumulator.load_init()?.init(0);
+        ctx.accounts.audit_log.load_init()?;
 
         Ok(())
     }
 
-    pub fn add_zk_verifier_program(ctx: Context<AddZkvmVerifier>, zkvm_selector: u64) -> Result<()> {
-        // Check if the owner is the program's upgrade authority
-        if !check_program_upgrade_authority(&ctx.accounts.program_data, &ctx.accounts.owner.key()) {
-            return err!(ZkError::Unauthorized);
+    pub fn init_verification_accumulator(
+        ctx: Context<InitVerificationAccumulator>,
+        shard: u64,
+    ) -> Result<()> {
+        // Anyone can create the other shards, or every shard on programs deployed before sharding
+        if shard >= ACCUMULATOR_SHARDS {
+            return err!(ZkError::InvalidAccumulatorShard);
         }
 
-        // Increment the counter
-        ctx.accounts.counter.count += 1;
+        ctx.accounts.verification_accumulator.load_init()?.init(shard);
+
+        Ok(())
+    }
+
+    pub fn add_zk_verifier_program(
+        ctx: Context<AddZkvmVerifier>,
+        zkvm_selector: u64,
+        journal_digest: JournalDigest,
+        dcap: bool,
+    ) -> Result<()> {
+        // Check the owner administers the registry, or the multisig approved this action
+        authorize_admin_action(
+            &ctx.accounts.registry,
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::AddZkVerifier {
+                zkvm_selector,
+                zkvm_program_id: ctx.accounts.zkvm_verifier_program.key(),
+                journal_digest,
+                dcap,
+            },
+        )?;
+
+        // Increment the counter of the registry
+        ctx.accounts.registry.count += 1;
 
         // Validate that selector matches current count
-        if zkvm_selector as u64 != ctx.accounts.counter.count {
+        if zkvm_selector as u64 != ctx.accounts.registry.count {
             return err!(ZkError::InvalidZkvmSelector);
         }
 
         // Update state
         let zkvm_verifier = &mut ctx.accounts.zkvm_verifier_account;
+        zkvm_verifier.registry = ctx.accounts.registry.key();
         zkvm_verifier.zkvm_selector = zkvm_selector;
         zkvm_verifier.zkvm_program_id = ctx.accounts.zkvm_verifier_program.key();
+        zkvm_verifier.zkvm_program_deploy_slot =
+            program_deploy_slot(&ctx.accounts.zkvm_verifier_program_data);
+        zkvm_verifier.journal_digest = journal_digest;
         zkvm_verifier.frozen = false;
+        zkvm_verifier.pending = true;
+        zkvm_verifier.deprecated = false;
+        zkvm_verifier.dcap = dcap;
+
+        ctx.accounts.zkvm_verifier_stats.zkvm_verifier = zkvm_verifier.key();
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::AddZkVerifier,
+            ctx.accounts.registry.registry_id,
+            zkvm_selector,
+            ctx.accounts.zkvm_verifier_program.key(),
+            [0; 32],
+            audit_value(ctx.accounts.zkvm_verifier_account.zkvm_program_deploy_slot),
+        )?;
 
         Ok(())
     }
 
     pub fn update_zk_verifier_program(
         ctx: Context<UpdateZkvmVerifierConfig>,
-        _zkvm_selector: u64,
+        zkvm_selector: u64,
     ) -> Result<()> {
-        // Check if the owner is the program's upgrade authority
-        if !check_program_upgrade_authority(&ctx.accounts.program_data, &ctx.accounts.owner.key()) {
-            return err!(ZkError::Unauthorized);
-        }
+        // Check the owner administers the registry, or the multisig approved this action
+        authorize_admin_action(
+            &ctx.accounts.registry,
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::UpdateZkVerifier {
+                zkvm_selector,
+                zkvm_program_id: ctx.accounts.zkvm_verifier_program.key(),
+            },
+        )?;
 
         let zkvm_verifier = &mut ctx.accounts.zkvm_verifier_account;
+        let old_deploy_slot = zkvm_verifier.zkvm_program_deploy_slot;
 
         zkvm_verifier.zkvm_program_id = ctx.accounts.zkvm_verifier_program.key();
+        zkvm_verifier.zkvm_program_deploy_slot =
+            program_deploy_slot(&ctx.accounts.zkvm_verifier_program_data);
+        // The new program must accept the canary proof before it verifies again
+        zkvm_verifier.pending = true;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::UpdateZkVerifier,
+            ctx.accounts.registry.registry_id,
+            zkvm_selector,
+            ctx.accounts.zkvm_verifier_program.key(),
+            audit_value(old_deploy_slot),
+            audit_value(ctx.accounts.zkvm_verifier_account.zkvm_program_deploy_slot),
+        )?;
 
         Ok(())
     }
 
     pub fn freeze_zk_verifier_program(
         ctx: Context<UpdateZkvmVerifierConfig>,
-        _zkvm_selector: u64,
+        zkvm_selector: u64,
         freeze: bool,
     ) -> Result<()> {
-        // Check if the owner is the program's upgrade authority
-        if !check_program_upgrade_authority(&ctx.accounts.program_data, &ctx.accounts.owner.key()) {
-            return err!(ZkError::Unauthorized);
-        }
+        // Check the owner administers the registry, or the multisig approved this action
+        authorize_admin_action(
+            &ctx.accounts.registry,
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::FreezeZkVerifier {
+                zkvm_selector,
+                zkvm_program_id: ctx.accounts.zkvm_verifier_program.key(),
+                freeze,
+            },
+        )?;
 
         let zkvm_verifier = &mut ctx.accounts.zkvm_verifier_account;
+        let was_frozen = zkvm_verifier.frozen;
         zkvm_verifier.frozen = freeze;
 
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::FreezeZkVerifier,
+            ctx.accounts.registry.registry_id,
+            zkvm_selector,
+            ctx.accounts.zkvm_verifier_program.key(),
+            audit_value(was_frozen as u64),
+            audit_value(freeze as u64),
+        )?;
+
         Ok(())
     }
 
-    pub fn verify_zkvm_proof(
-        ctx: Context<VerifyZkProof>,
-        _zkvm_selector: u64,
-        zk_verify_instruction_data: Vec<u8>,
+    pub fn remove_zk_verifier_program(
+        ctx: Context<RemoveZkvmVerifier>,
+        zkvm_selector: u64,
     ) -> Result<()> {
-        // Step 1: Check zkvm selector matches with the expected zkvm_verifier_program
-        let zkvm_verifier = &ctx.accounts.zkvm_verifier_account;
+        // Check the owner administers the registry, or the multisig approved this action
+        authorize_admin_action(
+            &ctx.accounts.registry,
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::RemoveZkVerifier {
+                zkvm_selector,
+                zkvm_program_id: ctx.accounts.zkvm_verifier_program.key(),
+            },
+        )?;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::RemoveZkVerifier,
+            ctx.accounts.registry.registry_id,
+            zkvm_selector,
+            ctx.accounts.zkvm_verifier_program.key(),
+            audit_value(ctx.accounts.zkvm_verifier_account.zkvm_program_deploy_slot),
+            [0; 32],
+        )?;
+
+        // The verifier and its stats are closed by the `close` constraints, the selector is not reused
+        Ok(())
+    }
+
+    pub fn init_verifier_stats(ctx: Context<InitVerifierStats>) -> Result<()> {
+        // Verifiers added before usage statistics existed have no stats account, anyone can pay for it
+        ctx.accounts.zkvm_verifier_stats.zkvm_verifier = ctx.accounts.zkvm_verifier_account.key();
+
+        Ok(())
+    }
+
+    pub fn init_audit_log(ctx: Context<InitAuditLog>) -> Result<()> {
+        // Registries created before audit logs were kept per registry have no audit log, anyone can
+        // pay for it
+        ctx.accounts.audit_log.load_init()?;
 
-        // Step 2: Check if the zkvm_verifier_program is frozen
-        if zkvm_verifier.frozen {
-            return err!(ZkError::ZkvmProgramFrozen);
+        Ok(())
+    }
+
+    pub fn migrate_v0_counter(ctx: Context<MigrateV0Counter>) -> Result<()> {
+        // Check the owner holds the admin role, migrations run before the admin multisig exists
+        check_migration_authority(
+            &ctx.accounts.program_data,
+            &ctx.accounts.owner.key(),
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+        )?;
+
+        // Verifiers added since the upgrade would reuse the selectors counted by the v0 counter
+        let registry = &mut ctx.accounts.registry;
+        if registry.count != 0 {
+            return err!(ZkError::RegistryNotEmpty);
         }
+        registry.count = ctx.accounts.counter.count;
 
-        // Step 3: Perform CPI to zkvm_verifier_program
-        let verify_cpi_context = CpiContext::new(
-            ctx.accounts.zkvm_verifier_program.to_account_info(),
-            vec![ctx.accounts.system_program.to_account_info()],
-        );
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::MigrateCounter,
+            DEFAULT_REGISTRY_ID,
+            0,
+            Pubkey::default(),
+            audit_value(0),
+            audit_value(ctx.accounts.counter.count),
+        )?;
 
-        invoke(
-            &Instruction {
-                program_id: ctx.accounts.zkvm_verifier_program.key(),
-                accounts: verify_cpi_context.to_account_metas(None),
-                data: zk_verify_instruction_data,
+        Ok(())
+    }
+
+    pub fn migrate_v0_zk_verifier(
+        ctx: Context<MigrateV0ZkvmVerifier>,
+        zkvm_selector: u64,
+        journal_digest: JournalDigest,
+    ) -> Result<()> {
+        // Check the owner holds the admin role, migrations run before the admin multisig exists
+        check_migration_authority(
+            &ctx.accounts.program_data,
+            &ctx.accounts.owner.key(),
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+        )?;
+
+        let v0_info = ctx.accounts.v0_zkvm_verifier_account.to_account_info();
+        if v0_info.owner != &crate::ID {
+            return err!(ZkError::InvalidV0Account);
+        }
+        let v0_zkvm_verifier = ZkvmVerifierV0::try_from_account_data(&v0_info.try_borrow_data()?)?;
+        if v0_zkvm_verifier.zkvm_selector != zkvm_selector
+            || v0_zkvm_verifier.zkvm_program_id != ctx.accounts.zkvm_verifier_program.key()
+        {
+            return err!(ZkError::ZkvmSelectorMismatch);
+        }
+
+        // The selector must have been counted by the migrated v0 counter
+        if zkvm_selector == 0 || zkvm_selector > ctx.accounts.registry.count {
+            return err!(ZkError::InvalidZkvmSelector);
+        }
+
+        // The verifier was already accepting proofs, so it is migrated active rather than pending
+        let zkvm_verifier = &mut ctx.accounts.zkvm_verifier_account;
+        zkvm_verifier.registry = ctx.accounts.registry.key();
+        zkvm_verifier.zkvm_selector = zkvm_selector;
+        zkvm_verifier.zkvm_program_id = v0_zkvm_verifier.zkvm_program_id;
+        zkvm_verifier.zkvm_program_deploy_slot =
+            program_deploy_slot(&ctx.accounts.zkvm_verifier_program_data);
+        zkvm_verifier.journal_digest = journal_digest;
+        zkvm_verifier.frozen = v0_zkvm_verifier.frozen;
+        zkvm_verifier.pending = false;
+        zkvm_verifier.deprecated = false;
+        zkvm_verifier.dcap = false;
+
+        ctx.accounts.zkvm_verifier_stats.zkvm_verifier = zkvm_verifier.key();
+
+        close_account(&v0_info, &ctx.accounts.owner.to_account_info())?;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::MigrateZkVerifier,
+            DEFAULT_REGISTRY_ID,
+            zkvm_selector,
+            ctx.accounts.zkvm_verifier_program.key(),
+            [0; 32],
+            audit_value(ctx.accounts.zkvm_verifier_account.zkvm_program_deploy_slot),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn init_canary_proof(
+        ctx: Context<InitCanaryProof>,
+        zkvm_selector: u64,
+        valid_instruction_data: Vec<u8>,
+        invalid_instruction_data: Vec<u8>,
+    ) -> Result<()> {
+        // Check the owner administers the registry, or the multisig approved this action
+        authorize_admin_action(
+            &ctx.accounts.registry,
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::SetCanaryProof {
+                zkvm_selector,
+                canary_digest: CanaryProof::instruction_data_digest(
+                    &valid_instruction_data,
+                    &invalid_instruction_data,
+                ),
             },
-            &[ctx.accounts.system_program.to_account_info()],
+        )?;
+
+        // The canary proof can only be set for a configured selector
+        if zkvm_selector == 0 || zkvm_selector > ctx.accounts.registry.count {
+            return err!(ZkError::InvalidZkvmSelector);
+        }
+        CanaryProof::check_instruction_data(&valid_instruction_data, &invalid_instruction_data)?;
+
+        let canary_proof = &mut ctx.accounts.canary_proof;
+        canary_proof.registry = ctx.accounts.registry.key();
+        canary_proof.zkvm_selector = zkvm_selector;
+        canary_proof.valid_instruction_data = valid_instruction_data;
+        canary_proof.invalid_instruction_data = invalid_instruction_data;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::SetCanaryProof,
+            ctx.accounts.registry.registry_id,
+            zkvm_selector,
+            Pubkey::default(),
+            [0; 32],
+            ctx.accounts.canary_proof.digest(),
         )?;
 
         Ok(())
     }
-}
 
-// Helper function to check if a pubkey matches the program's upgrade authority
-fn check_program_upgrade_authority(
-    program_data_info: &AccountInfo,
-    owner_key: &Pubkey,
-) -> bool {
-    // Parse the program data account
-    let data = program_data_info.try_borrow_data().unwrap();
+    pub fn update_canary_proof(
+        ctx: Context<UpdateCanaryProof>,
+        zkvm_selector: u64,
+        valid_instruction_data: Vec<u8>,
+        invalid_instruction_data: Vec<u8>,
+    ) -> Result<()> {
+        // Check the owner administers the registry, or the multisig approved this action
+        authorize_admin_action(
+            &ctx.accounts.registry,
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::SetCanaryProof {
+                zkvm_selector,
+                canary_digest: CanaryProof::instruction_data_digest(
+                    &valid_instruction_data,
+                    &invalid_instruction_data,
+                ),
+            },
+        )?;
 
-    // The upgrade authority is at offset 13 (1 byte for is_initialized + 8 bytes for slot + 4 bytes for upgrade authority present)
-    // If the upgrade authority is present (byte at offset 12 is 1), then the next 32 bytes are the upgrade authority pubkey
-    if data[12] == 1 {
-        let mut authority = [0u8; 32];
-        authority.copy_from_slice(&data[13..45]);
-        let authority_pubkey = Pubkey::new_from_array(authority);
+        CanaryProof::check_instruction_data(&valid_instruction_data, &invalid_instruction_data)?;
 
-        return &authority_pubkey == owner_key;
+        let canary_proof = &mut ctx.accounts.canary_proof;
+        let old_digest = canary_proof.digest();
+        canary_proof.valid_instruction_data = valid_instruction_data;
+        canary_proof.invalid_instruction_data = invalid_instruction_data;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::SetCanaryProof,
+            ctx.accounts.registry.registry_id,
+            zkvm_selector,
+            Pubkey::default(),
+            old_digest,
+            ctx.accounts.canary_proof.digest(),
+        )?;
+
+        Ok(())
     }
 
-    false
-}
\ No newline at end of file
+    pub fn activate_zk_verifier_program(
+        ctx: Context<ActivateZkvmVerifier>,
+        zkvm_selector: u64,
+    ) -> Result<()> {
+        // Check the owner holds the operator role on the registry
+        check_registry_role(
+            &ctx.accounts.registry,
+            &ctx.accounts.program_data,
+            &ctx.accounts.owner.key(),
+            ctx.accounts.owner_role.as_deref(),
+            Role::Operator,
+        )?;
+
+        let zkvm_verifier = &ctx.accounts.zkvm_verifier_account;
+        if !zkvm_verifier.pending {
+            return err!(ZkError::ZkvmProgramNotPending);
+        }
+
+        // The canary proof must run against the program that was configured
+        if program_deploy_slot(&ctx.accounts.zkvm_verifier_program_data)
+            != zkvm_verifier.zkvm_program_deploy_slot
+        {
+            return err!(ZkError::ZkvmProgramUpgraded);
+        }
+        if sol_remaining_compute_units() < MIN_ZK_VERIFY_COMPUTE_UNITS {
+            return err!(ZkError::ComputeBudgetExhausted);
+        }
+
+        // Only the known-good proof is checked on-chain: a rejected proof aborts the transaction, so the
+        // known-bad proof is off-chain advice that clients simulate before activating
+        invoke_zkvm_verifier_program(
+            &ctx.accounts.zkvm_verifier_program,
+            &ctx.accounts.system_program,
+            ctx.accounts.canary_proof.valid_instruction_data.clone(),
+        )?;
+
+        let zkvm_verifier = &mut ctx.accounts.zkvm_verifier_account;
+        zkvm_verifier.pending = false;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::ActivateZkVerifier,
+            ctx.accounts.registry.registry_id,
+            zkvm_selector,
+            ctx.accounts.zkvm_verifier_program.key(),
+            [0; 32],
+            audit_value(ctx.accounts.zkvm_verifier_account.zkvm_program_deploy_slot),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn init_admin_multisig(
+        ctx: Context<InitAdminMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+        delay_slots: [u64; ADMIN_ACTION_KINDS],
+    ) -> Result<()> {
+        // Check the owner holds the admin role
+        check_role(
+            &ctx.accounts.program_data,
+            &ctx.accounts.owner.key(),
+            ctx.accounts.owner_role.as_deref(),
+            Role::Admin,
+        )?;
+
+        AdminMultisig::check_config(&signers, threshold)?;
+        AdminMultisig::check_delays(&delay_slots)?;
+
+        let admin_multisig = &mut ctx.accounts.admin_multisig;
+        admin_multisig.signers = signers;
+        admin_multisig.threshold = threshold;
+        admin_multisig.proposal_count = 0;
+        admin_multisig.nonce = 0;
+        admin_multisig.delay_slots = delay_slots;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::SetAdminMultisig,
+            DEFAULT_REGISTRY_ID,
+            0,
+            Pubkey::default(),
+            [0; 32],
+            AdminMultisig::config_digest(&admin_multisig.signers, threshold),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn create_admin_proposal(
+        ctx: Context<CreateAdminProposal>,
+        action: AdminAction,
+    ) -> Result<u64> {
+        let admin_multisig = &mut ctx.accounts.admin_multisig;
+        let signer_index = admin_multisig.signer_index(&ctx.accounts.proposer.key())?;
+        match &action {
+            AdminAction::UpdateAdminMultisig { signers, threshold } => {
+                AdminMultisig::check_config(signers, *threshold)?
+            }
+            AdminAction::SetTimelockDelay { kind, delay_slots } => kind.check_delay(*delay_slots)?,
+            AdminAction::SetDcapCollateralConfig {
+                tcb_evaluation_data_numbers,
+                ..
+            } if tcb_evaluation_data_numbers.len() > MAX_ACCEPTED_TCB_EVALUATION_DATA_NUMBERS => {
+                return err!(ZkError::TooManyTcbEvaluationDataNumbers)
+            }
+            _ => {}
+        }
+
+        // The proposer approves its own proposal
+        let admin_proposal = &mut ctx.accounts.admin_proposal;
+        admin_proposal.proposal_id = admin_multisig.proposal_count;
+        admin_proposal.proposer = ctx.accounts.proposer.key();
+        admin_proposal.action = action;
+        admin_proposal.approvals = 1 << signer_index;
+        admin_proposal.multisig_nonce = admin_multisig.nonce;
+        admin_proposal.eta_slot = 0;
+        admin_proposal.executed = false;
+
+        admin_multisig.proposal_count += 1;
+
+        if admin_proposal.try_queue(admin_multisig, Clock::get()?.slot)? {
+            emit!(AdminActionQueued {
+                proposal_id: admin_proposal.proposal_id,
+                action: admin_proposal.action.clone(),
+                eta_slot: admin_proposal.eta_slot,
+            });
+        }
+
+        Ok(admin_proposal.proposal_id)
+    }
+
+    pub fn approve_admin_proposal(ctx: Context<ApproveAdminProposal>) -> Result<()> {
+        let admin_multisig = &ctx.accounts.admin_multisig;
+        let signer_index = admin_multisig.signer_index(&ctx.accounts.signer.key())?;
+
+        let admin_proposal = &mut ctx.accounts.admin_proposal;
+        if admin_proposal.executed {
+            return err!(ZkError::ProposalAlreadyExecuted);
+        }
+        if admin_proposal.multisig_nonce != admin_multisig.nonce {
+            return err!(ZkError::StaleProposal);
+        }
+        admin_proposal.approvals |= 1 << signer_index;
+
+        // The timelock starts once the threshold is reached
+        if admin_proposal.try_queue(admin_multisig, Clock::get()?.slot)? {
+            emit!(AdminActionQueued {
+                proposal_id: admin_proposal.proposal_id,
+                action: admin_proposal.action.clone(),
+                eta_slot: admin_proposal.eta_slot,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn cancel_admin_proposal(ctx: Context<CancelAdminProposal>) -> Result<()> {
+        // Any single signer can veto a proposal before it executes
+        let admin_multisig = &ctx.accounts.admin_multisig;
+        admin_multisig.signer_index(&ctx.accounts.signer.key())?;
+
+        let admin_proposal = &ctx.accounts.admin_proposal;
+        if admin_proposal.executed {
+            return err!(ZkError::ProposalAlreadyExecuted);
+        }
+
+        emit!(AdminActionCancelled {
+            proposal_id: admin_proposal.proposal_id,
+            action: admin_proposal.action.clone(),
+            cancelled_by: ctx.accounts.signer.key(),
+        });
+
+        // The account is closed by the `close` constraint
+        Ok(())
+    }
+
+    pub fn update_admin_multisig(ctx: Context<UpdateAdminMultisig>) -> Result<()> {
+        let admin_multisig = &mut ctx.accounts.admin_multisig;
+        let admin_proposal = &mut ctx.accounts.admin_proposal;
+        let action = admin_proposal.action.clone();
+        let AdminAction::UpdateAdminMultisig { signers, threshold } = &action else {
+            return err!(ZkError::ProposalActionMismatch);
+        };
+        admin_proposal.execute(admin_multisig, &action, Clock::get()?.slot)?;
+
+        let old_config =
+            AdminMultisig::config_digest(&admin_multisig.signers, admin_multisig.threshold);
+
+        // Pending proposals were approved by the previous signers and can no longer execute
+        admin_multisig.signers = signers.clone();
+        admin_multisig.threshold = *threshold;
+        admin_multisig.nonce += 1;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.executor.key(),
+            AuditAction::SetAdminMultisig,
+            DEFAULT_REGISTRY_ID,
+            0,
+            Pubkey::default(),
+            old_config,
+            AdminMultisig::config_digest(signers, *threshold),
+        )?;
+
+        emit!(AdminActionExecuted {
+            proposal_id: admin_proposal.proposal_id,
+            action,
+        });
+
+        Ok(())
+    }
+
+    pub fn set_timelock_delay(ctx: Context<UpdateAdminMultisig>) -> Result<()> {
+        let admin_multisig = &mut ctx.accounts.admin_multisig;
+        let admin_proposal = &mut ctx.accounts.admin_proposal;
+        let action = admin_proposal.action.clone();
+        let AdminAction::SetTimelockDelay { kind, delay_slots } = action else {
+            return err!(ZkError::ProposalActionMismatch);
+        };
+        admin_proposal.execute(admin_multisig, &action, Clock::get()?.slot)?;
+        kind.check_delay(delay_slots)?;
+
+        let old_delay = admin_multisig.delay_slots[kind as usize];
+
+        // Proposals already queued keep the delay they were queued with
+        admin_multisig.delay_slots[kind as usize] = delay_slots;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.executor.key(),
+            AuditAction::SetTimelockDelay,
+            DEFAULT_REGISTRY_ID,
+            kind as u64,
+            Pubkey::default(),
+            audit_value(old_delay),
+            audit_value(delay_slots),
+        )?;
+
+        emit!(AdminActionExecuted {
+            proposal_id: admin_proposal.proposal_id,
+            action,
+        });
+
+        Ok(())
+    }
+
+    pub fn queue_admin_action(ctx: Context<QueueAdminAction>, action: AdminAction) -> Result<()> {
+        // Once the admin multisig is initialized, default registry actions go through proposals
+        let registry = &ctx.accounts.registry;
+        if registry.is_default() && !ctx.accounts.admin_multisig.data_is_empty() {
+            return err!(ZkError::MultisigApprovalRequired);
+        }
+
+        // Check the owner administers the registry
+        check_registry_role(
+            registry,
+            &ctx.accounts.program_data,
+            &ctx.accounts.owner.key(),
+            ctx.accounts.owner_role.as_deref(),
+            Role::Admin,
+        )?;
+
+        // Only verifier actions are executed by a single admin
+        if matches!(
+            action,
+            AdminAction::UpdateAdminMultisig { .. } | AdminAction::SetTimelockDelay { .. }
+        ) {
+            return err!(ZkError::ProposalActionMismatch);
+        }
+
+        let eta_slot = Clock::get()?
+            .slot
+            .checked_add(action.min_delay_slots())
+            .ok_or(error!(ZkError::InvalidTimelockDelay))?;
+
+        let queued_admin_action = &mut ctx.accounts.queued_admin_action;
+        queued_admin_action.registry = registry.key();
+        queued_admin_action.queued_by = ctx.accounts.owner.key();
+        queued_admin_action.action = action.clone();
+        queued_admin_action.eta_slot = eta_slot;
+
+        emit!(QueuedAdminActionCreated {
+            registry: registry.key(),
+            action,
+            eta_slot,
+            queued_by: ctx.accounts.owner.key(),
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_queued_admin_action(ctx: Context<CancelQueuedAdminAction>) -> Result<()> {
+        // Check the owner administers the registry
+        check_registry_role(
+            &ctx.accounts.registry,
+            &ctx.accounts.program_data,
+            &ctx.accounts.owner.key(),
+            ctx.accounts.owner_role.as_deref(),
+            Role::Admin,
+        )?;
+
+        emit!(QueuedAdminActionCancelled {
+            registry: ctx.accounts.registry.key(),
+            action: ctx.accounts.queued_admin_action.action.clone(),
+            cancelled_by: ctx.accounts.owner.key(),
+        });
+
+        // The account is closed by the `close` constraint
+        Ok(())
+    }
+
+    pub fn emergency_freeze_zk_verifier(
+        ctx: Context<UpdateZkvmVerifierConfig>,
+        _zkvm_selector: u64,
+    ) -> Result<()> {
+        // Guardians of the registry can freeze immediately. Unfreezing and replacing verifiers
+        // always go through the timelock
+        check_registry_guardian(
+            &ctx.accounts.registry,
+            &ctx.accounts.program_data,
+            &ctx.accounts.owner.key(),
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+        )?;
+
+        let zkvm_verifier = &mut ctx.accounts.zkvm_verifier_account;
+        let was_frozen = zkvm_verifier.frozen;
+        zkvm_verifier.frozen = true;
+
+        emit!(VerifierEmergencyFrozen {
+            registry: zkvm_verifier.registry,
+            zkvm_selector: zkvm_verifier.zkvm_selector,
+            zkvm_program_id: zkvm_verifier.zkvm_program_id,
+            frozen_by: ctx.accounts.owner.key(),
+        });
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::EmergencyFreezeZkVerifier,
+            ctx.accounts.registry.registry_id,
+            ctx.accounts.zkvm_verifier_account.zkvm_selector,
+            ctx.accounts.zkvm_verifier_account.zkvm_program_id,
+            audit_value(was_frozen as u64),
+            audit_value(1),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn grant_role(ctx: Context<GrantRole>, member: Pubkey, role: Role) -> Result<()> {
+        // Check the owner holds the admin role, or the multisig approved this action
+        authorize_program_admin_action(
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::GrantRole { member, role },
+        )?;
+
+        let role_assignment = &mut ctx.accounts.role_assignment;
+        role_assignment.member = member;
+        role_assignment.role = role;
+        role_assignment.granted_by = ctx.accounts.owner.key();
+
+        emit!(RoleGranted {
+            member,
+            role,
+            granted_by: ctx.accounts.owner.key(),
+        });
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::GrantRole,
+            DEFAULT_REGISTRY_ID,
+            0,
+            member,
+            [0; 32],
+            audit_value(role as u64 + 1),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn revoke_role(ctx: Context<RevokeRole>, member: Pubkey) -> Result<()> {
+        // Check the owner holds the admin role, or the multisig approved this action
+        authorize_program_admin_action(
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::RevokeRole { member },
+        )?;
+
+        emit!(RoleRevoked {
+            member,
+            role: ctx.accounts.role_assignment.role,
+            revoked_by: ctx.accounts.owner.key(),
+        });
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::RevokeRole,
+            DEFAULT_REGISTRY_ID,
+            0,
+            member,
+            audit_value(ctx.accounts.role_assignment.role as u64 + 1),
+            [0; 32],
+        )?;
+
+        // The account is closed by the `close` constraint
+        Ok(())
+    }
+
+    pub fn create_registry(
+        ctx: Context<CreateRegistry>,
+        registry_id: u64,
+        admin: Pubkey,
+    ) -> Result<()> {
+        // Check the owner holds the admin role or the multisig approved this action, the tenant
+        // registry is then governed by its admin
+        authorize_program_admin_action(
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::CreateRegistry { registry_id, admin },
+        )?;
+
+        let registry = &mut ctx.accounts.registry;
+        registry.registry_id = registry_id;
+        registry.admin = admin;
+        registry.count = 0;
+        registry.paused = false;
+        registry.guardian = Pubkey::default();
+        ctx.accounts.registry_audit_log.load_init()?;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::CreateRegistry,
+            registry_id,
+            0,
+            admin,
+            [0; 32],
+            [0; 32],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn set_registry_admin(ctx: Context<SetRegistryAdmin>, admin: Pubkey) -> Result<()> {
+        // Only the current admin can hand over a tenant registry
+        let registry = &mut ctx.accounts.registry;
+        registry.check_admin(&ctx.accounts.admin.key())?;
+
+        let old_admin = registry.admin;
+        registry.admin = admin;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.admin.key(),
+            AuditAction::SetRegistryAdmin,
+            registry.registry_id,
+            0,
+            admin,
+            old_admin.to_bytes(),
+            admin.to_bytes(),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn set_registry_guardian(ctx: Context<SetRegistryAdmin>, guardian: Pubkey) -> Result<()> {
+        // Only the admin appoints the guardian of a tenant registry
+        let registry = &mut ctx.accounts.registry;
+        registry.check_admin(&ctx.accounts.admin.key())?;
+
+        let old_guardian = registry.guardian;
+        registry.guardian = guardian;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.admin.key(),
+            AuditAction::SetRegistryGuardian,
+            registry.registry_id,
+            0,
+            guardian,
+            old_guardian.to_bytes(),
+            guardian.to_bytes(),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn pause_registry(ctx: Context<SetRegistryPaused>) -> Result<()> {
+        // Guardians of the registry can pause immediately. Unpausing always goes through the
+        // timelock
+        check_registry_guardian(
+            &ctx.accounts.registry,
+            &ctx.accounts.program_data,
+            &ctx.accounts.owner.key(),
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+        )?;
+
+        let registry = &mut ctx.accounts.registry;
+        let was_paused = registry.paused;
+        registry.paused = true;
+
+        emit!(RegistryPaused {
+            registry: registry.key(),
+            paused_by: ctx.accounts.owner.key(),
+        });
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::SetRegistryPaused,
+            ctx.accounts.registry.registry_id,
+            0,
+            Pubkey::default(),
+            audit_value(was_paused as u64),
+            audit_value(1),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn unpause_registry(ctx: Context<SetRegistryPaused>) -> Result<()> {
+        // Check the owner administers the registry, or the multisig approved this action
+        authorize_admin_action(
+            &ctx.accounts.registry,
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::UnpauseRegistry {
+                registry_id: ctx.accounts.registry.registry_id,
+            },
+        )?;
+
+        let registry = &mut ctx.accounts.registry;
+        let was_paused = registry.paused;
+        registry.paused = false;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::SetRegistryPaused,
+            ctx.accounts.registry.registry_id,
+            0,
+            Pubkey::default(),
+            audit_value(was_paused as u64),
+            audit_value(0),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn revoke_zk_verifier_results(
+        ctx: Context<UpdateZkvmVerifierConfig>,
+        zkvm_selector: u64,
+    ) -> Result<()> {
+        // Check the owner administers the registry, or the multisig approved this action
+        authorize_admin_action(
+            &ctx.accounts.registry,
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::RevokeZkVerifierResults {
+                zkvm_selector,
+                zkvm_program_id: ctx.accounts.zkvm_verifier_program.key(),
+            },
+        )?;
+
+        // Every result recorded under an earlier epoch is now treated as invalid, and the verifier is
+        // frozen so that it records no new result until it is fixed and unfrozen
+        let zkvm_verifier = &mut ctx.accounts.zkvm_verifier_account;
+        zkvm_verifier.revocation_epoch += 1;
+        zkvm_verifier.frozen = true;
+
+        emit!(VerifierResultsRevoked {
+            registry: zkvm_verifier.registry,
+            zkvm_selector: zkvm_verifier.zkvm_selector,
+            zkvm_program_id: zkvm_verifier.zkvm_program_id,
+            revocation_epoch: zkvm_verifier.revocation_epoch,
+        });
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::RevokeZkVerifierResults,
+            ctx.accounts.registry.registry_id,
+            ctx.accounts.zkvm_verifier_account.zkvm_selector,
+            ctx.accounts.zkvm_verifier_account.zkvm_program_id,
+            audit_value(ctx.accounts.zkvm_verifier_account.revocation_epoch - 1),
+            audit_value(ctx.accounts.zkvm_verifier_account.revocation_epoch),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn set_zk_verifier_permissioned(
+        ctx: Context<UpdateZkvmVerifierConfig>,
+        _zkvm_selector: u64,
+        permissioned: bool,
+    ) -> Result<()> {
+        // Check the owner holds the operator role on the registry
+        check_registry_role(
+            &ctx.accounts.registry,
+            &ctx.accounts.program_data,
+            &ctx.accounts.owner.key(),
+            ctx.accounts.owner_role.as_deref(),
+            Role::Operator,
+        )?;
+
+        let was_permissioned = ctx.accounts.zkvm_verifier_account.permissioned;
+        ctx.accounts.zkvm_verifier_account.permissioned = permissioned;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::SetZkVerifierPermissioned,
+            ctx.accounts.registry.registry_id,
+            ctx.accounts.zkvm_verifier_account.zkvm_selector,
+            ctx.accounts.zkvm_verifier_account.zkvm_program_id,
+            audit_value(was_permissioned as u64),
+            audit_value(permissioned as u64),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn add_allowed_caller(
+        ctx: Context<AddAllowedCaller>,
+        zkvm_selector: u64,
+        caller: Pubkey,
+    ) -> Result<()> {
+        // Check the owner holds the operator role on the registry
+        check_registry_role(
+            &ctx.accounts.registry,
+            &ctx.accounts.program_data,
+            &ctx.accounts.owner.key(),
+            ctx.accounts.owner_role.as_deref(),
+            Role::Operator,
+        )?;
+
+        if ctx.accounts.zkvm_verifier_account.zkvm_selector != zkvm_selector {
+            return err!(ZkError::ZkvmSelectorMismatch);
+        }
+
+        let allowed_caller = &mut ctx.accounts.allowed_caller;
+        allowed_caller.zkvm_verifier = ctx.accounts.zkvm_verifier_account.key();
+        allowed_caller.caller = caller;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::AddAllowedCaller,
+            ctx.accounts.registry.registry_id,
+            zkvm_selector,
+            caller,
+            [0; 32],
+            [0; 32],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn remove_allowed_caller(
+        ctx: Context<RemoveAllowedCaller>,
+        _zkvm_selector: u64,
+        _caller: Pubkey,
+    ) -> Result<()> {
+        // Check the owner holds the operator role on the registry
+        check_registry_role(
+            &ctx.accounts.registry,
+            &ctx.accounts.program_data,
+            &ctx.accounts.owner.key(),
+            ctx.accounts.owner_role.as_deref(),
+            Role::Operator,
+        )?;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::RemoveAllowedCaller,
+            ctx.accounts.registry.registry_id,
+            ctx.accounts.zkvm_verifier_account.zkvm_selector,
+            ctx.accounts.allowed_caller.caller,
+            [0; 32],
+            [0; 32],
+        )?;
+
+        // The account is closed by the `close` constraint
+        Ok(())
+    }
+
+    pub fn deprecate_zk_verifier_program(
+        ctx: Context<UpdateZkvmVerifierConfig>,
+        zkvm_selector: u64,
+        sunset_slot: u64,
+        successor_selector: u64,
+        successor_program_id: Pubkey,
+    ) -> Result<()> {
+        // Check the owner administers the registry, or the multisig approved this action
+        authorize_admin_action(
+            &ctx.accounts.registry,
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::DeprecateZkVerifier {
+                zkvm_selector,
+                zkvm_program_id: ctx.accounts.zkvm_verifier_program.key(),
+                sunset_slot,
+                successor_selector,
+                successor_program_id,
+            },
+        )?;
+
+        // The grace period must not have already elapsed
+        if sunset_slot <= Clock::get()?.slot {
+            return err!(ZkError::InvalidSunsetSlot);
+        }
+
+        let zkvm_verifier = &mut ctx.accounts.zkvm_verifier_account;
+        let old_sunset_slot = if zkvm_verifier.deprecated {
+            zkvm_verifier.sunset_slot
+        } else {
+            0
+        };
+        zkvm_verifier.deprecated = true;
+        zkvm_verifier.sunset_slot = sunset_slot;
+        zkvm_verifier.successor_selector = successor_selector;
+        zkvm_verifier.successor_program_id = successor_program_id;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::DeprecateZkVerifier,
+            ctx.accounts.registry.registry_id,
+            ctx.accounts.zkvm_verifier_account.zkvm_selector,
+            ctx.accounts.zkvm_verifier_account.zkvm_program_id,
+            audit_value(old_sunset_slot),
+            audit_value(sunset_slot),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn add_dcap_guest(
+        ctx: Context<AddDcapGuest>,
+        zkvm_selector: u64,
+        guest_vkey: [u8; 32],
+    ) -> Result<()> {
+        // Check the owner administers the registry, or the multisig approved this action
+        authorize_admin_action(
+            &ctx.accounts.registry,
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::AddDcapGuest {
+                zkvm_selector,
+                guest_vkey,
+            },
+        )?;
+
+        // The guest can only be approved for a configured selector
+        if zkvm_selector == 0 || zkvm_selector > ctx.accounts.registry.count {
+            return err!(ZkError::InvalidZkvmSelector);
+        }
+
+        let dcap_guest = &mut ctx.accounts.dcap_guest;
+        dcap_guest.registry = ctx.accounts.registry.key();
+        dcap_guest.zkvm_selector = zkvm_selector;
+        dcap_guest.guest_vkey = guest_vkey;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::AddDcapGuest,
+            ctx.accounts.registry.registry_id,
+            zkvm_selector,
+            Pubkey::default(),
+            [0; 32],
+            guest_vkey,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn remove_dcap_guest(
+        ctx: Context<RemoveDcapGuest>,
+        zkvm_selector: u64,
+        guest_vkey: [u8; 32],
+    ) -> Result<()> {
+        // Check the owner administers the registry, or the multisig approved this action
+        authorize_admin_action(
+            &ctx.accounts.registry,
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::RemoveDcapGuest {
+                zkvm_selector,
+                guest_vkey,
+            },
+        )?;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::RemoveDcapGuest,
+            ctx.accounts.registry.registry_id,
+            ctx.accounts.dcap_guest.zkvm_selector,
+            Pubkey::default(),
+            ctx.accounts.dcap_guest.guest_vkey,
+            [0; 32],
+        )?;
+
+        // The account is closed by the `close` constraint
+        Ok(())
+    }
+
+    pub fn verify_zkvm_proof(
+        ctx: Context<VerifyZkProof>,
+        zkvm_selector: u64,
+        zk_verify_instruction_data: Vec<u8>,
+    ) -> Result<VerificationResult> {
+        // Anchor sets the result as the return data of the instruction
+        verify_zkvm_proof_cpi(ctx.accounts, zkvm_selector, zk_verify_instruction_data)
+    }
+
+    pub fn verify_dcap_guest_proof(
+        ctx: Context<VerifyDcapGuestProof>,
+        zkvm_selector: u64,
+        zk_verify_instruction_data: Vec<u8>,
+        guest_vkey: [u8; 32],
+    ) -> Result<VerificationResult> {
+        // The guest is approved by the seeds of the `dcap_guest` account, `guest_vkey` is only
+        // read by its constraints
+        let _ = guest_vkey;
+        verify_dcap_guest_proof_cpi(
+            &mut ctx.accounts.verify,
+            &ctx.accounts.dcap_guest,
+            zkvm_selector,
+            zk_verify_instruction_data,
+        )
+    }
+
+    pub fn verify_zkvm_proof_with_journal(
+        ctx: Context<VerifyZkProof>,
+        zkvm_selector: u64,
+        zk_verify_instruction_prefix: Vec<u8>,
+        program_vkey: [u8; 32],
+        journal: Vec<u8>,
+    ) -> Result<VerificationResult> {
+        verify_journal_cpi(
+            ctx.accounts,
+            zkvm_selector,
+            zk_verify_instruction_prefix,
+            program_vkey,
+            &journal,
+        )
+    }
+
+    pub fn verify_zkvm_proof_and_call<'info>(
+        ctx: Context<'_, '_, 'info, 'info, VerifyZkProofAndCall<'info>>,
+        zkvm_selector: u64,
+        zk_verify_instruction_data: Vec<u8>,
+        callback_data: Vec<u8>,
+    ) -> Result<VerificationResult> {
+        // Step 1: Verify the proof through the registry
+        let result = verify_zkvm_proof_cpi(
+            &mut ctx.accounts.verify,
+            zkvm_selector,
+            zk_verify_instruction_data,
+        )?;
+
+        // Step 2: Call back the consumer program with the verified statement written right after the
+        // discriminator of the callback data, where the caller cannot place a forged one
+        let callback_authority = &ctx.accounts.callback_authority;
+        let mut accounts = vec![AccountMeta::new_readonly(callback_authority.key(), true)];
+        let mut account_infos = vec![callback_authority.to_account_info()];
+        for account in ctx.remaining_accounts {
+            accounts.push(if account.is_writable {
+                AccountMeta::new(account.key(), account.is_signer)
+            } else {
+                AccountMeta::new_readonly(account.key(), account.is_signer)
+            });
+            account_infos.push(account.to_account_info());
+        }
+
+        let data = callback_instruction_data(&callback_data, &result)?;
+
+        invoke_signed(
+            &Instruction {
+                program_id: ctx.accounts.callback_program.key(),
+                accounts,
+                data,
+            },
+            &account_infos,
+            &[&[CALLBACK_AUTHORITY_SEED, &[ctx.bumps.callback_authority]]],
+        )?;
+
+        Ok(result)
+    }
+
+    pub fn verify_fresh_zkvm_proof(
+        ctx: Context<VerifyFreshZkProof>,
+        zkvm_selector: u64,
+        zk_verify_instruction_data: Vec<u8>,
+        journal: Vec<u8>,
+        nonce: [u8; 32],
+    ) -> Result<VerificationResult> {
+        // Step 1: Check the proof commits to the journal
+        let (_, output_digest) = split_vkey_and_output_digest(&zk_verify_instruction_data)?;
+        let journal_digest = ctx.accounts.verify.zkvm_verifier_account.journal_digest;
+        if output_digest != journal_digest.digest(&journal) {
+            return err!(ZkError::OutputDigestMismatch);
+        }
+
+        // Step 2: Check the freshness envelope, its nonce is marked used by the `init` constraint
+        let envelope = FreshnessEnvelope::decode(&journal)?;
+        if envelope.nonce != nonce {
+            return err!(ZkError::FreshnessNonceMismatch);
+        }
+
+        let clock = Clock::get()?;
+        envelope.check(
+            &clock,
+            &ctx.accounts.slot_hashes,
+            &ctx.accounts.instructions_sysvar,
+            &ctx.accounts.caller.key(),
+        )?;
+        ctx.accounts.proof_nonce.slot = clock.slot;
+
+        // Step 3: Verify the proof through the registry
+        verify_zkvm_proof_cpi(
+            &mut ctx.accounts.verify,
+            zkvm_selector,
+            zk_verify_instruction_data,
+        )
+    }
+
+    pub fn close_proof_nonce(ctx: Context<CloseProofNonce>, _nonce: [u8; 32]) -> Result<()> {
+        // The caller reclaims the rent once no proof carrying the nonce can be fresh anymore
+        if !ctx.accounts.proof_nonce.is_expired(Clock::get()?.slot) {
+            return err!(ZkError::ProofNonceNotExpired);
+        }
+
+        // The account is closed by the `close` constraint
+        Ok(())
+    }
+
+    pub fn init_dcap_collateral_config(
+        ctx: Context<InitDcapCollateralConfig>,
+        root_ca_hash: [u8; 32],
+        root_crl_hash: [u8; 32],
+        pck_crl_hash: [u8; 32],
+        tcb_evaluation_data_numbers: Vec<u32>,
+        max_collateral_age: u64,
+    ) -> Result<()> {
+        // Check the owner holds the admin role, or the multisig approved this action
+        authorize_program_admin_action(
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::SetDcapCollateralConfig {
+                root_ca_hash,
+                root_crl_hash,
+                pck_crl_hash,
+                tcb_evaluation_data_numbers: tcb_evaluation_data_numbers.clone(),
+                max_collateral_age,
+            },
+        )?;
+
+        let config = &mut ctx.accounts.dcap_collateral_config;
+        config.set(
+            root_ca_hash,
+            root_crl_hash,
+            pck_crl_hash,
+            tcb_evaluation_data_numbers,
+            max_collateral_age,
+        )?;
+        let new_config = config.digest()?;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::SetDcapCollateralConfig,
+            DEFAULT_REGISTRY_ID,
+            0,
+            Pubkey::default(),
+            [0; 32],
+            new_config,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn update_dcap_collateral_config(
+        ctx: Context<UpdateDcapCollateralConfig>,
+        root_ca_hash: [u8; 32],
+        root_crl_hash: [u8; 32],
+        pck_crl_hash: [u8; 32],
+        tcb_evaluation_data_numbers: Vec<u32>,
+        max_collateral_age: u64,
+    ) -> Result<()> {
+        // Check the owner holds the admin role, or the multisig approved this action
+        authorize_program_admin_action(
+            &ctx.accounts.owner.key(),
+            &ctx.accounts.program_data,
+            ctx.accounts.owner_role.as_deref(),
+            &ctx.accounts.admin_multisig,
+            ctx.accounts.admin_proposal.as_deref_mut(),
+            ctx.accounts.queued_admin_action.as_deref(),
+            &AdminAction::SetDcapCollateralConfig {
+                root_ca_hash,
+                root_crl_hash,
+                pck_crl_hash,
+                tcb_evaluation_data_numbers: tcb_evaluation_data_numbers.clone(),
+                max_collateral_age,
+            },
+        )?;
+
+        let config = &mut ctx.accounts.dcap_collateral_config;
+        let old_config = config.digest()?;
+        config.set(
+            root_ca_hash,
+            root_crl_hash,
+            pck_crl_hash,
+            tcb_evaluation_data_numbers,
+            max_collateral_age,
+        )?;
+        let new_config = config.digest()?;
+
+        record_admin_action(
+            &ctx.accounts.audit_log,
+            ctx.accounts.owner.key(),
+            AuditAction::SetDcapCollateralConfig,
+            DEFAULT_REGISTRY_ID,
+            0,
+            Pubkey::default(),
+            old_config,
+            new_config,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn init_dcap_policy(
+        ctx: Context<InitDcapPolicy>,
+        policy_id: u64,
+        allowed_tcb_statuses: u8,
+        min_tcb_evaluation_data_number: u32,
+        measurements: Vec<[u8; 48]>,
+        enclave_key_validity: u64,
+    ) -> Result<()> {
+        if measurements.len() > MAX_DCAP_POLICY_MEASUREMENTS {
+            return err!(ZkError::TooManyDcapPolicyMeasurements);
+        }
+
+        let dcap_policy = &mut ctx.accounts.dcap_policy;
+        dcap_policy.authority = ctx.accounts.authority.key();
+        dcap_policy.policy_id = policy_id;
+        dcap_policy.allowed_tcb_statuses = allowed_tcb_statuses;
+        dcap_policy.min_tcb_evaluation_data_number = min_tcb_evaluation_data_number;
+        dcap_policy.measurements = measurements;
+        dcap_policy.enclave_key_validity = enclave_key_validity;
+
+        Ok(())
+    }
+
+    pub fn update_dcap_policy(
+        ctx: Context<UpdateDcapPolicy>,
+        allowed_tcb_statuses: u8,
+        min_tcb_evaluation_data_number: u32,
+        measurements: Vec<[u8; 48]>,
+        enclave_key_validity: u64,
+    ) -> Result<()> {
+        if measurements.len() > MAX_DCAP_POLICY_MEASUREMENTS {
+            return err!(ZkError::TooManyDcapPolicyMeasurements);
+        }
+
+        let dcap_policy = &mut ctx.accounts.dcap_policy;
+        dcap_policy.allowed_tcb_statuses = allowed_tcb_statuses;
+        dcap_policy.min_tcb_evaluation_data_number = min_tcb_evaluation_data_number;
+        dcap_policy.measurements = measurements;
+        dcap_policy.enclave_key_validity = enclave_key_validity;
+
+        Ok(())
+    }
+
+    pub fn verify_dcap_proof(
+        ctx: Context<VerifyDcapProof>,
+        zkvm_selector: u64,
+        zk_verify_instruction_prefix: Vec<u8>,
+        guest_vkey: [u8; 32],
+    ) -> Result<()> {
+        // The guest is approved by the seeds of the `dcap_guest` account
+        let _ = guest_vkey;
+
+        verify_dcap_proof_cpi(ctx.accounts, zkvm_selector, zk_verify_instruction_prefix)?;
+
+        Ok(())
+    }
+
+    pub fn register_enclave_key(
+        ctx: Context<RegisterEnclaveKey>,
+        zkvm_selector: u64,
+        zk_verify_instruction_prefix: Vec<u8>,
+        guest_vkey: [u8; 32],
+        key_type: EnclaveKeyType,
+        key: [u8; 32],
+    ) -> Result<()> {
+        // The guest is approved by the seeds of the `dcap_guest` account
+        let _ = guest_vkey;
+
+        // Step 1: Verify the DCAP proof, its collateral and the application policy
+        let verified_output = verify_dcap_proof_cpi(
+            &mut ctx.accounts.dcap,
+            zkvm_selector,
+            zk_verify_instruction_prefix,
+        )?;
+
+        // Step 2: The enclave must have committed to the key in its report data
+        if key_type.key_from_report_data(&verified_output.report_data) != key {
+            return err!(ZkError::EnclaveKeyMismatch);
+        }
+
+        // Step 3: Remember the key until the policy validity period elapses
+        let dcap_policy = &ctx.accounts.dcap.dcap_policy;
+        let enclave_key = &mut ctx.accounts.enclave_key;
+        enclave_key.dcap_policy = dcap_policy.key();
+        enclave_key.key_type = key_type;
+        enclave_key.key = key;
+        enclave_key.tee_type = verified_output.tee_type;
+        enclave_key.measurement = verified_output.measurement;
+        enclave_key.mr_signer = verified_output.mr_signer;
+        enclave_key.expires_at = dcap_policy.enclave_key_expires_at(verified_output.timestamp)?;
+        enclave_key.payer = ctx.accounts.payer.key();
+
+        Ok(())
+    }
+
+    pub fn init_hybrid_policy(
+        ctx: Context<InitHybridPolicy>,
+        policy_id: u64,
+        mode: HybridMode,
+        registry: Pubkey,
+        zkvm_selector: u64,
+        program_vkey: [u8; 32],
+        dcap_policy: Pubkey,
+    ) -> Result<()> {
+        let hybrid_policy = &mut ctx.accounts.hybrid_policy;
+        hybrid_policy.authority = ctx.accounts.authority.key();
+        hybrid_policy.policy_id = policy_id;
+        hybrid_policy.mode = mode;
+        hybrid_policy.registry = registry;
+        hybrid_policy.zkvm_selector = zkvm_selector;
+        hybrid_policy.program_vkey = program_vkey;
+        hybrid_policy.dcap_policy = dcap_policy;
+
+        Ok(())
+    }
+
+    pub fn update_hybrid_policy(
+        ctx: Context<UpdateHybridPolicy>,
+        mode: HybridMode,
+        registry: Pubkey,
+        zkvm_selector: u64,
+        program_vkey: [u8; 32],
+        dcap_policy: Pubkey,
+    ) -> Result<()> {
+        let hybrid_policy = &mut ctx.accounts.hybrid_policy;
+        hybrid_policy.mode = mode;
+        hybrid_policy.registry = registry;
+        hybrid_policy.zkvm_selector = zkvm_selector;
+        hybrid_policy.program_vkey = program_vkey;
+        hybrid_policy.dcap_policy = dcap_policy;
+
+        Ok(())
+    }
+
+    pub fn verify_hybrid(
+        ctx: Context<VerifyHybrid>,
+        zkvm_selector: u64,
+        zk_verify_instruction_data: Vec<u8>,
+        output_digest: [u8; 32],
+    ) -> Result<HybridVerificationResult> {
+        // Accept `output_digest` according to the hybrid policy, with a ZK proof and, if an enclave
+        // key is given, an enclave signature. See `verify_hybrid_enclave` for enclave signatures only
+        let hybrid_policy = &ctx.accounts.hybrid_policy;
+        if zkvm_selector != hybrid_policy.zkvm_selector {
+            return err!(ZkError::ZkvmSelectorMismatch);
+        }
+        if ctx.accounts.verify.zkvm_verifier_account.registry != hybrid_policy.registry {
+            return err!(ZkError::RegistryMismatch);
+        }
+
+        // Step 1: Verify the ZK proof of the statement
+        let (vkey, proof_output_digest) = split_vkey_and_output_digest(&zk_verify_instruction_data)?;
+        if vkey != hybrid_policy.program_vkey {
+            return err!(ZkError::HybridVkeyMismatch);
+        }
+        if proof_output_digest != output_digest {
+            return err!(ZkError::OutputDigestMismatch);
+        }
+
+        verify_zkvm_proof_cpi(
+            &mut ctx.accounts.verify,
+            zkvm_selector,
+            zk_verify_instruction_data,
+        )?;
+
+        // Step 2: Check the attested enclave signature of the statement, if provided
+        let tee_verified = check_hybrid_enclave_signature(
+            hybrid_policy,
+            ctx.accounts.enclave_key.as_deref(),
+            &ctx.accounts.instructions_sysvar,
+            &output_digest,
+        )?;
+
+        // Step 3: Evaluate the policy
+        if !hybrid_policy.mode.is_satisfied(true, tee_verified) {
+            return err!(ZkError::HybridPolicyNotSatisfied);
+        }
+
+        // An enclave key is only accepted once its signature was checked
+        record_hybrid_verification(
+            hybrid_policy,
+            output_digest,
+            true,
+            ctx.accounts.enclave_key.as_ref().map(|enclave_key| enclave_key.key()),
+        )
+    }
+
+    pub fn verify_hybrid_enclave(
+        ctx: Context<VerifyHybridEnclave>,
+        output_digest: [u8; 32],
+    ) -> Result<HybridVerificationResult> {
+        // Accept `output_digest` with an enclave signature only, without the accounts of a ZK proof
+        if ctx.accounts.registry.paused {
+            return err!(ZkError::RegistryPaused);
+        }
+        let hybrid_policy = &ctx.accounts.hybrid_policy;
+        let tee_verified = check_hybrid_enclave_signature(
+            hybrid_policy,
+            Some(&ctx.accounts.enclave_key),
+            &ctx.accounts.instructions_sysvar,
+            &output_digest,
+        )?;
+
+        if !hybrid_policy.mode.is_satisfied(false, tee_verified) {
+            return err!(ZkError::HybridPolicyNotSatisfied);
+        }
+
+        record_hybrid_verification(
+            hybrid_policy,
+            output_digest,
+            false,
+            Some(ctx.accounts.enclave_key.key()),
+        )
+    }
+
+    pub fn close_enclave_key(ctx: Context<CloseEnclaveKey>) -> Result<()> {
+        // Anyone can reclaim the rent for the original payer once the key has expired
+        if ctx.accounts.enclave_key.expires_at > Clock::get()?.unix_timestamp {
+            return err!(ZkError::EnclaveKeyNotExpired);
+        }
+
+        // The account is closed by the `close` constraint
+        Ok(())
+    }
+
+    pub fn init_verified_state(
+        ctx: Context<InitVerifiedState>,
+        state_id: u64,
+        registry: Pubkey,
+        zkvm_selector: u64,
+        guest_vkey: [u8; 32],
+        initial_root: [u8; 32],
+    ) -> Result<()> {
+        let verified_state = &mut ctx.accounts.verified_state;
+        verified_state.owner = ctx.accounts.owner.key();
+        verified_state.state_id = state_id;
+        verified_state.registry = registry;
+        verified_state.zkvm_selector = zkvm_selector;
+        verified_state.guest_vkey = guest_vkey;
+        verified_state.root = initial_root;
+        verified_state.sequence = 0;
+        verified_state.last_advanced_slot = Clock::get()?.slot;
+
+        Ok(())
+    }
+
+    pub fn advance_state(
+        ctx: Context<AdvanceState>,
+        zkvm_selector: u64,
+        zk_verify_instruction_data: Vec<u8>,
+        journal: Vec<u8>,
+    ) -> Result<()> {
+        // Step 1: Check the proof is of the state transition guest and commits to the journal
+        let verified_state = &ctx.accounts.verified_state;
+        if zkvm_selector != verified_state.zkvm_selector {
+            return err!(ZkError::ZkvmSelectorMismatch);
+        }
+        if ctx.accounts.verify.zkvm_verifier_account.registry != verified_state.registry {
+            return err!(ZkError::RegistryMismatch);
+        }
+
+        let (vkey, output_digest) = split_vkey_and_output_digest(&zk_verify_instruction_data)?;
+        if vkey != verified_state.guest_vkey {
+            return err!(ZkError::StateGuestMismatch);
+        }
+        let journal_digest = ctx.accounts.verify.zkvm_verifier_account.journal_digest;
+        if output_digest != journal_digest.digest(&journal) {
+            return err!(ZkError::OutputDigestMismatch);
+        }
+
+        // Step 2: Apply the transition, which must continue from the current root and sequence
+        let transition = StateTransition::decode(&journal)?;
+        let slot = Clock::get()?.slot;
+        ctx.accounts.verified_state.advance(&transition, slot)?;
+
+        // Step 3: Verify the proof through the registry
+        verify_zkvm_proof_cpi(
+            &mut ctx.accounts.verify,
+            zkvm_selector,
+            zk_verify_instruction_data,
+        )?;
+
+        emit!(StateAdvanced {
+            verified_state: ctx.accounts.verified_state.key(),
+            sequence: transition.sequence,
+            old_root: transition.old_root,
+            new_root: transition.new_root,
+            slot,
+        });
+
+        Ok(())
+    }
+
+    pub fn init_journal_buffer(
+        ctx: Context<InitJournalBuffer>,
+        buffer_id: u64,
+        journal_len: u32,
+    ) -> Result<()> {
+        if journal_len as usize > MAX_VERIFIED_JOURNAL_LEN {
+            return err!(ZkError::JournalTooLarge);
+        }
+
+        let verified_journal = &mut ctx.accounts.verified_journal;
+        verified_journal.authority = ctx.accounts.authority.key();
+        verified_journal.buffer_id = buffer_id;
+        verified_journal.journal = vec![0; journal_len as usize];
+
+        Ok(())
+    }
+
+    pub fn write_journal_buffer(
+        ctx: Context<WriteJournalBuffer>,
+        offset: u32,
+        bytes: Vec<u8>,
+    ) -> Result<()> {
+        let verified_journal = &mut ctx.accounts.verified_journal;
+        if verified_journal.verified {
+            return err!(ZkError::JournalAlreadyVerified);
+        }
+
+        let start = offset as usize;
+        let chunk = start
+            .checked_add(bytes.len())
+            .and_then(|end| verified_journal.journal.get_mut(start..end))
+            .ok_or(error!(ZkError::JournalBufferOutOfBounds))?;
+        chunk.copy_from_slice(&bytes);
+
+        Ok(())
+    }
+
+    pub fn verify_journal_buffer(
+        ctx: Context<VerifyJournalBuffer>,
+        zkvm_selector: u64,
+        zk_verify_instruction_prefix: Vec<u8>,
+        program_vkey: [u8; 32],
+    ) -> Result<VerificationResult> {
+        let verified_journal = &mut ctx.accounts.verified_journal;
+        if verified_journal.verified {
+            return err!(ZkError::JournalAlreadyVerified);
+        }
+
+        let result = verify_journal_cpi(
+            &mut ctx.accounts.verify,
+            zkvm_selector,
+            zk_verify_instruction_prefix,
+            program_vkey,
+            &verified_journal.journal,
+        )?;
+        verified_journal.mark_verified(&result);
+
+        Ok(result)
+    }
+
+    pub fn store_verified_journal(
+        ctx: Context<StoreVerifiedJournal>,
+        zkvm_selector: u64,
+        zk_verify_instruction_prefix: Vec<u8>,
+        program_vkey: [u8; 32],
+        journal: Vec<u8>,
+        buffer_id: u64,
+    ) -> Result<VerificationResult> {
+        if journal.len() > MAX_VERIFIED_JOURNAL_LEN {
+            return err!(ZkError::JournalTooLarge);
+        }
+
+        let result = verify_journal_cpi(
+            &mut ctx.accounts.verify,
+            zkvm_selector,
+            zk_verify_instruction_prefix,
+            program_vkey,
+            &journal,
+        )?;
+
+        let verified_journal = &mut ctx.accounts.verified_journal;
+        verified_journal.authority = ctx.accounts.authority.key();
+        verified_journal.buffer_id = buffer_id;
+        verified_journal.journal = journal;
+        verified_journal.mark_verified(&result);
+
+        Ok(result)
+    }
+
+    pub fn close_verified_journal(_ctx: Context<CloseVerifiedJournal>) -> Result<()> {
+        // The account is closed by the `close` constraint
+        Ok(())
+    }
+
+    pub fn prove_journal_field(
+        ctx: Context<ProveJournalField>,
+        registry: Pubkey,
+        zkvm_selector: u64,
+        program_vkey: [u8; 32],
+        index: u32,
+        value: Vec<u8>,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        // The verified journal of a Merkleized guest is the Merkle root of its fields
+        let journal = ctx
+            .accounts
+            .verified_journal
+            .verified_journal(&registry, zkvm_selector, &program_vkey)?;
+        let root: [u8; 32] = journal
+            .try_into()
+            .map_err(|_| error!(ZkError::MalformedJournal))?;
+
+        if !merkle::verify_field(&root, index, &value, &proof) {
+            return err!(ZkError::InvalidJournalFieldProof);
+        }
+
+        Ok(())
+    }
+}
+
+// Helper function to verify a proof by CPI into the configured zkVM verifier program, refusing DCAP
+// verifiers whose proofs must be checked against an approved DCAP guest first
+fn verify_zkvm_proof_cpi(
+    accounts: &mut VerifyZkProof,
+    zkvm_selector: u64,
+    zk_verify_instruction_data: Vec<u8>,
+) -> Result<VerificationResult> {
+    if accounts.zkvm_verifier_account.dcap {
+        return err!(ZkError::DcapVerifierReserved);
+    }
+
+    invoke_registered_verifier(accounts, zkvm_selector, zk_verify_instruction_data)
+}
+
+// Helper function to verify a proof of an approved DCAP guest program, under any verifier
+fn verify_dcap_guest_proof_cpi(
+    accounts: &mut VerifyZkProof,
+    dcap_guest: &DcapGuest,
+    zkvm_selector: u64,
+    zk_verify_instruction_data: Vec<u8>,
+) -> Result<VerificationResult> {
+    let (vkey, _output_digest) = split_vkey_and_output_digest(&zk_verify_instruction_data)?;
+    if vkey != dcap_guest.guest_vkey {
+        return err!(ZkError::DcapGuestNotApproved);
+    }
+
+    invoke_registered_verifier(accounts, zkvm_selector, zk_verify_instruction_data)
+}
+
+// Helper function to CPI into the zkVM verifier program configured for the selector and record the
+// verified statement
+fn invoke_registered_verifier(
+    accounts: &mut VerifyZkProof,
+    zkvm_selector: u64,
+    zk_verify_instruction_data: Vec<u8>,
+) -> Result<VerificationResult> {
+    // Step 1: Check zkvm selector matches with the expected zkvm_verifier_program
+    let zkvm_verifier = &accounts.zkvm_verifier_account;
+    if zkvm_verifier.zkvm_selector != zkvm_selector
+        || zkvm_verifier.zkvm_program_id != accounts.zkvm_verifier_program.key()
+    {
+        return err!(ZkError::ZkvmSelectorMismatch);
+    }
+
+    // Step 2: Check if the registry is paused, or the zkvm_verifier_program frozen or not activated yet
+    if accounts.registry.paused {
+        return err!(ZkError::RegistryPaused);
+    }
+    if zkvm_verifier.frozen {
+        return err!(ZkError::ZkvmProgramFrozen);
+    }
+    if zkvm_verifier.pending {
+        return err!(ZkError::ZkvmProgramPending);
+    }
+
+    // Step 2b: Refuse deprecated verifiers past their sunset slot, warn otherwise
+    if zkvm_verifier.deprecated {
+        if Clock::get()?.slot > zkvm_verifier.sunset_slot {
+            return err!(ZkError::ZkvmProgramSunset);
+        }
+        msg!(
+            "WARNING: zkVM verifier {} is deprecated and will be refused after slot {}, migrate to selector {} ({})",
+            zkvm_verifier.zkvm_program_id,
+            zkvm_verifier.sunset_slot,
+            zkvm_verifier.successor_selector,
+            zkvm_verifier.successor_program_id
+        );
+    }
+
+    // Step 3: Refuse verifier programs that were upgraded since they were configured
+    if program_deploy_slot(&accounts.zkvm_verifier_program_data)
+        != zkvm_verifier.zkvm_program_deploy_slot
+    {
+        return err!(ZkError::ZkvmProgramUpgraded);
+    }
+
+    // Step 3b: Identify the caller and enforce the allowlist of permissioned verifiers
+    let caller = identify_caller(accounts)?;
+    if zkvm_verifier.permissioned {
+        match &accounts.allowed_caller {
+            Some(allowed)
+                if caller != Pubkey::default()
+                    && allowed.zkvm_verifier == zkvm_verifier.key()
+                    && allowed.caller == caller => {}
+            _ => return err!(ZkError::CallerNotAllowed),
+        }
+    }
+
+    // Step 4: Reject instruction data that cannot hold a vkey and an output digest
+    let (program_vkey, output_digest) = split_vkey_and_output_digest(&zk_verify_instruction_data)?;
+
+    // Step 5: Make sure the verifier has enough compute left to run
+    if sol_remaining_compute_units() < MIN_ZK_VERIFY_COMPUTE_UNITS {
+        return err!(ZkError::ComputeBudgetExhausted);
+    }
+
+    // Step 6: Perform CPI to zkvm_verifier_program
+    invoke_zkvm_verifier_program(
+        &accounts.zkvm_verifier_program,
+        &accounts.system_program,
+        zk_verify_instruction_data,
+    )?;
+
+    // Step 7: Record usage statistics
+    let clock = Clock::get()?;
+    accounts.zkvm_verifier_stats.record_verification(&clock);
+
+    let result = VerificationResult {
+        registry: zkvm_verifier.registry,
+        zkvm_selector,
+        zkvm_program_id: accounts.zkvm_verifier_program.key(),
+        program_vkey,
+        output_digest,
+        slot: clock.slot,
+        revocation_epoch: zkvm_verifier.revocation_epoch,
+    };
+
+    // Step 8: Append the statement to the verification accumulator
+    let statement_hash = result.statement_hash();
+    let mut accumulator = accounts.verification_accumulator.load_mut()?;
+    let leaf_index = accumulator.append(statement_hash)?;
+    emit!(StatementVerified {
+        statement_hash,
+        registry: result.registry,
+        zkvm_selector,
+        zkvm_program_id: result.zkvm_program_id,
+        revocation_epoch: result.revocation_epoch,
+        caller,
+        shard: accumulator.shard,
+        leaf_index,
+        root: accumulator.root(),
+    });
+
+    Ok(result)
+}
+
+// Helper function to check the attested enclave signature of a hybrid statement, returning whether
+// an enclave key was given
+fn check_hybrid_enclave_signature(
+    hybrid_policy: &HybridPolicy,
+    enclave_key: Option<&EnclaveKey>,
+    instructions_sysvar: &AccountInfo,
+    output_digest: &[u8; 32],
+) -> Result<bool> {
+    let Some(enclave_key) = enclave_key else {
+        return Ok(false);
+    };
+    if enclave_key.dcap_policy != hybrid_policy.dcap_policy {
+        return err!(ZkError::EnclaveKeyPolicyMismatch);
+    }
+    enclave::check_enclave_signature(instructions_sysvar, enclave_key, output_digest)?;
+
+    Ok(true)
+}
+
+// Helper function to build the result of a statement accepted under a hybrid policy, recording which
+// evidence satisfied it in an event
+fn record_hybrid_verification(
+    hybrid_policy: &Account<HybridPolicy>,
+    output_digest: [u8; 32],
+    zk_verified: bool,
+    enclave_key: Option<Pubkey>,
+) -> Result<HybridVerificationResult> {
+    let result = HybridVerificationResult {
+        hybrid_policy: hybrid_policy.key(),
+        mode: hybrid_policy.mode,
+        output_digest,
+        zk_verified,
+        tee_verified: enclave_key.is_some(),
+        enclave_key: enclave_key.unwrap_or_default(),
+        slot: Clock::get()?.slot,
+    };
+
+    emit!(HybridStatementVerified {
+        hybrid_policy: result.hybrid_policy,
+        mode: result.mode,
+        output_digest,
+        zk_verified,
+        tee_verified: result.tee_verified,
+        enclave_key: result.enclave_key,
+        slot: result.slot,
+    });
+
+    Ok(result)
+}
+
+// Helper function to identify who directly requested a verification: the caller signer if any,
+// which is a wallet at the top level or the caller authority PDA of a consumer program under CPI,
+// else the program of the top-level instruction when it invoked the registry itself, and the
+// default pubkey otherwise. Deeper in a CPI chain the top-level program is not the direct caller.
+fn identify_caller(accounts: &VerifyZkProof) -> Result<Pubkey> {
+    if let Some(signer) = &accounts.caller_signer {
+        return Ok(signer.key());
+    }
+
+    if get_stack_height() == TRANSACTION_LEVEL_STACK_HEIGHT + 1 {
+        if let Some(instructions_sysvar) = &accounts.instructions_sysvar {
+            let current_index = load_current_index_checked(instructions_sysvar)?;
+            let current = load_instruction_at_checked(current_index as usize, instructions_sysvar)?;
+            return Ok(current.program_id);
+        }
+    }
+
+    Ok(Pubkey::default())
+}
+
+// Helper function to verify a proof of a raw journal, completing the verify instruction with the
+// vkey and the zkVM-specific digest of the journal
+fn verify_journal_cpi(
+    accounts: &mut VerifyZkProof,
+    zkvm_selector: u64,
+    zk_verify_instruction_prefix: Vec<u8>,
+    program_vkey: [u8; 32],
+    journal: &[u8],
+) -> Result<VerificationResult> {
+    let output_digest = accounts.zkvm_verifier_account.journal_digest.digest(journal);
+
+    let mut zk_verify_instruction_data = zk_verify_instruction_prefix;
+    zk_verify_instruction_data.extend_from_slice(&program_vkey);
+    zk_verify_instruction_data.extend_from_slice(&output_digest);
+
+    verify_zkvm_proof_cpi(accounts, zkvm_selector, zk_verify_instruction_data)
+}
+
+// Helper function to verify a DCAP proof of the journal buffer against the collateral config and
+// policy, returning the decoded journal
+fn verify_dcap_proof_cpi(
+    accounts: &mut VerifyDcapProof,
+    zkvm_selector: u64,
+    zk_verify_instruction_prefix: Vec<u8>,
+) -> Result<DcapVerifiedOutput> {
+    let journal = &accounts.verified_journal.journal;
+    if accounts.verified_journal.verified {
+        return err!(ZkError::JournalAlreadyVerified);
+    }
+
+    // Step 1: Decode the journal, check its collateral and enforce the application policy
+    let verified_output = DcapVerifiedOutput::decode(journal)?;
+    accounts
+        .dcap_collateral_config
+        .check(&verified_output, Clock::get()?.unix_timestamp)?;
+    accounts.dcap_policy.check(&verified_output)?;
+
+    // Step 2: Verify the proof of the approved guest committing to the journal
+    let output_digest = accounts.verify.zkvm_verifier_account.journal_digest.digest(journal);
+    let mut zk_verify_instruction_data = zk_verify_instruction_prefix;
+    zk_verify_instruction_data.extend_from_slice(&accounts.dcap_guest.guest_vkey);
+    zk_verify_instruction_data.extend_from_slice(&output_digest);
+
+    let result = verify_dcap_guest_proof_cpi(
+        &mut accounts.verify,
+        &accounts.dcap_guest,
+        zkvm_selector,
+        zk_verify_instruction_data,
+    )?;
+    accounts.verified_journal.mark_verified(&result);
+
+    Ok(verified_output)
+}
+
+// Helper function to run the verify instruction of a zkVM verifier program
+fn invoke_zkvm_verifier_program<'info>(
+    zkvm_verifier_program: &AccountInfo<'info>,
+    system_program: &Program<'info, System>,
+    zk_verify_instruction_data: Vec<u8>,
+) -> std::result::Result<(), ProgramError> {
+    let verify_cpi_context = CpiContext::new(
+        zkvm_verifier_program.to_account_info(),
+        vec![system_program.to_account_info()],
+    );
+
+    invoke(
+        &Instruction {
+            program_id: zkvm_verifier_program.key(),
+            accounts: verify_cpi_context.to_account_metas(None),
+            data: zk_verify_instruction_data,
+        },
+        &[system_program.to_account_info()],
+    )
+}
+
+/// Helper function to read the vkey and output digest at the end of the verify instruction data
+pub fn split_vkey_and_output_digest(zk_verify_instruction_data: &[u8]) -> Result<([u8; 32], [u8; 32])> {
+    let len = zk_verify_instruction_data.len();
+    if len < MIN_ZK_VERIFY_INSTRUCTION_DATA_LEN {
+        return err!(ZkError::MalformedProof);
+    }
+
+    let vkey = zk_verify_instruction_data[len - 64..len - 32].try_into().unwrap();
+    let output_digest = zk_verify_instruction_data[len - 32..].try_into().unwrap();
+
+    Ok((vkey, output_digest))
+}
+
+/// Helper function to build the instruction data of a `verify_zkvm_proof_and_call` callback: the 8 byte
+/// discriminator of `callback_data`, the `VerificationResult`, then the rest of `callback_data`.
+/// The result is the first argument of an Anchor callee, which must never decode a result from the
+/// rest of the callback data as the caller controls it.
+pub fn callback_instruction_data(
+    callback_data: &[u8],
+    result: &VerificationResult,
+) -> Result<Vec<u8>> {
+    if callback_data.len() < CALLBACK_DISCRIMINATOR_LEN {
+        return err!(ZkError::MalformedCallbackData);
+    }
+
+    let (discriminator, args) = callback_data.split_at(CALLBACK_DISCRIMINATOR_LEN);
+    let mut data = Vec::with_capacity(callback_data.len() + VERIFICATION_RESULT_LEN);
+    data.extend_from_slice(discriminator);
+    result.serialize(&mut data)?;
+    data.extend_from_slice(args);
+
+    Ok(data)
+}
+
+// Helper function to check if a pubkey matches the program's upgrade authority
+fn check_program_upgrade_authority(
+    program_data_info: &AccountInfo,
+    owner_key: &Pubkey,
+) -> bool {
+    // Parse the program data account
+    let data = program_data_info.try_borrow_data().unwrap();
+
+    // The upgrade authority is at offset 13 (1 byte for is_initialized + 8 bytes for slot + 4 bytes for upgrade authority present)
+    // If the upgrade authority is present (byte at offset 12 is 1), then the next 32 bytes are the upgrade authority pubkey
+    if data[12] == 1 {
+        let mut authority = [0u8; 32];
+        authority.copy_from_slice(&data[13..45]);
+        let authority_pubkey = Pubkey::new_from_array(authority);
+
+        return &authority_pubkey == owner_key;
+    }
+
+    false
+}
+
+// Helper function to check the owner holds `role`: the program upgrade authority holds every role,
+// other keys need a role assignment granting it
+fn check_role(
+    program_data_info: &AccountInfo,
+    owner_key: &Pubkey,
+    owner_role: Option<&RoleAssignment>,
+    role: Role,
+) -> Result<()> {
+    if check_program_upgrade_authority(program_data_info, owner_key) {
+        return Ok(());
+    }
+    match owner_role {
+        Some(assignment) if assignment.member == *owner_key && assignment.role.grants(role) => Ok(()),
+        _ => err!(ZkError::Unauthorized),
+    }
+}
+
+// Helper function to check the owner holds `role` on a registry: tenant registries are governed by
+// their admin, the default registry by the program roles
+fn check_registry_role(
+    registry: &Registry,
+    program_data_info: &AccountInfo,
+    owner_key: &Pubkey,
+    owner_role: Option<&RoleAssignment>,
+    role: Role,
+) -> Result<()> {
+    if !registry.is_default() {
+        return registry.check_admin(owner_key);
+    }
+    check_role(program_data_info, owner_key, owner_role, role)
+}
+
+// Helper function to check the owner can freeze verifiers and pause the registry: the default
+// registry trusts program guardians and admin multisig signers, a tenant registry only its own
+// admin and guardian
+fn check_registry_guardian(
+    registry: &Registry,
+    program_data_info: &AccountInfo,
+    owner_key: &Pubkey,
+    owner_role: Option<&RoleAssignment>,
+    admin_multisig_info: &AccountInfo,
+) -> Result<()> {
+    if !registry.is_default() {
+        return registry.check_guardian(owner_key);
+    }
+    if check_role(program_data_info, owner_key, owner_role, Role::Guardian).is_ok() {
+        return Ok(());
+    }
+    check_admin_multisig_signer(admin_multisig_info, owner_key)
+}
+
+// Helper function to authorize a registry admin action: the admin of a tenant registry executes it
+// once queued in the registry timelock. On the default registry an admin executes it once queued
+// until the admin multisig is initialized, then it needs a proposal for exactly this action whose
+// timelock expired
+#[allow(clippy::too_many_arguments)]
+fn authorize_admin_action(
+    registry: &Registry,
+    owner_key: &Pubkey,
+    program_data_info: &AccountInfo,
+    owner_role: Option<&RoleAssignment>,
+    admin_multisig_info: &AccountInfo,
+    admin_proposal: Option<&mut AdminProposal>,
+    queued_admin_action: Option<&QueuedAdminAction>,
+    action: &AdminAction,
+) -> Result<()> {
+    if !registry.is_default() {
+        registry.check_admin(owner_key)?;
+        return check_queued_admin_action(queued_admin_action, action);
+    }
+    if admin_multisig_info.data_is_empty() {
+        check_role(program_data_info, owner_key, owner_role, Role::Admin)?;
+        return check_queued_admin_action(queued_admin_action, action);
+    }
+
+    execute_admin_proposal(admin_multisig_info, admin_proposal, action)
+}
+
+// Helper function to authorize an admin action that is not scoped to a registry, e.g. granting a
+// role: an admin executes it once queued on the default registry until the admin multisig is
+// initialized, then it needs a proposal for exactly this action whose timelock expired
+fn authorize_program_admin_action(
+    owner_key: &Pubkey,
+    program_data_info: &AccountInfo,
+    owner_role: Option<&RoleAssignment>,
+    admin_multisig_info: &AccountInfo,
+    admin_proposal: Option<&mut AdminProposal>,
+    queued_admin_action: Option<&QueuedAdminAction>,
+    action: &AdminAction,
+) -> Result<()> {
+    if admin_multisig_info.data_is_empty() {
+        check_role(program_data_info, owner_key, owner_role, Role::Admin)?;
+        return check_queued_admin_action(queued_admin_action, action);
+    }
+    execute_admin_proposal(admin_multisig_info, admin_proposal, action)
+}
+
+// Helper function to execute the proposal approving `action` under the initialized admin multisig
+fn execute_admin_proposal(
+    admin_multisig_info: &AccountInfo,
+    admin_proposal: Option<&mut AdminProposal>,
+    action: &AdminAction,
+) -> Result<()> {
+    let admin_multisig =
+        AdminMultisig::try_deserialize(&mut &admin_multisig_info.try_borrow_data()?[..])?;
+    let Some(admin_proposal) = admin_proposal else {
+        return err!(ZkError::MultisigApprovalRequired);
+    };
+    admin_proposal.execute(&admin_multisig, action, Clock::get()?.slot)?;
+
+    emit!(AdminActionExecuted {
+        proposal_id: admin_proposal.proposal_id,
+        action: action.clone(),
+    });
+
+    Ok(())
+}
+
+// Helper function to check `signer` is a signer of the initialized admin multisig
+fn check_admin_multisig_signer(admin_multisig_info: &AccountInfo, signer: &Pubkey) -> Result<()> {
+    if admin_multisig_info.data_is_empty() {
+        return err!(ZkError::Unauthorized);
+    }
+    let admin_multisig =
+        AdminMultisig::try_deserialize(&mut &admin_multisig_info.try_borrow_data()?[..])?;
+    admin_multisig.signer_index(signer)?;
+    Ok(())
+}
+
+// Helper function to check an admin action executed by a single admin waited for its timelock in a
+// queued admin action, freezing a verifier executes immediately
+fn check_queued_admin_action(
+    queued_admin_action: Option<&QueuedAdminAction>,
+    action: &AdminAction,
+) -> Result<()> {
+    if action.min_delay_slots() == 0 {
+        return Ok(());
+    }
+    let Some(queued_admin_action) = queued_admin_action else {
+        return err!(ZkError::AdminActionNotQueued);
+    };
+    queued_admin_action.check_executable(action, Clock::get()?.slot)
+}
+
+// Helper function to append an admin action to the audit log
+#[allow(clippy::too_many_arguments)]
+fn record_admin_action(
+    audit_log: &AccountLoader<AuditLog>,
+    actor: Pubkey,
+    action: AuditAction,
+    registry_id: u64,
+    zkvm_selector: u64,
+    subject: Pubkey,
+    old_value: [u8; 32],
+    new_value: [u8; 32],
+) -> Result<()> {
+    audit_log.load_mut()?.append(AuditLogEntry {
+        actor,
+        subject,
+        registry_id,
+        zkvm_selector,
+        slot: Clock::get()?.slot,
+        old_value,
+        new_value,
+        action: action as u8,
+        padding: [0; 7],
+    });
+    Ok(())
+}
+
+// Helper function to check the owner may migrate accounts of the v0 layout: an admin migrates them
+// right after the upgrade, before the admin multisig is initialized
+fn check_migration_authority(
+    program_data_info: &AccountInfo,
+    owner_key: &Pubkey,
+    owner_role: Option<&RoleAssignment>,
+    admin_multisig_info: &AccountInfo,
+) -> Result<()> {
+    if !admin_multisig_info.data_is_empty() {
+        return err!(ZkError::MultisigApprovalRequired);
+    }
+    check_role(program_data_info, owner_key, owner_role, Role::Admin)
+}
+
+// Helper function to close an account the program owns but can no longer deserialize
+fn close_account<'info>(info: &AccountInfo<'info>, destination: &AccountInfo<'info>) -> Result<()> {
+    let lamports = destination
+        .lamports()
+        .checked_add(info.lamports())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    **destination.try_borrow_mut_lamports()? = lamports;
+    **info.try_borrow_mut_lamports()? = 0;
+
+    info.assign(&System::id());
+    info.realloc(0, false)?;
+    Ok(())
+}
+
+// Helper function to read the slot at which an upgradeable program was last deployed
+fn program_deploy_slot(program_data_info: &AccountInfo) -> u64 {
+    if program_data_info.owner != &bpf_loader_upgradeable::id() {
+        return 0;
+    }
+
+    let data = program_data_info.try_borrow_data().unwrap();
+
+    // ProgramData accounts start with the 4 byte enum tag (3), followed by the 8 byte deployment slot
+    if data.len() < 12 || data[0..4] != [3, 0, 0, 0] {
+        return 0;
+    }
+
+    u64::from_le_bytes(data[4..12].try_into().unwrap())
+}
@@ -0,0 +1,64 @@
+//! Merkleized journals: the guest commits the Merkle root of its journal fields as its journal,
+//! so that consumers check only the fields they need against a verified root.
+//!
+//! Leaves are `SHA-256(0x00 || index || value)`, with the index a little endian u32, and inner
+//! nodes are `SHA-256(0x01 || left || right)`. Leaves are padded with zero hashes up to a power
+//! of two, so a field proof lists one sibling per level, from the leaf up.
+//!
+//! The verification accumulator uses the same inner nodes over statement hashes as leaves, in a
+//! tree of `ACCUMULATOR_DEPTH` levels whose empty leaves are zero.
+
+use anchor_lang::solana_program::hash::hashv;
+
+use super::state::ACCUMULATOR_DEPTH;
+
+/// Deepest field proof accepted, enough for any journal that fits in a transaction
+pub const MAX_JOURNAL_FIELD_PROOF_LEN: usize = 32;
+
+pub fn leaf_hash(index: u32, value: &[u8]) -> [u8; 32] {
+    hashv(&[&[0], &index.to_le_bytes(), value]).to_bytes()
+}
+
+pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[&[1], left, right]).to_bytes()
+}
+
+/// Check that `value` is the field at `index` of the journal committed by `root`
+pub fn verify_field(root: &[u8; 32], index: u32, value: &[u8], proof: &[[u8; 32]]) -> bool {
+    if proof.len() > MAX_JOURNAL_FIELD_PROOF_LEN {
+        return false;
+    }
+
+    let mut node = leaf_hash(index, value);
+    let mut position = index as u64;
+    for sibling in proof {
+        node = if position & 1 == 0 {
+            node_hash(&node, sibling)
+        } else {
+            node_hash(sibling, &node)
+        };
+        position >>= 1;
+    }
+
+    // The index must fit within the depth of the proof
+    position == 0 && &node == root
+}
+
+/// Check that `statement_hash` is the leaf at `index` of the verification accumulator with `root`
+pub fn verify_statement(
+    root: &[u8; 32],
+    index: u64,
+    statement_hash: &[u8; 32],
+    proof: &[[u8; 32]; ACCUMULATOR_DEPTH],
+) -> bool {
+    let mut node = *statement_hash;
+    for (level, sibling) in proof.iter().enumerate() {
+        node = if (index >> level) & 1 == 0 {
+            node_hash(&node, sibling)
+        } else {
+            node_hash(sibling, &node)
+        };
+    }
+
+    index >> ACCUMULATOR_DEPTH == 0 && &node == root
+}
@@ -1,15 +1,1816 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::{hash, hashv};
 
-/// This account keeps track of the number of zkVM verifier configured in the program
+use super::dcap::DcapVerifiedOutput;
+use super::errors::ZkError;
+use super::freshness::MAX_FRESHNESS_AGE_SLOTS;
+use super::merkle::node_hash;
+use super::MIN_ZK_VERIFY_INSTRUCTION_DATA_LEN;
+
+/// Identifier of the registry created at initialization, governed by the program admins
+pub const DEFAULT_REGISTRY_ID: u64 = 0;
+
+/// Address of the default registry, its audit log also records the admin actions that are not
+/// scoped to a registry
+pub fn default_registry_address() -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"registry", DEFAULT_REGISTRY_ID.to_le_bytes().as_ref()],
+        &crate::ID,
+    )
+    .0
+}
+
+/// This account is a namespace of zkVM verifiers, each tenant registry has its own admin and selectors
 #[account]
-pub struct Counter {
-    pub count: u64
+#[derive(InitSpace)]
+pub struct Registry {
+    pub registry_id: u64,
+    /// Admin of a tenant registry, unset for the default registry
+    pub admin: Pubkey,
+    /// Number of zkVM verifiers configured in the registry
+    pub count: u64,
+    /// Set by a guardian to refuse every verification in the registry
+    pub paused: bool,
+    /// Guardian of a tenant registry, can freeze its verifiers and pause it alongside the admin.
+    /// Unset for the default registry, whose guardians hold the program guardian role
+    pub guardian: Pubkey,
+}
+
+impl Registry {
+    pub fn is_default(&self) -> bool {
+        self.registry_id == DEFAULT_REGISTRY_ID
+    }
+
+    /// Check that `admin` administers this tenant registry
+    pub fn check_admin(&self, admin: &Pubkey) -> Result<()> {
+        if self.is_default() || &self.admin != admin {
+            return err!(ZkError::NotRegistryAdmin);
+        }
+        Ok(())
+    }
+
+    /// Check that `guardian` is the admin or the guardian of this tenant registry
+    pub fn check_guardian(&self, guardian: &Pubkey) -> Result<()> {
+        let is_guardian = self.guardian != Pubkey::default() && &self.guardian == guardian;
+        if self.is_default() || (&self.admin != guardian && !is_guardian) {
+            return err!(ZkError::NotRegistryGuardian);
+        }
+        Ok(())
+    }
+}
+
+/// How a zkVM derives the output digest it verifies from the journal (public values)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum JournalDigest {
+    /// SHA-256 of the journal, used by RISC Zero
+    Sha256,
+    /// SHA-256 of the public values with the top 3 bits cleared to fit the BN254 field, used by SP1
+    Sha256Bn254,
+}
+
+impl JournalDigest {
+    pub fn digest(&self, journal: &[u8]) -> [u8; 32] {
+        let mut digest = hash(journal).to_bytes();
+        if *self == JournalDigest::Sha256Bn254 {
+            digest[0] &= 0b0001_1111;
+        }
+        digest
+    }
 }
 
 /// This account stores the Verifier program ID and the corresponding vkey of the zkVM Program
 #[account]
+#[derive(InitSpace)]
 pub struct ZkvmVerifier {
+    /// Registry the verifier is configured in, selectors are only unique within a registry
+    pub registry: Pubkey,
     pub zkvm_selector: u64,
     pub zkvm_program_id: Pubkey,
-    pub frozen: bool
-}
\ No newline at end of file
+    /// Slot at which `zkvm_program_id` was last deployed, 0 if it is not upgradeable
+    pub zkvm_program_deploy_slot: u64,
+    pub journal_digest: JournalDigest,
+    pub frozen: bool,
+    /// Set when the verifier is being retired, verifications are still accepted until `sunset_slot`
+    pub deprecated: bool,
+    /// Last slot at which a deprecated verifier still accepts proofs
+    pub sunset_slot: u64,
+    /// Selector of the verifier that integrators should migrate to
+    pub successor_selector: u64,
+    /// Program ID of the verifier that integrators should migrate to
+    pub successor_program_id: Pubkey,
+    /// Bumped when a soundness issue is found, results of earlier epochs are no longer trusted
+    pub revocation_epoch: u64,
+    /// Only callers with an `AllowedCaller` account can verify through a permissioned verifier
+    pub permissioned: bool,
+    /// Set when the program is added or updated, proofs are refused until the canary proof activates it
+    pub pending: bool,
+    /// Set when the verifier is added for Automata DCAP guest programs. Its proofs are only verified
+    /// for approved guests, so every result it records attests a DCAP quote
+    pub dcap: bool,
+}
+
+impl ZkvmVerifier {
+    /// Check that a result this verifier recorded under `revocation_epoch` was not revoked since
+    pub fn check_not_revoked(&self, revocation_epoch: u64) -> Result<()> {
+        if revocation_epoch < self.revocation_epoch {
+            return err!(ZkError::VerificationResultRevoked);
+        }
+
+        Ok(())
+    }
+
+    /// Check that a verification result was recorded by this verifier and was not revoked
+    pub fn check_result(&self, result: &VerificationResult) -> Result<()> {
+        if result.zkvm_selector != self.zkvm_selector
+            || result.zkvm_program_id != self.zkvm_program_id
+        {
+            return err!(ZkError::ZkvmSelectorMismatch);
+        }
+
+        self.check_not_revoked(result.revocation_epoch)
+    }
+}
+
+/// This account counted the zkVM verifiers before registries existed (account layout v0), it is only
+/// read to migrate the count to the default registry
+#[account]
+pub struct Counter {
+    pub count: u64,
+}
+
+/// zkVM verifier account before registries existed (account layout v0), at the seeds
+/// `[b"zkvm_verifier", zkvm_selector, zkvm_program_id]` and with the discriminator of `ZkvmVerifier`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ZkvmVerifierV0 {
+    pub zkvm_selector: u64,
+    pub zkvm_program_id: Pubkey,
+    pub frozen: bool,
+}
+
+impl ZkvmVerifierV0 {
+    /// Size of the account including the discriminator
+    pub const SPACE: usize = 8 + 8 + 32 + 1;
+
+    /// Decode the data of a v0 zkVM verifier account, the size tells it apart from a `ZkvmVerifier`
+    pub fn try_from_account_data(data: &[u8]) -> Result<Self> {
+        if data.len() != Self::SPACE || data[..8] != *ZkvmVerifier::DISCRIMINATOR {
+            return err!(ZkError::InvalidV0Account);
+        }
+        Self::deserialize(&mut &data[8..]).map_err(|_| error!(ZkError::InvalidV0Account))
+    }
+}
+
+/// Maximum length of the verify instruction data of a canary proof
+pub const MAX_CANARY_PROOF_LEN: usize = 512;
+
+/// Known-good and known-bad verify instruction data of a selector, a pending verifier program is only
+/// activated once it accepts the known-good proof. A failed CPI aborts the transaction, so the registry
+/// cannot check that the known-bad proof is rejected: clients simulate it before activating
+#[account]
+#[derive(InitSpace)]
+pub struct CanaryProof {
+    pub registry: Pubkey,
+    pub zkvm_selector: u64,
+    #[max_len(MAX_CANARY_PROOF_LEN)]
+    pub valid_instruction_data: Vec<u8>,
+    /// Empty when no known-bad proof is set. Only simulated by clients, never checked on-chain
+    #[max_len(MAX_CANARY_PROOF_LEN)]
+    pub invalid_instruction_data: Vec<u8>,
+}
+
+impl CanaryProof {
+    /// Check that the instruction data fits the account and the known-good proof carries a vkey and output digest
+    pub fn check_instruction_data(
+        valid_instruction_data: &[u8],
+        invalid_instruction_data: &[u8],
+    ) -> Result<()> {
+        if valid_instruction_data.len() < MIN_ZK_VERIFY_INSTRUCTION_DATA_LEN
+            || valid_instruction_data.len() > MAX_CANARY_PROOF_LEN
+            || invalid_instruction_data.len() > MAX_CANARY_PROOF_LEN
+        {
+            return err!(ZkError::InvalidCanaryProof);
+        }
+
+        Ok(())
+    }
+
+    /// SHA-256 of the known-good instruction data, recorded in the audit log
+    pub fn digest(&self) -> [u8; 32] {
+        hash(&self.valid_instruction_data).to_bytes()
+    }
+
+    /// Digest of both instruction data, approved by the admin multisig in place of the data itself
+    pub fn instruction_data_digest(
+        valid_instruction_data: &[u8],
+        invalid_instruction_data: &[u8],
+    ) -> [u8; 32] {
+        hashv(&[
+            &(valid_instruction_data.len() as u64).to_le_bytes(),
+            valid_instruction_data,
+            invalid_instruction_data,
+        ])
+        .to_bytes()
+    }
+}
+
+/// This account allows a consumer program or signer to verify through a permissioned verifier, it exists only while allowed.
+/// Programs invoked below the top-level instruction are allowed by their caller authority PDA
+#[account]
+#[derive(InitSpace)]
+pub struct AllowedCaller {
+    pub zkvm_verifier: Pubkey,
+    pub caller: Pubkey,
+}
+
+/// Number of recent epochs for which per-epoch verification counts are kept
+pub const EPOCH_USAGE_HISTORY: usize = 8;
+
+/// Verification count of a single epoch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct EpochUsage {
+    pub epoch: u64,
+    pub verifications: u64,
+}
+
+/// This account keeps the usage statistics of a zkVM verifier
+#[account]
+#[derive(InitSpace)]
+pub struct ZkvmVerifierStats {
+    pub zkvm_verifier: Pubkey,
+    pub total_verifications: u64,
+    pub last_verified_slot: u64,
+    /// Verification counts of recent epochs, indexed by `epoch % EPOCH_USAGE_HISTORY`
+    pub epoch_usage: [EpochUsage; EPOCH_USAGE_HISTORY],
+}
+
+impl ZkvmVerifierStats {
+    /// Record a successful verification at the given clock
+    pub fn record_verification(&mut self, clock: &Clock) {
+        self.total_verifications += 1;
+        self.last_verified_slot = clock.slot;
+
+        let usage = &mut self.epoch_usage[(clock.epoch % EPOCH_USAGE_HISTORY as u64) as usize];
+        if usage.epoch != clock.epoch {
+            // The slot still holds an older epoch, start counting afresh
+            usage.epoch = clock.epoch;
+            usage.verifications = 0;
+        }
+        usage.verifications += 1;
+    }
+
+    /// Verification count of the given epoch, if it is still within the kept history
+    pub fn epoch_verifications(&self, epoch: u64) -> Option<u64> {
+        let usage = &self.epoch_usage[(epoch % EPOCH_USAGE_HISTORY as u64) as usize];
+        (usage.epoch == epoch && usage.verifications > 0).then_some(usage.verifications)
+    }
+}
+
+/// Serialized length of a `VerificationResult`
+pub const VERIFICATION_RESULT_LEN: usize = 32 + 8 + 32 + 32 + 32 + 8 + 8;
+
+/// Statement verified by `verify_zkvm_proof`, set as the return data of the instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerificationResult {
+    pub registry: Pubkey,
+    pub zkvm_selector: u64,
+    pub zkvm_program_id: Pubkey,
+    pub program_vkey: [u8; 32],
+    pub output_digest: [u8; 32],
+    /// Slot at which the proof was verified
+    pub slot: u64,
+    /// Revocation epoch of the verifier at the time of verification, see `ZkvmVerifier::check_result`
+    pub revocation_epoch: u64,
+}
+
+impl VerificationResult {
+    /// See `statement_hash`
+    pub fn statement_hash(&self) -> [u8; 32] {
+        statement_hash(
+            &self.registry,
+            self.zkvm_selector,
+            &self.program_vkey,
+            &self.output_digest,
+        )
+    }
+}
+
+/// Statement accepted under a hybrid policy by `verify_hybrid` or `verify_hybrid_enclave`, set as the
+/// return data of the instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HybridVerificationResult {
+    pub hybrid_policy: Pubkey,
+    pub mode: HybridMode,
+    pub output_digest: [u8; 32],
+    /// Whether a ZK proof of the statement was verified
+    pub zk_verified: bool,
+    /// Whether an attested enclave signed the statement
+    pub tee_verified: bool,
+    /// Enclave key that signed the statement, default if none did
+    pub enclave_key: Pubkey,
+    /// Slot at which the statement was accepted
+    pub slot: u64,
+}
+
+/// SHA-256 of the registry, the selector (little endian), the vkey and the output digest of a verified statement
+pub fn statement_hash(
+    registry: &Pubkey,
+    zkvm_selector: u64,
+    program_vkey: &[u8; 32],
+    output_digest: &[u8; 32],
+) -> [u8; 32] {
+    let mut statement = Vec::with_capacity(32 + 8 + 32 + 32);
+    statement.extend_from_slice(registry.as_ref());
+    statement.extend_from_slice(&zkvm_selector.to_le_bytes());
+    statement.extend_from_slice(program_vkey);
+    statement.extend_from_slice(output_digest);
+    hash(&statement).to_bytes()
+}
+
+/// This account approves an Automata DCAP guest program for a zkVM selector, it exists only while approved
+#[account]
+#[derive(InitSpace)]
+pub struct DcapGuest {
+    pub registry: Pubkey,
+    pub zkvm_selector: u64,
+    /// Image ID (RISC Zero) or program vkey hash (SP1) of the DCAP guest program
+    pub guest_vkey: [u8; 32],
+}
+
+/// Maximum number of measurements a `DcapPolicy` can accept
+pub const MAX_DCAP_POLICY_MEASUREMENTS: usize = 8;
+
+/// This account stores the policy an application enforces on DCAP verified outputs
+#[account]
+#[derive(InitSpace)]
+pub struct DcapPolicy {
+    pub authority: Pubkey,
+    pub policy_id: u64,
+    /// Bitmask of accepted `TcbStatus` values, see `TcbStatus::mask`
+    pub allowed_tcb_statuses: u8,
+    pub min_tcb_evaluation_data_number: u32,
+    /// Accepted MRENCLAVE (zero padded) or MRTD values, any measurement is accepted when empty
+    #[max_len(MAX_DCAP_POLICY_MEASUREMENTS)]
+    pub measurements: Vec<[u8; 48]>,
+    /// How long, in seconds after the guest verification, enclave keys registered under this policy stay valid
+    pub enclave_key_validity: u64,
+}
+
+impl DcapPolicy {
+    /// Enforce the policy on a decoded DCAP verified output
+    pub fn check(&self, output: &DcapVerifiedOutput) -> Result<()> {
+        if self.allowed_tcb_statuses & output.tcb_status.mask() == 0 {
+            return err!(ZkError::DcapTcbStatusNotAllowed);
+        }
+
+        if output.tcb_evaluation_data_number < self.min_tcb_evaluation_data_number {
+            return err!(ZkError::DcapTcbEvaluationTooOld);
+        }
+
+        if !self.measurements.is_empty() && !self.measurements.contains(&output.measurement) {
+            return err!(ZkError::DcapMeasurementNotAllowed);
+        }
+
+        Ok(())
+    }
+    /// Unix timestamp at which an enclave key attested at `timestamp` expires under this policy
+    pub fn enclave_key_expires_at(&self, timestamp: u64) -> Result<i64> {
+        let timestamp =
+            i64::try_from(timestamp).map_err(|_| error!(ZkError::InvalidEnclaveKeyExpiry))?;
+        let validity = i64::try_from(self.enclave_key_validity)
+            .map_err(|_| error!(ZkError::InvalidEnclaveKeyExpiry))?;
+        timestamp
+            .checked_add(validity)
+            .ok_or_else(|| error!(ZkError::InvalidEnclaveKeyExpiry))
+    }
+}
+
+/// Maximum number of TCB evaluation data numbers the collateral config can accept
+pub const MAX_ACCEPTED_TCB_EVALUATION_DATA_NUMBERS: usize = 8;
+
+/// This account stores the DCAP collateral trust anchors that DCAP journals are checked against
+#[account]
+#[derive(InitSpace)]
+pub struct DcapCollateralConfig {
+    /// Hash of the Intel SGX root CA certificate
+    pub root_ca_hash: [u8; 32],
+    pub root_crl_hash: [u8; 32],
+    pub pck_crl_hash: [u8; 32],
+    /// TCB evaluation data numbers of the TCB info and QE identity collateral that are accepted
+    #[max_len(MAX_ACCEPTED_TCB_EVALUATION_DATA_NUMBERS)]
+    pub tcb_evaluation_data_numbers: Vec<u32>,
+    /// Maximum age, in seconds, of the guest verification timestamp
+    pub max_collateral_age: u64,
+}
+
+impl DcapCollateralConfig {
+    /// Digest of the whole config, recorded in the audit log
+    pub fn digest(&self) -> Result<[u8; 32]> {
+        let mut data = Vec::new();
+        self.serialize(&mut data)?;
+        Ok(hash(&data).to_bytes())
+    }
+
+    /// Replace the trust anchors, checking that the accepted TCB evaluation data numbers fit the account
+    pub fn set(
+        &mut self,
+        root_ca_hash: [u8; 32],
+        root_crl_hash: [u8; 32],
+        pck_crl_hash: [u8; 32],
+        tcb_evaluation_data_numbers: Vec<u32>,
+        max_collateral_age: u64,
+    ) -> Result<()> {
+        if tcb_evaluation_data_numbers.len() > MAX_ACCEPTED_TCB_EVALUATION_DATA_NUMBERS {
+            return err!(ZkError::TooManyTcbEvaluationDataNumbers);
+        }
+
+        self.root_ca_hash = root_ca_hash;
+        self.root_crl_hash = root_crl_hash;
+        self.pck_crl_hash = pck_crl_hash;
+        self.tcb_evaluation_data_numbers = tcb_evaluation_data_numbers;
+        self.max_collateral_age = max_collateral_age;
+
+        Ok(())
+    }
+
+    /// Check the collateral a DCAP journal was verified against, `now` being the current unix timestamp
+    pub fn check(&self, output: &DcapVerifiedOutput, now: i64) -> Result<()> {
+        if output.collateral.root_ca != self.root_ca_hash {
+            return err!(ZkError::DcapRootCaMismatch);
+        }
+
+        if output.collateral.root_crl != self.root_crl_hash
+            || output.collateral.pck_crl != self.pck_crl_hash
+        {
+            return err!(ZkError::DcapCrlMismatch);
+        }
+
+        if !self
+            .tcb_evaluation_data_numbers
+            .contains(&output.tcb_evaluation_data_number)
+        {
+            return err!(ZkError::DcapTcbEvaluationNotAccepted);
+        }
+
+        let now = u64::try_from(now).map_err(|_| error!(ZkError::DcapCollateralFromFuture))?;
+        let age = now
+            .checked_sub(output.timestamp)
+            .ok_or_else(|| error!(ZkError::DcapCollateralFromFuture))?;
+        if age > self.max_collateral_age {
+            return err!(ZkError::DcapCollateralExpired);
+        }
+
+        Ok(())
+    }
+}
+
+/// Signature scheme of an enclave key
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum EnclaveKeyType {
+    /// Ed25519 public key, committed in the first 32 bytes of the report data
+    Ed25519,
+    /// secp256k1 Ethereum address, committed in the first 20 bytes of the report data
+    Secp256k1,
+}
+
+impl EnclaveKeyType {
+    /// Extract the key the enclave committed to in its report data, zero padded to 32 bytes
+    pub fn key_from_report_data(&self, report_data: &[u8; 64]) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        match self {
+            EnclaveKeyType::Ed25519 => key.copy_from_slice(&report_data[..32]),
+            EnclaveKeyType::Secp256k1 => key[..20].copy_from_slice(&report_data[..20]),
+        }
+        key
+    }
+}
+
+/// This account stores an enclave key attested by a verified DCAP proof under a `DcapPolicy`
+#[account]
+#[derive(InitSpace)]
+pub struct EnclaveKey {
+    pub dcap_policy: Pubkey,
+    pub key_type: EnclaveKeyType,
+    /// Ed25519 public key, or secp256k1 Ethereum address zero padded to 32 bytes
+    pub key: [u8; 32],
+    pub tee_type: u32,
+    /// MRENCLAVE (SGX, zero padded) or MRTD (TDX)
+    pub measurement: [u8; 48],
+    pub mr_signer: [u8; 32],
+    /// Unix timestamp after which the key is no longer trusted
+    pub expires_at: i64,
+    /// Receives the rent back when the expired key is closed
+    pub payer: Pubkey,
+}
+
+/// Which evidence a `HybridPolicy` requires to accept a result
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum HybridMode {
+    ZkOnly,
+    TeeOnly,
+    ZkOrTee,
+    ZkAndTee,
+}
+
+impl HybridMode {
+    /// Whether the evidence provided satisfies the mode
+    pub fn is_satisfied(&self, zk_verified: bool, tee_verified: bool) -> bool {
+        match self {
+            HybridMode::ZkOnly => zk_verified,
+            HybridMode::TeeOnly => tee_verified,
+            HybridMode::ZkOrTee => zk_verified || tee_verified,
+            HybridMode::ZkAndTee => zk_verified && tee_verified,
+        }
+    }
+}
+
+/// This account stores how an application accepts results: a ZK proof, an attested enclave signature, or both
+#[account]
+#[derive(InitSpace)]
+pub struct HybridPolicy {
+    pub authority: Pubkey,
+    pub policy_id: u64,
+    pub mode: HybridMode,
+    /// Registry of the zkVM verifier the ZK proof must go through
+    pub registry: Pubkey,
+    /// Selector of the zkVM verifier the ZK proof must go through
+    pub zkvm_selector: u64,
+    /// Program vkey the ZK proof must be for
+    pub program_vkey: [u8; 32],
+    /// DCAP policy the enclave key must have been registered under
+    pub dcap_policy: Pubkey,
+}
+
+/// Length of the journal of a state transition guest: state owner, state id, old root, new root and sequence
+pub const STATE_TRANSITION_JOURNAL_LEN: usize = 32 + 8 + 32 + 32 + 8;
+
+/// State transition committed in the journal of a state transition guest, the state id and sequence are little endian.
+/// The owner and state id bind the transition to a single `VerifiedState` so it cannot be replayed on another one
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StateTransition {
+    pub owner: Pubkey,
+    pub state_id: u64,
+    pub old_root: [u8; 32],
+    pub new_root: [u8; 32],
+    pub sequence: u64,
+}
+
+impl StateTransition {
+    pub fn decode(journal: &[u8]) -> Result<Self> {
+        if journal.len() != STATE_TRANSITION_JOURNAL_LEN {
+            return err!(ZkError::MalformedStateTransitionJournal);
+        }
+
+        Ok(Self {
+            owner: Pubkey::new_from_array(journal[..32].try_into().unwrap()),
+            state_id: u64::from_le_bytes(journal[32..40].try_into().unwrap()),
+            old_root: journal[40..72].try_into().unwrap(),
+            new_root: journal[72..104].try_into().unwrap(),
+            sequence: u64::from_le_bytes(journal[104..].try_into().unwrap()),
+        })
+    }
+}
+
+/// This account tracks the state root of an off-chain state machine, advanced only by proofs of its guest
+#[account]
+#[derive(InitSpace)]
+pub struct VerifiedState {
+    pub owner: Pubkey,
+    pub state_id: u64,
+    /// Registry of the zkVM verifier the state transition proofs must go through
+    pub registry: Pubkey,
+    /// Selector of the zkVM verifier the state transition proofs must go through
+    pub zkvm_selector: u64,
+    /// Image ID (RISC Zero) or program vkey hash (SP1) of the state transition guest program
+    pub guest_vkey: [u8; 32],
+    pub root: [u8; 32],
+    /// Sequence number of the last applied transition, 0 for the initial root
+    pub sequence: u64,
+    pub last_advanced_slot: u64,
+}
+
+impl VerifiedState {
+    /// Apply a transition of this state continuing from the current root and sequence
+    pub fn advance(&mut self, transition: &StateTransition, slot: u64) -> Result<()> {
+        if transition.owner != self.owner || transition.state_id != self.state_id {
+            return err!(ZkError::StateIdMismatch);
+        }
+        if transition.old_root != self.root {
+            return err!(ZkError::StateRootMismatch);
+        }
+        if Some(transition.sequence) != self.sequence.checked_add(1) {
+            return err!(ZkError::StateSequenceMismatch);
+        }
+
+        self.root = transition.new_root;
+        self.sequence = transition.sequence;
+        self.last_advanced_slot = slot;
+
+        Ok(())
+    }
+}
+
+/// This account marks the nonce of a freshness envelope as used by its caller
+#[account]
+#[derive(InitSpace)]
+pub struct ProofNonce {
+    /// Slot at which the proof carrying the nonce was verified
+    pub slot: u64,
+}
+
+impl ProofNonce {
+    /// Whether no envelope carrying the nonce can pass the freshness check anymore at `slot`, so
+    /// that closing the account cannot let the proof be replayed
+    pub fn is_expired(&self, slot: u64) -> bool {
+        slot.saturating_sub(self.slot) > MAX_FRESHNESS_AGE_SLOTS
+    }
+}
+
+/// Largest journal a `VerifiedJournal` account can hold, within the account growth allowed in a CPI
+pub const MAX_VERIFIED_JOURNAL_LEN: usize = 8 * 1024;
+
+/// This account holds a journal, stored at once or uploaded in chunks, and the statement it was verified under
+#[account]
+#[derive(InitSpace)]
+pub struct VerifiedJournal {
+    pub authority: Pubkey,
+    pub buffer_id: u64,
+    /// Set once the proof of the journal is verified, the journal can no longer be written afterwards
+    pub verified: bool,
+    pub registry: Pubkey,
+    pub zkvm_selector: u64,
+    pub program_vkey: [u8; 32],
+    pub output_digest: [u8; 32],
+    /// See `statement_hash`
+    pub statement_hash: [u8; 32],
+    pub verified_slot: u64,
+    pub zkvm_program_id: Pubkey,
+    /// See `ZkvmVerifier::check_not_revoked`
+    pub revocation_epoch: u64,
+    /// Space for the journal is allocated at creation
+    #[max_len(0)]
+    pub journal: Vec<u8>,
+}
+
+impl VerifiedJournal {
+    /// Return the journal if it was verified for the given registry, selector and vkey
+    pub fn verified_journal(
+        &self,
+        registry: &Pubkey,
+        zkvm_selector: u64,
+        program_vkey: &[u8; 32],
+    ) -> Result<&[u8]> {
+        if !self.verified {
+            return err!(ZkError::JournalNotVerified);
+        }
+        if &self.registry != registry
+            || self.zkvm_selector != zkvm_selector
+            || &self.program_vkey != program_vkey
+        {
+            return err!(ZkError::JournalStatementMismatch);
+        }
+
+        Ok(&self.journal)
+    }
+
+    /// Record the statement the journal was verified under
+    pub fn mark_verified(&mut self, result: &VerificationResult) {
+        self.verified = true;
+        self.registry = result.registry;
+        self.zkvm_selector = result.zkvm_selector;
+        self.program_vkey = result.program_vkey;
+        self.output_digest = result.output_digest;
+        self.statement_hash = result.statement_hash();
+        self.verified_slot = result.slot;
+        self.zkvm_program_id = result.zkvm_program_id;
+        self.revocation_epoch = result.revocation_epoch;
+    }
+}
+
+/// Depth of the verification accumulator tree
+pub const ACCUMULATOR_DEPTH: usize = 32;
+/// Number of recent accumulator roots kept, so that proofs built against a slightly older root still check
+pub const ACCUMULATOR_ROOT_HISTORY: usize = 64;
+/// Number of verification accumulator shards, verifications append to the shard their caller picks so
+/// that concurrent verifications do not all write lock the same account
+pub const ACCUMULATOR_SHARDS: u64 = 16;
+
+/// This account accumulates the statement hash of successful verifications in an incremental Merkle tree, one per shard.
+/// Only the frontier of the tree is stored, clients rebuild the leaves from the `StatementVerified` events of the shard.
+#[account(zero_copy)]
+pub struct VerificationAccumulator {
+    pub shard: u64,
+    /// Number of appended leaves, also the index of the next one
+    pub next_index: u64,
+    /// Position of the current root in `roots`
+    pub root_index: u64,
+    /// Rightmost left node of each level
+    pub frontier: [[u8; 32]; ACCUMULATOR_DEPTH],
+    pub roots: [[u8; 32]; ACCUMULATOR_ROOT_HISTORY],
+}
+
+impl VerificationAccumulator {
+    pub fn root(&self) -> [u8; 32] {
+        self.roots[self.root_index as usize % ACCUMULATOR_ROOT_HISTORY]
+    }
+
+    /// Whether `root` is the current root or one of the recent ones
+    pub fn is_known_root(&self, root: &[u8; 32]) -> bool {
+        root != &[0u8; 32] && self.roots.contains(root)
+    }
+
+    /// Set the shard and the root of the empty tree
+    pub fn init(&mut self, shard: u64) {
+        self.shard = shard;
+        self.roots[0] = self.fold(0, [0u8; 32]);
+    }
+
+    /// Append a leaf and return its index
+    pub fn append(&mut self, leaf: [u8; 32]) -> Result<u64> {
+        let index = self.next_index;
+        if index >= 1 << ACCUMULATOR_DEPTH {
+            return err!(ZkError::AccumulatorFull);
+        }
+
+        let root = self.fold(index, leaf);
+        self.next_index += 1;
+        self.root_index += 1;
+        self.roots[self.root_index as usize % ACCUMULATOR_ROOT_HISTORY] = root;
+
+        Ok(index)
+    }
+
+    // Hash the leaf at `index` up to the root, recording it in the frontier wherever it is a left node
+    fn fold(&mut self, index: u64, leaf: [u8; 32]) -> [u8; 32] {
+        let mut node = leaf;
+        let mut zero = [0u8; 32];
+        for level in 0..ACCUMULATOR_DEPTH {
+            if (index >> level) & 1 == 0 {
+                self.frontier[level] = node;
+                node = node_hash(&node, &zero);
+            } else {
+                node = node_hash(&self.frontier[level], &node);
+            }
+            zero = node_hash(&zero, &zero);
+        }
+        node
+    }
+}
+
+/// Maximum number of signers of the admin multisig
+pub const MAX_ADMIN_SIGNERS: usize = 10;
+
+/// This account stores the signers and threshold that must approve registry admin actions once
+/// initialized, replacing the program upgrade authority for those actions
+#[account]
+#[derive(InitSpace)]
+pub struct AdminMultisig {
+    #[max_len(MAX_ADMIN_SIGNERS)]
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+    /// Id of the next proposal
+    pub proposal_count: u64,
+    /// Bumped when the signers change, invalidating pending proposals
+    pub nonce: u64,
+    /// Slots between the approval of a proposal and its execution, per `AdminActionKind`
+    pub delay_slots: [u64; ADMIN_ACTION_KINDS],
+}
+
+impl AdminMultisig {
+    pub fn check_config(signers: &[Pubkey], threshold: u8) -> Result<()> {
+        if signers.is_empty()
+            || signers.len() > MAX_ADMIN_SIGNERS
+            || threshold == 0
+            || threshold as usize > signers.len()
+        {
+            return err!(ZkError::InvalidMultisigConfig);
+        }
+        for (i, signer) in signers.iter().enumerate() {
+            if signers[..i].contains(signer) {
+                return err!(ZkError::InvalidMultisigConfig);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn signer_index(&self, signer: &Pubkey) -> Result<usize> {
+        self.signers
+            .iter()
+            .position(|s| s == signer)
+            .ok_or(error!(ZkError::NotMultisigSigner))
+    }
+
+    /// Digest of a set of signers and threshold, recorded in the audit log
+    pub fn config_digest(signers: &[Pubkey], threshold: u8) -> [u8; 32] {
+        let mut data = Vec::with_capacity(signers.len() * 32 + 1);
+        for signer in signers {
+            data.extend_from_slice(signer.as_ref());
+        }
+        data.push(threshold);
+        hash(&data).to_bytes()
+    }
+
+    /// Check every delay is at least the minimum of its kind
+    pub fn check_delays(delay_slots: &[u64; ADMIN_ACTION_KINDS]) -> Result<()> {
+        for (kind, delay) in AdminActionKind::ALL.iter().zip(delay_slots) {
+            kind.check_delay(*delay)?;
+        }
+        Ok(())
+    }
+
+    pub fn delay(&self, action: &AdminAction) -> u64 {
+        self.delay_slots[action.kind() as usize].max(action.min_delay_slots())
+    }
+}
+
+/// Number of `AdminActionKind` variants
+pub const ADMIN_ACTION_KINDS: usize = 9;
+
+/// Minimum timelock delay of the admin actions that change which proofs are accepted or who
+/// governs the registry, so that they can be noticed and vetoed before they execute
+pub const MIN_TIMELOCK_DELAY_SLOTS: u64 = 150;
+
+/// Kind of an `AdminAction`, each kind has its own timelock delay
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum AdminActionKind {
+    AddZkVerifier,
+    UpdateZkVerifier,
+    FreezeZkVerifier,
+    RemoveZkVerifier,
+    UpdateAdminMultisig,
+    SetTimelockDelay,
+    ManageRoles,
+    ConfigureZkVerifier,
+    ConfigureDcap,
+}
+
+impl AdminActionKind {
+    pub const ALL: [AdminActionKind; ADMIN_ACTION_KINDS] = [
+        AdminActionKind::AddZkVerifier,
+        AdminActionKind::UpdateZkVerifier,
+        AdminActionKind::FreezeZkVerifier,
+        AdminActionKind::RemoveZkVerifier,
+        AdminActionKind::UpdateAdminMultisig,
+        AdminActionKind::SetTimelockDelay,
+        AdminActionKind::ManageRoles,
+        AdminActionKind::ConfigureZkVerifier,
+        AdminActionKind::ConfigureDcap,
+    ];
+
+    /// Freezing can execute immediately to stop an unsound verifier, unfreezing is delayed by
+    /// `AdminAction::min_delay_slots`. Every other kind changes which proofs are accepted or who
+    /// governs the registry and waits at least `MIN_TIMELOCK_DELAY_SLOTS`
+    pub fn min_delay_slots(&self) -> u64 {
+        match self {
+            AdminActionKind::FreezeZkVerifier => 0,
+            _ => MIN_TIMELOCK_DELAY_SLOTS,
+        }
+    }
+
+    pub fn check_delay(&self, delay_slots: u64) -> Result<()> {
+        if delay_slots < self.min_delay_slots() {
+            return err!(ZkError::InvalidTimelockDelay);
+        }
+        Ok(())
+    }
+}
+
+/// Registry admin action that requires the approval of the admin multisig
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub enum AdminAction {
+    AddZkVerifier {
+        zkvm_selector: u64,
+        zkvm_program_id: Pubkey,
+        journal_digest: JournalDigest,
+        dcap: bool,
+    },
+    UpdateZkVerifier {
+        zkvm_selector: u64,
+        zkvm_program_id: Pubkey,
+    },
+    FreezeZkVerifier {
+        zkvm_selector: u64,
+        zkvm_program_id: Pubkey,
+        freeze: bool,
+    },
+    RemoveZkVerifier {
+        zkvm_selector: u64,
+        zkvm_program_id: Pubkey,
+    },
+    UpdateAdminMultisig {
+        #[max_len(MAX_ADMIN_SIGNERS)]
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    },
+    SetTimelockDelay {
+        kind: AdminActionKind,
+        delay_slots: u64,
+    },
+    GrantRole {
+        member: Pubkey,
+        role: Role,
+    },
+    RevokeRole {
+        member: Pubkey,
+    },
+    CreateRegistry {
+        registry_id: u64,
+        admin: Pubkey,
+    },
+    RevokeZkVerifierResults {
+        zkvm_selector: u64,
+        zkvm_program_id: Pubkey,
+    },
+    DeprecateZkVerifier {
+        zkvm_selector: u64,
+        zkvm_program_id: Pubkey,
+        sunset_slot: u64,
+        successor_selector: u64,
+        successor_program_id: Pubkey,
+    },
+    /// Digest is `CanaryProof::instruction_data_digest`
+    SetCanaryProof {
+        zkvm_selector: u64,
+        canary_digest: [u8; 32],
+    },
+    AddDcapGuest {
+        zkvm_selector: u64,
+        guest_vkey: [u8; 32],
+    },
+    RemoveDcapGuest {
+        zkvm_selector: u64,
+        guest_vkey: [u8; 32],
+    },
+    SetDcapCollateralConfig {
+        root_ca_hash: [u8; 32],
+        root_crl_hash: [u8; 32],
+        pck_crl_hash: [u8; 32],
+        #[max_len(MAX_ACCEPTED_TCB_EVALUATION_DATA_NUMBERS)]
+        tcb_evaluation_data_numbers: Vec<u32>,
+        max_collateral_age: u64,
+    },
+    UnpauseRegistry {
+        registry_id: u64,
+    },
+}
+
+impl AdminAction {
+    pub fn kind(&self) -> AdminActionKind {
+        match self {
+            AdminAction::AddZkVerifier { .. } => AdminActionKind::AddZkVerifier,
+            AdminAction::UpdateZkVerifier { .. } => AdminActionKind::UpdateZkVerifier,
+            AdminAction::FreezeZkVerifier { .. } => AdminActionKind::FreezeZkVerifier,
+            AdminAction::RemoveZkVerifier { .. } => AdminActionKind::RemoveZkVerifier,
+            AdminAction::UpdateAdminMultisig { .. } => AdminActionKind::UpdateAdminMultisig,
+            AdminAction::SetTimelockDelay { .. } => AdminActionKind::SetTimelockDelay,
+            AdminAction::GrantRole { .. }
+            | AdminAction::RevokeRole { .. }
+            | AdminAction::CreateRegistry { .. } => AdminActionKind::ManageRoles,
+            AdminAction::RevokeZkVerifierResults { .. } | AdminAction::UnpauseRegistry { .. } => {
+                AdminActionKind::FreezeZkVerifier
+            }
+            AdminAction::DeprecateZkVerifier { .. } | AdminAction::SetCanaryProof { .. } => {
+                AdminActionKind::ConfigureZkVerifier
+            }
+            AdminAction::AddDcapGuest { .. }
+            | AdminAction::RemoveDcapGuest { .. }
+            | AdminAction::SetDcapCollateralConfig { .. } => AdminActionKind::ConfigureDcap,
+        }
+    }
+
+    /// Minimum delay before the action executes: freezing a verifier, revoking its results and
+    /// revoking a compromised role are immediate, unfreezing and unpausing always wait for
+    /// `MIN_TIMELOCK_DELAY_SLOTS`
+    pub fn min_delay_slots(&self) -> u64 {
+        match self {
+            AdminAction::FreezeZkVerifier { freeze: true, .. }
+            | AdminAction::RevokeZkVerifierResults { .. }
+            | AdminAction::RevokeRole { .. } => 0,
+            AdminAction::FreezeZkVerifier { freeze: false, .. }
+            | AdminAction::UnpauseRegistry { .. } => MIN_TIMELOCK_DELAY_SLOTS,
+            _ => self.kind().min_delay_slots(),
+        }
+    }
+
+    /// Digest of the action, seeding the account that queues it
+    pub fn digest(&self) -> [u8; 32] {
+        let mut data = Vec::new();
+        self.serialize(&mut data).unwrap();
+        hash(&data).to_bytes()
+    }
+}
+
+/// This account stores an admin action proposed to the admin multisig and the signers that approved it
+#[account]
+#[derive(InitSpace)]
+pub struct AdminProposal {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub action: AdminAction,
+    /// Bit `i` is set once `signers[i]` of the multisig approved
+    pub approvals: u16,
+    /// Multisig nonce at creation, the proposal is void once the signers change
+    pub multisig_nonce: u64,
+    /// Slot from which the proposal can be executed, zero until it reaches the threshold
+    pub eta_slot: u64,
+    pub executed: bool,
+}
+
+impl AdminProposal {
+    /// Queue the proposal behind the timelock of its action once it reaches the threshold.
+    /// Returns whether it was queued by this call
+    pub fn try_queue(&mut self, multisig: &AdminMultisig, slot: u64) -> Result<bool> {
+        if self.eta_slot != 0 || self.approvals.count_ones() < multisig.threshold as u32 {
+            return Ok(false);
+        }
+        self.eta_slot = slot
+            .checked_add(multisig.delay(&self.action))
+            .ok_or(error!(ZkError::InvalidTimelockDelay))?;
+        Ok(true)
+    }
+
+    /// Check the proposal authorizes `action` under the current multisig and its timelock has
+    /// expired, and mark it executed
+    pub fn execute(&mut self, multisig: &AdminMultisig, action: &AdminAction, slot: u64) -> Result<()> {
+        if self.executed {
+            return err!(ZkError::ProposalAlreadyExecuted);
+        }
+        if self.multisig_nonce != multisig.nonce {
+            return err!(ZkError::StaleProposal);
+        }
+        if &self.action != action {
+            return err!(ZkError::ProposalActionMismatch);
+        }
+        if self.eta_slot == 0 {
+            return err!(ZkError::ProposalNotApproved);
+        }
+        if slot < self.eta_slot {
+            return err!(ZkError::TimelockNotExpired);
+        }
+        self.executed = true;
+        Ok(())
+    }
+}
+
+/// This account stores an admin action queued by a single admin while no admin multisig governs the
+/// registry, it is closed when the action executes
+#[account]
+#[derive(InitSpace)]
+pub struct QueuedAdminAction {
+    pub registry: Pubkey,
+    pub queued_by: Pubkey,
+    pub action: AdminAction,
+    /// Slot from which the action can be executed
+    pub eta_slot: u64,
+}
+
+impl QueuedAdminAction {
+    /// Check the queued action is `action` and its timelock has expired
+    pub fn check_executable(&self, action: &AdminAction, slot: u64) -> Result<()> {
+        if &self.action != action {
+            return err!(ZkError::ProposalActionMismatch);
+        }
+        if slot < self.eta_slot {
+            return err!(ZkError::TimelockNotExpired);
+        }
+        Ok(())
+    }
+}
+
+/// Registry role, the program upgrade authority holds every role
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum Role {
+    /// Can perform every admin instruction
+    Admin,
+    /// Can only freeze verifiers
+    Guardian,
+    /// Can manage the callers of permissioned verifiers
+    Operator,
+}
+
+impl Role {
+    /// Whether holding this role allows acting as `role`
+    pub fn grants(&self, role: Role) -> bool {
+        *self == Role::Admin || *self == role
+    }
+}
+
+/// This account grants a role to a member, it exists only while the role is granted
+#[account]
+#[derive(InitSpace)]
+pub struct RoleAssignment {
+    pub member: Pubkey,
+    pub role: Role,
+    pub granted_by: Pubkey,
+}
+
+/// Number of entries kept by the audit log, older entries are overwritten
+pub const AUDIT_LOG_CAPACITY: usize = 60;
+
+/// Admin instruction recorded in the audit log. Values are zero unless stated, numbers and flags
+/// are stored little endian in the first 8 bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AuditAction {
+    /// Subject is the verifier program, new value its deploy slot
+    AddZkVerifier = 1,
+    /// Subject is the verifier program, old and new values its deploy slot
+    UpdateZkVerifier,
+    /// Old and new values are the frozen flag
+    FreezeZkVerifier,
+    /// Old and new values are the frozen flag
+    EmergencyFreezeZkVerifier,
+    /// Subject is the verifier program, old value its deploy slot
+    RemoveZkVerifier,
+    /// Old and new values are the revocation epoch
+    RevokeZkVerifierResults,
+    /// Old and new values are the sunset slot, zero when not deprecated
+    DeprecateZkVerifier,
+    /// Old and new values are the permissioned flag
+    SetZkVerifierPermissioned,
+    /// Subject is the caller
+    AddAllowedCaller,
+    /// Subject is the caller
+    RemoveAllowedCaller,
+    /// New value is the guest vkey
+    AddDcapGuest,
+    /// Old value is the guest vkey
+    RemoveDcapGuest,
+    /// Old and new values are the digest of the collateral config
+    SetDcapCollateralConfig,
+    /// Subject is the member, new value is the role plus one
+    GrantRole,
+    /// Subject is the member, old value is the role plus one
+    RevokeRole,
+    /// Old and new values are the digest of the signers and threshold
+    SetAdminMultisig,
+    /// Selector is the `AdminActionKind`, old and new values the delay
+    SetTimelockDelay,
+    /// Subject is the registry admin
+    CreateRegistry,
+    /// Subject is the new registry admin, old and new values the previous and new admin
+    SetRegistryAdmin,
+    /// Old and new values are the hash of the known-good instruction data
+    SetCanaryProof,
+    /// Subject is the verifier program, new value its deploy slot
+    ActivateZkVerifier,
+    /// Old and new values are the paused flag
+    SetRegistryPaused,
+    /// Old and new values are the verifier count of the default registry
+    MigrateCounter,
+    /// Subject is the verifier program, new value its deploy slot
+    MigrateZkVerifier,
+    /// Subject is the new registry guardian, old and new values the previous and new guardian
+    SetRegistryGuardian,
+}
+
+impl AuditAction {
+    pub const ALL: &'static [AuditAction] = &[
+        AuditAction::AddZkVerifier,
+        AuditAction::UpdateZkVerifier,
+        AuditAction::FreezeZkVerifier,
+        AuditAction::EmergencyFreezeZkVerifier,
+        AuditAction::RemoveZkVerifier,
+        AuditAction::RevokeZkVerifierResults,
+        AuditAction::DeprecateZkVerifier,
+        AuditAction::SetZkVerifierPermissioned,
+        AuditAction::AddAllowedCaller,
+        AuditAction::RemoveAllowedCaller,
+        AuditAction::AddDcapGuest,
+        AuditAction::RemoveDcapGuest,
+        AuditAction::SetDcapCollateralConfig,
+        AuditAction::GrantRole,
+        AuditAction::RevokeRole,
+        AuditAction::SetAdminMultisig,
+        AuditAction::SetTimelockDelay,
+        AuditAction::CreateRegistry,
+        AuditAction::SetRegistryAdmin,
+        AuditAction::SetCanaryProof,
+        AuditAction::ActivateZkVerifier,
+        AuditAction::SetRegistryPaused,
+        AuditAction::MigrateCounter,
+        AuditAction::MigrateZkVerifier,
+        AuditAction::SetRegistryGuardian,
+    ];
+
+    pub fn from_u8(action: u8) -> Option<AuditAction> {
+        Self::ALL.iter().copied().find(|a| *a as u8 == action)
+    }
+}
+
+/// Encode a number or flag as an audit log value
+pub fn audit_value(value: u64) -> [u8; 32] {
+    let mut encoded = [0u8; 32];
+    encoded[..8].copy_from_slice(&value.to_le_bytes());
+    encoded
+}
+
+#[zero_copy]
+pub struct AuditLogEntry {
+    /// Signer of the admin instruction
+    pub actor: Pubkey,
+    pub subject: Pubkey,
+    /// Registry the action applies to, the default registry for program-wide actions
+    pub registry_id: u64,
+    pub zkvm_selector: u64,
+    pub slot: u64,
+    pub old_value: [u8; 32],
+    pub new_value: [u8; 32],
+    /// `AuditAction` discriminant, zero for unused entries
+    pub action: u8,
+    pub padding: [u8; 7],
+}
+
+/// This account keeps the most recent admin actions on a registry in a ring buffer, so that
+/// configuration changes can be attributed without an archive node
+#[account(zero_copy)]
+pub struct AuditLog {
+    /// Number of appended entries, `next_index % AUDIT_LOG_CAPACITY` is the next slot to write
+    pub next_index: u64,
+    pub entries: [AuditLogEntry; AUDIT_LOG_CAPACITY],
+}
+
+impl AuditLog {
+    pub fn append(&mut self, entry: AuditLogEntry) {
+        self.entries[(self.next_index % AUDIT_LOG_CAPACITY as u64) as usize] = entry;
+        self.next_index += 1;
+    }
+
+    /// Entries still in the buffer, oldest first
+    pub fn entries(&self) -> Vec<AuditLogEntry> {
+        let start = self.next_index.saturating_sub(AUDIT_LOG_CAPACITY as u64);
+        (start..self.next_index)
+            .map(|index| self.entries[(index % AUDIT_LOG_CAPACITY as u64) as usize])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dcap::{DcapCollateralHashes, TcbStatus};
+
+    fn dcap_policy(enclave_key_validity: u64) -> DcapPolicy {
+        DcapPolicy {
+            authority: Pubkey::default(),
+            policy_id: 0,
+            allowed_tcb_statuses: 0,
+            min_tcb_evaluation_data_number: 0,
+            measurements: Vec::new(),
+            enclave_key_validity,
+        }
+    }
+
+    #[test]
+    fn enclave_key_expiry_adds_validity() {
+        assert_eq!(
+            dcap_policy(3600)
+                .enclave_key_expires_at(1_700_000_000)
+                .unwrap(),
+            1_700_003_600
+        );
+    }
+
+    #[test]
+    fn enclave_key_expiry_rejects_overflow() {
+        let expected = error!(ZkError::InvalidEnclaveKeyExpiry);
+        assert_eq!(
+            dcap_policy(0).enclave_key_expires_at(u64::MAX).unwrap_err(),
+            expected
+        );
+        assert_eq!(
+            dcap_policy(u64::MAX).enclave_key_expires_at(0).unwrap_err(),
+            expected
+        );
+        assert_eq!(
+            dcap_policy(i64::MAX as u64)
+                .enclave_key_expires_at(1)
+                .unwrap_err(),
+            expected
+        );
+    }
+
+    fn collateral_config(max_collateral_age: u64) -> DcapCollateralConfig {
+        DcapCollateralConfig {
+            root_ca_hash: [1; 32],
+            root_crl_hash: [2; 32],
+            pck_crl_hash: [3; 32],
+            tcb_evaluation_data_numbers: vec![17],
+            max_collateral_age,
+        }
+    }
+
+    fn verified_output(timestamp: u64) -> DcapVerifiedOutput {
+        DcapVerifiedOutput {
+            quote_version: 4,
+            tee_type: 0,
+            tcb_status: TcbStatus::UpToDate,
+            fmspc: [0; 6],
+            measurement: [0; 48],
+            mr_signer: [0; 32],
+            report_data: [0; 64],
+            advisory_ids: Vec::new(),
+            timestamp,
+            tcb_evaluation_data_number: 17,
+            collateral: DcapCollateralHashes {
+                root_ca: [1; 32],
+                root_crl: [2; 32],
+                pck_crl: [3; 32],
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn collateral_within_max_age_is_accepted() {
+        let config = collateral_config(100);
+        assert!(config.check(&verified_output(1_000), 1_000).is_ok());
+        assert!(config.check(&verified_output(1_000), 1_100).is_ok());
+    }
+
+    #[test]
+    fn collateral_older_than_max_age_is_rejected() {
+        let config = collateral_config(100);
+        assert_eq!(
+            config.check(&verified_output(1_000), 1_101).unwrap_err(),
+            error!(ZkError::DcapCollateralExpired)
+        );
+    }
+
+    #[test]
+    fn collateral_from_the_future_is_rejected() {
+        let config = collateral_config(100);
+        assert_eq!(
+            config.check(&verified_output(1_001), 1_000).unwrap_err(),
+            error!(ZkError::DcapCollateralFromFuture)
+        );
+        assert_eq!(
+            config.check(&verified_output(0), -1).unwrap_err(),
+            error!(ZkError::DcapCollateralFromFuture)
+        );
+    }
+
+    #[test]
+    fn collateral_config_rejects_too_many_tcb_evaluation_data_numbers() {
+        let mut config = collateral_config(100);
+        let numbers = vec![0; MAX_ACCEPTED_TCB_EVALUATION_DATA_NUMBERS + 1];
+        assert_eq!(
+            config
+                .set([0; 32], [0; 32], [0; 32], numbers, 0)
+                .unwrap_err(),
+            error!(ZkError::TooManyTcbEvaluationDataNumbers)
+        );
+        assert_eq!(config.tcb_evaluation_data_numbers, vec![17]);
+    }
+
+    fn verified_state() -> VerifiedState {
+        VerifiedState {
+            owner: Pubkey::new_from_array([9; 32]),
+            state_id: 3,
+            registry: Pubkey::default(),
+            zkvm_selector: 1,
+            guest_vkey: [0; 32],
+            root: [4; 32],
+            sequence: 0,
+            last_advanced_slot: 0,
+        }
+    }
+
+    fn state_transition_journal(owner: [u8; 32], state_id: u64, sequence: u64) -> Vec<u8> {
+        let mut journal = owner.to_vec();
+        journal.extend_from_slice(&state_id.to_le_bytes());
+        journal.extend_from_slice(&[4; 32]);
+        journal.extend_from_slice(&[5; 32]);
+        journal.extend_from_slice(&sequence.to_le_bytes());
+        journal
+    }
+
+    #[test]
+    fn state_transition_decodes_and_advances() {
+        let journal = state_transition_journal([9; 32], 3, 1);
+        let transition = StateTransition::decode(&journal).unwrap();
+        assert_eq!(transition.owner, Pubkey::new_from_array([9; 32]));
+        assert_eq!(transition.state_id, 3);
+        assert_eq!(transition.old_root, [4; 32]);
+        assert_eq!(transition.new_root, [5; 32]);
+        assert_eq!(transition.sequence, 1);
+
+        let mut state = verified_state();
+        state.advance(&transition, 42).unwrap();
+        assert_eq!(state.root, [5; 32]);
+        assert_eq!(state.sequence, 1);
+        assert_eq!(state.last_advanced_slot, 42);
+    }
+
+    #[test]
+    fn state_transition_of_another_state_is_rejected() {
+        for journal in [
+            state_transition_journal([8; 32], 3, 1),
+            state_transition_journal([9; 32], 4, 1),
+        ] {
+            let transition = StateTransition::decode(&journal).unwrap();
+            assert_eq!(
+                verified_state().advance(&transition, 42).unwrap_err(),
+                error!(ZkError::StateIdMismatch)
+            );
+        }
+    }
+
+    #[test]
+    fn state_transition_rejects_malformed_journal() {
+        let journal = state_transition_journal([9; 32], 3, 1);
+        assert_eq!(
+            StateTransition::decode(&journal[..72]).unwrap_err(),
+            error!(ZkError::MalformedStateTransitionJournal)
+        );
+    }
+
+    #[test]
+    fn proof_nonce_expires_after_the_freshness_window() {
+        let nonce = ProofNonce { slot: 1_000 };
+        assert!(!nonce.is_expired(1_000));
+        assert!(!nonce.is_expired(1_000 + MAX_FRESHNESS_AGE_SLOTS));
+        assert!(nonce.is_expired(1_001 + MAX_FRESHNESS_AGE_SLOTS));
+    }
+
+    fn zkvm_verifier(revocation_epoch: u64) -> ZkvmVerifier {
+        ZkvmVerifier {
+            registry: Pubkey::new_from_array([1; 32]),
+            zkvm_selector: 2,
+            zkvm_program_id: Pubkey::new_from_array([3; 32]),
+            zkvm_program_deploy_slot: 10,
+            journal_digest: JournalDigest::Sha256,
+            frozen: false,
+            deprecated: false,
+            sunset_slot: 0,
+            successor_selector: 0,
+            successor_program_id: Pubkey::default(),
+            revocation_epoch,
+            permissioned: false,
+            pending: false,
+            dcap: false,
+        }
+    }
+
+    fn verification_result(revocation_epoch: u64) -> VerificationResult {
+        VerificationResult {
+            registry: Pubkey::new_from_array([1; 32]),
+            zkvm_selector: 2,
+            zkvm_program_id: Pubkey::new_from_array([3; 32]),
+            program_vkey: [4; 32],
+            output_digest: [5; 32],
+            slot: 20,
+            revocation_epoch,
+        }
+    }
+
+    #[test]
+    fn result_of_the_current_epoch_is_accepted() {
+        assert!(zkvm_verifier(1)
+            .check_result(&verification_result(1))
+            .is_ok());
+    }
+
+    #[test]
+    fn result_of_a_revoked_epoch_is_rejected() {
+        assert_eq!(
+            zkvm_verifier(2)
+                .check_result(&verification_result(1))
+                .unwrap_err(),
+            error!(ZkError::VerificationResultRevoked)
+        );
+    }
+
+    #[test]
+    fn result_of_another_verifier_is_not_reported_as_revoked() {
+        let mut result = verification_result(0);
+        result.zkvm_program_id = Pubkey::new_from_array([6; 32]);
+        assert_eq!(
+            zkvm_verifier(0).check_result(&result).unwrap_err(),
+            error!(ZkError::ZkvmSelectorMismatch)
+        );
+
+        let mut result = verification_result(0);
+        result.zkvm_selector = 7;
+        assert_eq!(
+            zkvm_verifier(0).check_result(&result).unwrap_err(),
+            error!(ZkError::ZkvmSelectorMismatch)
+        );
+    }
+
+    fn admin_multisig(delay_slots: [u64; ADMIN_ACTION_KINDS]) -> AdminMultisig {
+        AdminMultisig {
+            signers: vec![Pubkey::new_from_array([1; 32])],
+            threshold: 1,
+            proposal_count: 0,
+            nonce: 0,
+            delay_slots,
+        }
+    }
+
+    fn admin_proposal(action: AdminAction) -> AdminProposal {
+        AdminProposal {
+            proposal_id: 0,
+            proposer: Pubkey::new_from_array([1; 32]),
+            action,
+            approvals: 1,
+            multisig_nonce: 0,
+            eta_slot: 0,
+            executed: false,
+        }
+    }
+
+    fn freeze_action(freeze: bool) -> AdminAction {
+        AdminAction::FreezeZkVerifier {
+            zkvm_selector: 2,
+            zkvm_program_id: Pubkey::new_from_array([3; 32]),
+            freeze,
+        }
+    }
+
+    #[test]
+    fn only_freezing_may_have_no_timelock_delay() {
+        let mut delay_slots = [MIN_TIMELOCK_DELAY_SLOTS; ADMIN_ACTION_KINDS];
+        delay_slots[AdminActionKind::FreezeZkVerifier as usize] = 0;
+        assert!(AdminMultisig::check_delays(&delay_slots).is_ok());
+
+        delay_slots[AdminActionKind::UpdateZkVerifier as usize] = 0;
+        assert_eq!(
+            AdminMultisig::check_delays(&delay_slots).unwrap_err(),
+            error!(ZkError::InvalidTimelockDelay)
+        );
+    }
+
+    #[test]
+    fn unfreezing_waits_for_the_minimum_delay() {
+        let multisig = admin_multisig([0; ADMIN_ACTION_KINDS]);
+        assert_eq!(multisig.delay(&freeze_action(true)), 0);
+        assert_eq!(
+            multisig.delay(&freeze_action(false)),
+            MIN_TIMELOCK_DELAY_SLOTS
+        );
+    }
+
+    #[test]
+    fn unpausing_waits_for_the_minimum_delay_unlike_revoking() {
+        let multisig = admin_multisig([0; ADMIN_ACTION_KINDS]);
+        let revoke = AdminAction::RevokeZkVerifierResults {
+            zkvm_selector: 1,
+            zkvm_program_id: Pubkey::new_from_array([2; 32]),
+        };
+        let unpause = AdminAction::UnpauseRegistry { registry_id: 0 };
+        assert_eq!(revoke.kind(), AdminActionKind::FreezeZkVerifier);
+        assert_eq!(unpause.kind(), AdminActionKind::FreezeZkVerifier);
+        assert_eq!(multisig.delay(&revoke), 0);
+        assert_eq!(multisig.delay(&unpause), MIN_TIMELOCK_DELAY_SLOTS);
+    }
+
+    #[test]
+    fn role_and_dcap_actions_use_the_delay_of_their_kind() {
+        let mut delay_slots = [MIN_TIMELOCK_DELAY_SLOTS; ADMIN_ACTION_KINDS];
+        delay_slots[AdminActionKind::ManageRoles as usize] = MIN_TIMELOCK_DELAY_SLOTS + 7;
+        delay_slots[AdminActionKind::ConfigureDcap as usize] = MIN_TIMELOCK_DELAY_SLOTS + 9;
+        let multisig = admin_multisig(delay_slots);
+
+        let grant = AdminAction::GrantRole {
+            member: Pubkey::new_from_array([4; 32]),
+            role: Role::Admin,
+        };
+        let add_guest = AdminAction::AddDcapGuest {
+            zkvm_selector: 1,
+            guest_vkey: [5; 32],
+        };
+        assert_eq!(multisig.delay(&grant), MIN_TIMELOCK_DELAY_SLOTS + 7);
+        assert_eq!(multisig.delay(&add_guest), MIN_TIMELOCK_DELAY_SLOTS + 9);
+        assert!(AdminMultisig::check_delays(&[0; ADMIN_ACTION_KINDS]).is_err());
+    }
+
+    #[test]
+    fn only_freezing_and_revoking_skip_the_minimum_delay() {
+        for kind in [
+            AdminActionKind::ManageRoles,
+            AdminActionKind::ConfigureZkVerifier,
+            AdminActionKind::ConfigureDcap,
+        ] {
+            assert_eq!(
+                kind.check_delay(0).unwrap_err(),
+                error!(ZkError::InvalidTimelockDelay)
+            );
+        }
+
+        let grant = AdminAction::GrantRole {
+            member: Pubkey::new_from_array([4; 32]),
+            role: Role::Admin,
+        };
+        let revoke = AdminAction::RevokeRole {
+            member: Pubkey::new_from_array([4; 32]),
+        };
+        let deprecate = AdminAction::DeprecateZkVerifier {
+            zkvm_selector: 1,
+            zkvm_program_id: Pubkey::new_from_array([5; 32]),
+            sunset_slot: 10,
+            successor_selector: 2,
+            successor_program_id: Pubkey::new_from_array([6; 32]),
+        };
+        assert_eq!(grant.min_delay_slots(), MIN_TIMELOCK_DELAY_SLOTS);
+        assert_eq!(deprecate.min_delay_slots(), MIN_TIMELOCK_DELAY_SLOTS);
+        assert_eq!(revoke.min_delay_slots(), 0);
+    }
+
+    #[test]
+    fn canary_digest_binds_both_instruction_data() {
+        assert_ne!(
+            CanaryProof::instruction_data_digest(&[1, 2], &[3]),
+            CanaryProof::instruction_data_digest(&[1], &[2, 3])
+        );
+    }
+
+    #[test]
+    fn proposal_executes_once_its_timelock_expired() {
+        let multisig = admin_multisig([MIN_TIMELOCK_DELAY_SLOTS; ADMIN_ACTION_KINDS]);
+        let action = AdminAction::UpdateZkVerifier {
+            zkvm_selector: 2,
+            zkvm_program_id: Pubkey::new_from_array([3; 32]),
+        };
+        let mut proposal = admin_proposal(action.clone());
+        assert!(proposal.try_queue(&multisig, 100).unwrap());
+        assert!(!proposal.try_queue(&multisig, 200).unwrap());
+        assert_eq!(proposal.eta_slot, 100 + MIN_TIMELOCK_DELAY_SLOTS);
+
+        assert_eq!(
+            proposal
+                .execute(&multisig, &action, 99 + MIN_TIMELOCK_DELAY_SLOTS)
+                .unwrap_err(),
+            error!(ZkError::TimelockNotExpired)
+        );
+        assert!(proposal
+            .execute(&multisig, &action, 100 + MIN_TIMELOCK_DELAY_SLOTS)
+            .is_ok());
+    }
+
+    #[test]
+    fn proposal_delay_overflowing_the_slot_is_rejected() {
+        let multisig = admin_multisig([u64::MAX; ADMIN_ACTION_KINDS]);
+        let mut proposal = admin_proposal(freeze_action(true));
+        assert_eq!(
+            proposal.try_queue(&multisig, 1).unwrap_err(),
+            error!(ZkError::InvalidTimelockDelay)
+        );
+    }
+
+    #[test]
+    fn queued_admin_action_checks_the_action_and_timelock() {
+        let queued = QueuedAdminAction {
+            registry: Pubkey::new_from_array([1; 32]),
+            queued_by: Pubkey::new_from_array([1; 32]),
+            action: freeze_action(false),
+            eta_slot: 1_000,
+        };
+        assert_eq!(
+            queued
+                .check_executable(&freeze_action(false), 999)
+                .unwrap_err(),
+            error!(ZkError::TimelockNotExpired)
+        );
+        assert_eq!(
+            queued
+                .check_executable(&freeze_action(true), 1_000)
+                .unwrap_err(),
+            error!(ZkError::ProposalActionMismatch)
+        );
+        assert!(queued
+            .check_executable(&freeze_action(false), 1_000)
+            .is_ok());
+        assert_ne!(freeze_action(false).digest(), freeze_action(true).digest());
+    }
+
+    #[test]
+    fn v0_zkvm_verifier_is_decoded_from_its_account_data() {
+        let v0_zkvm_verifier = ZkvmVerifierV0 {
+            zkvm_selector: 1,
+            zkvm_program_id: Pubkey::new_from_array([7; 32]),
+            frozen: true,
+        };
+        let mut data = ZkvmVerifier::DISCRIMINATOR.to_vec();
+        v0_zkvm_verifier.serialize(&mut data).unwrap();
+        assert_eq!(data.len(), ZkvmVerifierV0::SPACE);
+        assert_eq!(
+            ZkvmVerifierV0::try_from_account_data(&data).unwrap(),
+            v0_zkvm_verifier
+        );
+
+        // Another discriminator or the current layout are not v0 verifiers
+        let mut other_discriminator = data.clone();
+        other_discriminator[0] ^= 1;
+        assert_eq!(
+            ZkvmVerifierV0::try_from_account_data(&other_discriminator).unwrap_err(),
+            error!(ZkError::InvalidV0Account)
+        );
+        data.resize(8 + ZkvmVerifier::INIT_SPACE, 0);
+        assert_eq!(
+            ZkvmVerifierV0::try_from_account_data(&data).unwrap_err(),
+            error!(ZkError::InvalidV0Account)
+        );
+    }
+
+    fn audit_log_entry(slot: u64) -> AuditLogEntry {
+        AuditLogEntry {
+            actor: Pubkey::default(),
+            subject: Pubkey::default(),
+            registry_id: DEFAULT_REGISTRY_ID,
+            zkvm_selector: 0,
+            slot,
+            old_value: [0; 32],
+            new_value: audit_value(slot),
+            action: AuditAction::AddZkVerifier as u8,
+            padding: [0; 7],
+        }
+    }
+
+    #[test]
+    fn audit_log_keeps_the_most_recent_entries_oldest_first() {
+        let mut audit_log = AuditLog {
+            next_index: 0,
+            entries: [audit_log_entry(0); AUDIT_LOG_CAPACITY],
+        };
+        assert!(audit_log.entries().is_empty());
+
+        for slot in 0..3 {
+            audit_log.append(audit_log_entry(slot));
+        }
+        let slots: Vec<u64> = audit_log.entries().iter().map(|entry| entry.slot).collect();
+        assert_eq!(slots, vec![0, 1, 2]);
+
+        // Once full, the oldest entries are overwritten
+        let appended = AUDIT_LOG_CAPACITY as u64 + 5;
+        for slot in 3..appended {
+            audit_log.append(audit_log_entry(slot));
+        }
+        let slots: Vec<u64> = audit_log.entries().iter().map(|entry| entry.slot).collect();
+        assert_eq!(slots, (5..appended).collect::<Vec<u64>>());
+        assert_eq!(audit_log.next_index, appended);
+    }
+
+    #[test]
+    fn statement_hash_binds_the_registry_selector_vkey_and_output() {
+        let registry = Pubkey::new_from_array([1; 32]);
+        let expected =
+            hashv(&[registry.as_ref(), &7u64.to_le_bytes(), &[2; 32], &[3; 32]]).to_bytes();
+        assert_eq!(statement_hash(&registry, 7, &[2; 32], &[3; 32]), expected);
+
+        assert_ne!(
+            statement_hash(&Pubkey::new_from_array([4; 32]), 7, &[2; 32], &[3; 32]),
+            expected
+        );
+        assert_ne!(statement_hash(&registry, 8, &[2; 32], &[3; 32]), expected);
+        assert_ne!(statement_hash(&registry, 7, &[4; 32], &[3; 32]), expected);
+        assert_ne!(statement_hash(&registry, 7, &[2; 32], &[4; 32]), expected);
+
+        // The verifier program and slot of a result do not change the statement it proves
+        let result = VerificationResult {
+            registry,
+            zkvm_selector: 7,
+            zkvm_program_id: Pubkey::new_from_array([5; 32]),
+            program_vkey: [2; 32],
+            output_digest: [3; 32],
+            slot: 10,
+            revocation_epoch: 1,
+        };
+        assert_eq!(result.statement_hash(), expected);
+    }
+
+    #[test]
+    fn tenant_registry_is_guarded_by_its_admin_and_guardian_only() {
+        let admin = Pubkey::new_from_array([1; 32]);
+        let guardian = Pubkey::new_from_array([2; 32]);
+        let tenant = Registry {
+            registry_id: 1,
+            admin,
+            count: 0,
+            paused: false,
+            guardian,
+        };
+        assert!(tenant.check_guardian(&admin).is_ok());
+        assert!(tenant.check_guardian(&guardian).is_ok());
+
+        let expected = error!(ZkError::NotRegistryGuardian);
+        let other = Pubkey::new_from_array([3; 32]);
+        assert_eq!(tenant.check_guardian(&other).unwrap_err(), expected);
+
+        // An unset guardian does not let the default key through
+        let unguarded = Registry {
+            guardian: Pubkey::default(),
+            ..tenant
+        };
+        assert_eq!(unguarded.check_guardian(&Pubkey::default()).unwrap_err(), expected);
+
+        // The default registry is guarded by the program roles instead
+        let default_registry = Registry {
+            registry_id: DEFAULT_REGISTRY_ID,
+            admin: Pubkey::default(),
+            count: 0,
+            paused: false,
+            guardian: Pubkey::default(),
+        };
+        assert_eq!(default_registry.check_guardian(&admin).unwrap_err(), expected);
+    }
+}
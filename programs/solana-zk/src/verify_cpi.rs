@@ -0,0 +1,57 @@
+//! Helpers for consumer programs verifying proofs through CPI into the registry and binding the
+//! statement it returns.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::get_return_data;
+
+use super::cpi;
+use super::cpi::accounts::VerifyZkProof;
+use super::errors::ZkError;
+use super::state::VerificationResult;
+
+/// Verify a proof through CPI into the registry `verify_zkvm_proof` instruction and return the
+/// statement it verified
+pub fn verify_zkvm_proof<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, VerifyZkProof<'info>>,
+    zkvm_selector: u64,
+    zk_verify_instruction_data: Vec<u8>,
+) -> Result<VerificationResult> {
+    cpi::verify_zkvm_proof(ctx, zkvm_selector, zk_verify_instruction_data)?;
+
+    verification_result()
+}
+
+/// Verify a proof of the raw journal through CPI into the registry
+/// `verify_zkvm_proof_with_journal` instruction, and decode the journal as `T`. The guest program
+/// must commit the Borsh encoding of `T` as its journal.
+pub fn verify_zkvm_proof_with_journal<'info, T: AnchorDeserialize>(
+    ctx: CpiContext<'_, '_, '_, 'info, VerifyZkProof<'info>>,
+    zkvm_selector: u64,
+    zk_verify_instruction_prefix: Vec<u8>,
+    program_vkey: [u8; 32],
+    journal: Vec<u8>,
+) -> Result<(VerificationResult, T)> {
+    let output = T::try_from_slice(&journal).map_err(|_| error!(ZkError::MalformedJournal))?;
+
+    cpi::verify_zkvm_proof_with_journal(
+        ctx,
+        zkvm_selector,
+        zk_verify_instruction_prefix,
+        program_vkey,
+        journal,
+    )?;
+
+    Ok((verification_result()?, output))
+}
+
+/// Decode the `VerificationResult` returned by the last CPI, if it was made into the registry
+pub fn verification_result() -> Result<VerificationResult> {
+    let (program_id, data) =
+        get_return_data().ok_or(error!(ZkError::MissingVerificationResult))?;
+    if program_id != crate::ID {
+        return err!(ZkError::MissingVerificationResult);
+    }
+
+    VerificationResult::try_from_slice(&data)
+        .map_err(|_| error!(ZkError::MissingVerificationResult))
+}
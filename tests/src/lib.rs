@@ -24,4 +24,12 @@ pub fn get_rpc_client() -> RpcClient {
         "http://localhost:8899".to_string(),
         CommitmentConfig::confirmed()
     )
-}
\ No newline at end of file
+}
+
+/// Wait until the cluster reaches `slot`, e.g. for a timelock to expire
+pub async fn wait_for_slot(slot: u64) {
+    let rpc_client = get_rpc_client();
+    while rpc_client.get_slot().await.unwrap() < slot {
+        tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+    }
+}
@@ -1,8 +1,31 @@
 use super::*;
 use crate::zkvm::risc0::deploy_risc0_groth16_verifier;
-use anchor_client::solana_sdk::{pubkey::Pubkey, signature::read_keypair_file};
+use anchor_client::solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Signer},
+};
 use solana_zk_client::selector::ZkvmSelectorType;
-use solana_zk_client::derive_zkvm_verifier_pda;
+use solana_zk_client::{derive_role_pda, derive_zkvm_verifier_pda};
+use solana_zk_client::error::SolanaZkClientError;
+use solana_zk_consumer::{parse_verify_instruction, VerifiedOutput};
+use solana_zk::errors::ZkError;
+use solana_zk::merkle::verify_statement;
+use solana_zk::state::{
+    statement_hash, AdminAction, AdminActionKind, AuditAction, CanaryProof, Role, RoleAssignment,
+    ADMIN_ACTION_KINDS, DEFAULT_REGISTRY_ID, MIN_TIMELOCK_DELAY_SLOTS,
+};
+use solana_zk_client::accumulator::AccumulatorTree;
+
+const RISC0_PROGRAM_VKEY: [u8; 32] = [
+    194, 234, 254, 27, 160, 22, 16, 243, 183, 18, 129, 249, 221, 50, 128, 179, 61, 151, 55, 11,
+    182, 141, 58, 218, 41, 37, 211, 145, 190, 36, 94, 16,
+];
+const RISC0_OUTPUT_DIGEST: [u8; 32] = [
+    82, 214, 11, 39, 59, 213, 203, 56, 126, 18, 201, 48, 106, 142, 95, 222, 29, 78, 90, 31,
+    203, 21, 88, 64, 76, 137, 82, 59, 91, 242, 160, 174,
+];
+const RISC0_PROOF_HEX: &str = "1850aa52559f1d4a858a48b788b52bdd963888e29465a59ca4dace241ad1aeef2b1796d0acb6ea9f4d77a60a0555f28c85867e62b91ac8d0473ff017c88883da077c6be0d1140a77f0ab695679470472cc32f55ebdcf735e9d52ff4a53d3b685020772e77e8e94578796fd6cc122420a77c1c0ba8dff1c6e07e53e30da46d483147732f37ffb72fda399256a551beb49da688ea7cbdcf268fbc15695c3db42a40569e5093c75654a1390cb1fe9c57c360a8f338f66d61ae1115d4584faecc36f238a9eb4cfecea8d3e4995a354dbe5c4bc12db6a12da41e376931548110fb3c008c01d08cf9e8afb7fe661befbb5afce139c9a1ba1b6c10562645ce60954ab48";
 
 #[tokio::test]
 async fn test_solana_zk_program() {
@@ -20,47 +43,119 @@ async fn test_solana_zk_program() {
     let risc0_pubkey = test_config_risc0(&client, &payer).await;
     println!("====== test_config_risc0 ====== DONE");
 
+    println!("====== test_canary_risc0 ======");
+    test_canary_risc0(&client, &risc0_pubkey).await;
+    println!("====== test_canary_risc0 ====== DONE");
+
     println!("====== test_verify_risc0_proof ======");
     test_verify_risc0_proof(&client, &risc0_pubkey).await;
     println!("====== test_verify_risc0_proof ====== DONE");
+
+    println!("====== test_verify_risc0_invalid_proof ======");
+    test_verify_risc0_invalid_proof(&client, &risc0_pubkey).await;
+    println!("====== test_verify_risc0_invalid_proof ====== DONE");
+
+    println!("====== test_verifier_stats ======");
+    test_verifier_stats(&client, &risc0_pubkey).await;
+    println!("====== test_verifier_stats ====== DONE");
+
+    println!("====== test_verification_accumulator ======");
+    test_verification_accumulator(&client).await;
+    println!("====== test_verification_accumulator ====== DONE");
+
+    println!("====== test_verify_instruction_introspection ======");
+    test_verify_instruction_introspection(&client, &risc0_pubkey).await;
+    println!("====== test_verify_instruction_introspection ====== DONE");
+
+    println!("====== test_permissioned_risc0 ======");
+    test_permissioned_risc0(&client, &payer, &risc0_pubkey).await;
+    println!("====== test_permissioned_risc0 ====== DONE");
+
+    println!("====== test_deprecate_risc0 ======");
+    test_deprecate_risc0(&client, &risc0_pubkey).await;
+    println!("====== test_deprecate_risc0 ====== DONE");
+
+    println!("====== test_roles ======");
+    test_roles(&client, &risc0_pubkey).await;
+    println!("====== test_roles ====== DONE");
+
+    println!("====== test_audit_log ======");
+    test_audit_log(&client, &payer, &risc0_pubkey).await;
+    println!("====== test_audit_log ====== DONE");
+
+    println!("====== test_registries ======");
+    test_registries(&payer, &risc0_pubkey).await;
+    println!("====== test_registries ====== DONE");
+
+    println!("====== test_admin_multisig ======");
+    test_admin_multisig(&client, &payer, &risc0_pubkey).await;
+    println!("====== test_admin_multisig ====== DONE");
 }
 
 async fn test_initialize(client: &SolanaZkClient<&Keypair>) {
     client.initialize().await.expect("Failed to initialize");
+    // Shard 0 is created by initialize, the others on demand
+    if client.accumulator_shard() != 0 {
+        client
+            .init_verification_accumulator(client.accumulator_shard())
+            .await
+            .expect("Failed to initialize verification accumulator shard");
+    }
 
-    // Fetch the counter account data
-    let counter_account = client
-        .program()
-        .account::<solana_zk::state::Counter>(client.counter())
+    // Fetch the default registry account data
+    let registry_account = client
+        .registry_account()
         .await
-        .expect("Failed to fetch counter account");
+        .expect("Failed to fetch registry account");
 
-    assert_eq!(counter_account.count, 0);
+    assert!(registry_account.is_default());
+    assert_eq!(registry_account.count, 0);
 }
 
 async fn test_config_risc0(client: &SolanaZkClient<&Keypair>, payer: &Keypair) -> Pubkey {
     // deploy the RiscZero Groth16 Verifier program
     let rpc_client = get_rpc_client();
-    let zkvm_verifier_program_id = deploy_risc0_groth16_verifier(&payer, &rpc_client)
+    let zkvm_verifier_program_id = deploy_risc0_groth16_verifier(payer, &rpc_client)
         .await
         .expect("Failed to deploy Risc0 Groth16 Verifier program");
 
     // Fetch the zkvm verifier PDA
     let zkvm_selector = ZkvmSelectorType::RiscZero;
-    let (zkvm_verifier_config_pda_id, _) =
-        derive_zkvm_verifier_pda(zkvm_selector.to_u64(), &zkvm_verifier_program_id);
+    let (zkvm_verifier_config_pda_id, _) = derive_zkvm_verifier_pda(
+        &client.registry(),
+        zkvm_selector.to_u64(),
+        &zkvm_verifier_program_id,
+    );
+
+    // A single admin queues the verifier behind the timelock before adding it
+    let action = AdminAction::AddZkVerifier {
+        zkvm_selector: zkvm_selector.to_u64(),
+        zkvm_program_id: zkvm_verifier_program_id,
+        journal_digest: zkvm_selector.journal_digest(),
+        dcap: zkvm_selector.is_dcap(),
+    };
+    let err = client
+        .add_zk_verifier_program(zkvm_selector, Some(zkvm_verifier_program_id))
+        .await
+        .expect_err("Verifiers should not be added before they are queued");
+    assert!(matches!(
+        err,
+        SolanaZkClientError::Registry(ZkError::AdminActionNotQueued)
+    ));
+    client.queue_admin_action(action.clone()).await.unwrap();
+    let queued_admin_action = client.queued_admin_action(&action).await.unwrap();
+    wait_for_slot(queued_admin_action.eta_slot).await;
 
     client
         .add_zk_verifier_program(zkvm_selector, Some(zkvm_verifier_program_id))
         .await
         .unwrap();
 
-    // Fetch the counter account data
-    let counter_account = client
-        .program()
-        .account::<solana_zk::state::Counter>(client.counter())
+    // Fetch the default registry account data
+    let registry_account = client
+        .registry_account()
         .await
-        .expect("Failed to fetch counter account");
+        .expect("Failed to fetch registry account");
 
     // Fetch the Verifier config PDA account data
     let zkvm_verifier_config_pda = client
@@ -69,7 +164,7 @@ async fn test_config_risc0(client: &SolanaZkClient<&Keypair>, payer: &Keypair) -
         .await
         .expect("Failed to fetch zkvm verifier config account");
 
-    assert_eq!(counter_account.count, 1);
+    assert_eq!(registry_account.count, 1);
     assert_eq!(
         zkvm_verifier_config_pda.zkvm_program_id,
         zkvm_verifier_program_id
@@ -78,30 +173,688 @@ async fn test_config_risc0(client: &SolanaZkClient<&Keypair>, payer: &Keypair) -
         zkvm_verifier_config_pda.zkvm_selector,
         zkvm_selector.to_u64()
     );
-    assert_eq!(zkvm_verifier_config_pda.frozen, false);
+    assert!(!zkvm_verifier_config_pda.frozen);
+    assert!(zkvm_verifier_config_pda.pending);
 
     zkvm_verifier_program_id
 }
 
+async fn test_canary_risc0(client: &SolanaZkClient<&Keypair>, risc0_program_id: &Pubkey) {
+    let zkvm_selector = ZkvmSelectorType::RiscZero;
+    let (zkvm_verifier_config_pda_id, _) =
+        derive_zkvm_verifier_pda(&client.registry(), zkvm_selector.to_u64(), risc0_program_id);
+
+    // Pending verifiers refuse proofs until activated
+    let proof_bytes = hex::decode(RISC0_PROOF_HEX).unwrap();
+    let err = client
+        .verify_zkvm_proof(
+            zkvm_selector,
+            Some(*risc0_program_id),
+            RISC0_PROGRAM_VKEY,
+            RISC0_OUTPUT_DIGEST,
+            proof_bytes.as_slice(),
+        )
+        .await
+        .expect_err("Pending verifiers should not verify proofs");
+    assert!(matches!(
+        err,
+        SolanaZkClientError::Registry(ZkError::ZkvmProgramPending)
+    ));
+
+    // The known-bad canary proof tampers with the output digest
+    let mut invalid_output_digest = RISC0_OUTPUT_DIGEST;
+    invalid_output_digest[0] ^= 1;
+    let valid_instruction_data = zkvm_selector
+        .verify_instruction_data(&proof_bytes, RISC0_PROGRAM_VKEY, RISC0_OUTPUT_DIGEST)
+        .unwrap();
+    let invalid_instruction_data = zkvm_selector
+        .verify_instruction_data(&proof_bytes, RISC0_PROGRAM_VKEY, invalid_output_digest)
+        .unwrap();
+
+    // The canary proof decides which verifier gets activated, it waits for the timelock too
+    let action = AdminAction::SetCanaryProof {
+        zkvm_selector: zkvm_selector.to_u64(),
+        canary_digest: CanaryProof::instruction_data_digest(
+            &valid_instruction_data,
+            &invalid_instruction_data,
+        ),
+    };
+    client.queue_admin_action(action.clone()).await.unwrap();
+    let queued_admin_action = client.queued_admin_action(&action).await.unwrap();
+    wait_for_slot(queued_admin_action.eta_slot).await;
+
+    client
+        .init_canary_proof(
+            zkvm_selector,
+            valid_instruction_data,
+            Some(invalid_instruction_data),
+        )
+        .await
+        .unwrap();
+
+    client
+        .activate_zk_verifier_program(zkvm_selector, Some(*risc0_program_id))
+        .await
+        .unwrap();
+
+    let zkvm_verifier_config_pda = client
+        .program()
+        .account::<solana_zk::state::ZkvmVerifier>(zkvm_verifier_config_pda_id)
+        .await
+        .expect("Failed to fetch zkvm verifier config account");
+    assert!(!zkvm_verifier_config_pda.pending);
+
+    // Active verifiers cannot be activated again
+    let err = client
+        .activate_zk_verifier_program(zkvm_selector, Some(*risc0_program_id))
+        .await
+        .expect_err("Active verifiers should not be activated again");
+    assert!(matches!(
+        err,
+        SolanaZkClientError::Registry(ZkError::ZkvmProgramNotPending)
+    ));
+}
+
 async fn test_verify_risc0_proof(client: &SolanaZkClient<&Keypair>, risc0_program_id: &Pubkey) {
-    let program_vkey: [u8; 32] = [
-        194, 234, 254, 27, 160, 22, 16, 243, 183, 18, 129, 249, 221, 50, 128, 179, 61, 151, 55, 11,
-        182, 141, 58, 218, 41, 37, 211, 145, 190, 36, 94, 16,
-    ];
-    let output_digest: [u8; 32] = [
-        82, 214, 11, 39, 59, 213, 203, 56, 126, 18, 201, 48, 106, 142, 95, 222, 29, 78, 90, 31,
-        203, 21, 88, 64, 76, 137, 82, 59, 91, 242, 160, 174,
-    ];
-    let proof_bytes = hex::decode("1850aa52559f1d4a858a48b788b52bdd963888e29465a59ca4dace241ad1aeef2b1796d0acb6ea9f4d77a60a0555f28c85867e62b91ac8d0473ff017c88883da077c6be0d1140a77f0ab695679470472cc32f55ebdcf735e9d52ff4a53d3b685020772e77e8e94578796fd6cc122420a77c1c0ba8dff1c6e07e53e30da46d483147732f37ffb72fda399256a551beb49da688ea7cbdcf268fbc15695c3db42a40569e5093c75654a1390cb1fe9c57c360a8f338f66d61ae1115d4584faecc36f238a9eb4cfecea8d3e4995a354dbe5c4bc12db6a12da41e376931548110fb3c008c01d08cf9e8afb7fe661befbb5afce139c9a1ba1b6c10562645ce60954ab48").unwrap();
+    let proof_bytes = hex::decode(RISC0_PROOF_HEX).unwrap();
 
     client
         .verify_zkvm_proof(
             ZkvmSelectorType::RiscZero,
-            Some(risc0_program_id.clone()),
-            program_vkey,
+            Some(*risc0_program_id),
+            RISC0_PROGRAM_VKEY,
+            RISC0_OUTPUT_DIGEST,
+            proof_bytes.as_slice(),
+        )
+        .await
+        .unwrap();
+}
+
+async fn test_verify_risc0_invalid_proof(
+    client: &SolanaZkClient<&Keypair>,
+    risc0_program_id: &Pubkey,
+) {
+    let proof_bytes = hex::decode(RISC0_PROOF_HEX).unwrap();
+    let mut output_digest = RISC0_OUTPUT_DIGEST;
+    output_digest[0] ^= 1;
+
+    let err = client
+        .verify_zkvm_proof(
+            ZkvmSelectorType::RiscZero,
+            Some(*risc0_program_id),
+            RISC0_PROGRAM_VKEY,
             output_digest,
             proof_bytes.as_slice(),
         )
         .await
+        .expect_err("Verification of a tampered output digest should fail");
+
+    assert!(matches!(err, SolanaZkClientError::Risc0Verifier(_)));
+}
+
+async fn test_verifier_stats(client: &SolanaZkClient<&Keypair>, risc0_program_id: &Pubkey) {
+    let stats = client
+        .verifier_stats(ZkvmSelectorType::RiscZero, Some(*risc0_program_id))
+        .await
+        .expect("Failed to fetch zkvm verifier stats account");
+
+    // Only the valid proof is counted, the tampered one was rejected
+    assert_eq!(stats.total_verifications, 1);
+    assert!(stats.last_verified_slot > 0);
+    assert_eq!(
+        stats
+            .epoch_usage
+            .iter()
+            .map(|usage| usage.verifications)
+            .sum::<u64>(),
+        1
+    );
+}
+
+async fn test_verification_accumulator(client: &SolanaZkClient<&Keypair>) {
+    let accumulator = client
+        .verification_accumulator()
+        .await
+        .expect("Failed to fetch verification accumulator account");
+
+    // Only the valid proof was appended
+    let statement_hash = statement_hash(
+        &client.registry(),
+        ZkvmSelectorType::RiscZero.to_u64(),
+        &RISC0_PROGRAM_VKEY,
+        &RISC0_OUTPUT_DIGEST,
+    );
+    let tree = AccumulatorTree::new(vec![statement_hash]);
+    assert_eq!(accumulator.next_index, 1);
+    assert_eq!(accumulator.root(), tree.root());
+
+    let proof = tree.proof(0).unwrap();
+    assert!(verify_statement(&accumulator.root(), 0, &statement_hash, &proof));
+}
+
+async fn test_verify_instruction_introspection(
+    client: &SolanaZkClient<&Keypair>,
+    risc0_program_id: &Pubkey,
+) {
+    let proof_bytes = hex::decode(RISC0_PROOF_HEX).unwrap();
+
+    let instructions = client
+        .verify_zkvm_proof_instructions(
+            ZkvmSelectorType::RiscZero,
+            Some(*risc0_program_id),
+            RISC0_PROGRAM_VKEY,
+            RISC0_OUTPUT_DIGEST,
+            proof_bytes.as_slice(),
+        )
+        .unwrap();
+
+    // Consumers find the statement proven by the registry instruction through the instructions sysvar
+    let verified = instructions
+        .iter()
+        .find_map(parse_verify_instruction)
+        .expect("Failed to find the registry verify instruction");
+    assert_eq!(verified.zkvm_selector, ZkvmSelectorType::RiscZero.to_u64());
+    assert_eq!(verified.program_vkey, RISC0_PROGRAM_VKEY);
+    assert_eq!(verified.output, VerifiedOutput::Digest(RISC0_OUTPUT_DIGEST));
+
+    // The registry instruction verifies the proof as a top-level instruction
+    let mut request = client.program().request();
+    for instruction in instructions {
+        request = request.instruction(instruction);
+    }
+    request.send().await.unwrap();
+}
+
+async fn test_permissioned_risc0(
+    client: &SolanaZkClient<&Keypair>,
+    payer: &Keypair,
+    risc0_program_id: &Pubkey,
+) {
+    let zkvm_selector = ZkvmSelectorType::RiscZero;
+    let proof_bytes = hex::decode(RISC0_PROOF_HEX).unwrap();
+
+    client
+        .set_zk_verifier_permissioned(zkvm_selector, Some(*risc0_program_id), true)
+        .await
+        .unwrap();
+
+    // The payer is not on the allowlist yet
+    let err = client
+        .verify_zkvm_proof(
+            zkvm_selector,
+            Some(*risc0_program_id),
+            RISC0_PROGRAM_VKEY,
+            RISC0_OUTPUT_DIGEST,
+            proof_bytes.as_slice(),
+        )
+        .await
+        .expect_err("Verification by a caller outside the allowlist should fail");
+    assert!(matches!(
+        err,
+        SolanaZkClientError::Registry(ZkError::CallerNotAllowed)
+    ));
+
+    client
+        .add_allowed_caller(zkvm_selector, Some(*risc0_program_id), payer.pubkey())
+        .await
         .unwrap();
+    test_verify_risc0_proof(client, risc0_program_id).await;
+
+    client
+        .remove_allowed_caller(zkvm_selector, Some(*risc0_program_id), payer.pubkey())
+        .await
+        .unwrap();
+    client
+        .set_zk_verifier_permissioned(zkvm_selector, Some(*risc0_program_id), false)
+        .await
+        .unwrap();
+}
+
+async fn test_deprecate_risc0(client: &SolanaZkClient<&Keypair>, risc0_program_id: &Pubkey) {
+    let zkvm_selector = ZkvmSelectorType::RiscZero;
+    let (zkvm_verifier_config_pda_id, _) =
+        derive_zkvm_verifier_pda(&client.registry(), zkvm_selector.to_u64(), risc0_program_id);
+
+    let current_slot = client.program().rpc().get_slot().await.unwrap();
+    let sunset_slot = current_slot + 1_000;
+
+    let action = AdminAction::DeprecateZkVerifier {
+        zkvm_selector: zkvm_selector.to_u64(),
+        zkvm_program_id: *risc0_program_id,
+        sunset_slot,
+        successor_selector: zkvm_selector.to_u64(),
+        successor_program_id: *risc0_program_id,
+    };
+    client.queue_admin_action(action.clone()).await.unwrap();
+    let queued_admin_action = client.queued_admin_action(&action).await.unwrap();
+    wait_for_slot(queued_admin_action.eta_slot).await;
+
+    client
+        .deprecate_zk_verifier_program(
+            zkvm_selector,
+            Some(*risc0_program_id),
+            sunset_slot,
+            zkvm_selector,
+            Some(*risc0_program_id),
+        )
+        .await
+        .unwrap();
+
+    let zkvm_verifier_config_pda = client
+        .program()
+        .account::<solana_zk::state::ZkvmVerifier>(zkvm_verifier_config_pda_id)
+        .await
+        .expect("Failed to fetch zkvm verifier config account");
+
+    assert!(zkvm_verifier_config_pda.deprecated);
+    assert_eq!(zkvm_verifier_config_pda.sunset_slot, sunset_slot);
+    assert_eq!(
+        zkvm_verifier_config_pda.successor_program_id,
+        *risc0_program_id
+    );
+
+    // Proofs are still accepted during the grace period
+    test_verify_risc0_proof(client, risc0_program_id).await;
+}
+
+async fn test_roles(client: &SolanaZkClient<&Keypair>, risc0_program_id: &Pubkey) {
+    let zkvm_selector = ZkvmSelectorType::RiscZero;
+    let (zkvm_verifier_config_pda_id, _) =
+        derive_zkvm_verifier_pda(&client.registry(), zkvm_selector.to_u64(), risc0_program_id);
+
+    let guardian = Keypair::new();
+    let rpc_client = get_rpc_client();
+    let airdrop = rpc_client
+        .request_airdrop(&guardian.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    rpc_client.poll_for_signature(&airdrop).await.unwrap();
+
+    // Roles are granted behind the timelock, revoking them is immediate
+    let action = AdminAction::GrantRole {
+        member: guardian.pubkey(),
+        role: Role::Guardian,
+    };
+    let err = client
+        .grant_role(guardian.pubkey(), Role::Guardian)
+        .await
+        .expect_err("Roles should not be granted before they are queued");
+    assert!(matches!(
+        err,
+        SolanaZkClientError::Registry(ZkError::AdminActionNotQueued)
+    ));
+    client.queue_admin_action(action.clone()).await.unwrap();
+    let queued_admin_action = client.queued_admin_action(&action).await.unwrap();
+    wait_for_slot(queued_admin_action.eta_slot).await;
+
+    client.grant_role(guardian.pubkey(), Role::Guardian).await.unwrap();
+    let guardian_client = setup(&guardian);
+
+    // Guardians can freeze immediately but cannot perform admin instructions
+    guardian_client
+        .freeze_zk_verifier_program(zkvm_selector, Some(*risc0_program_id), true)
+        .await
+        .expect_err("Guardians should not be able to use admin instructions");
+    guardian_client
+        .emergency_freeze_zk_verifier(zkvm_selector, Some(*risc0_program_id))
+        .await
+        .unwrap();
+
+    let zkvm_verifier_config_pda = client
+        .program()
+        .account::<solana_zk::state::ZkvmVerifier>(zkvm_verifier_config_pda_id)
+        .await
+        .expect("Failed to fetch zkvm verifier config account");
+    assert!(zkvm_verifier_config_pda.frozen);
+
+    // Only an admin can unfreeze, once the unfreeze waited for its timelock
+    let action = AdminAction::FreezeZkVerifier {
+        zkvm_selector: zkvm_selector.to_u64(),
+        zkvm_program_id: *risc0_program_id,
+        freeze: false,
+    };
+    client.queue_admin_action(action.clone()).await.unwrap();
+    let err = client
+        .freeze_zk_verifier_program(zkvm_selector, Some(*risc0_program_id), false)
+        .await
+        .expect_err("Unfreezing should wait for the timelock");
+    assert!(matches!(
+        err,
+        SolanaZkClientError::Registry(ZkError::TimelockNotExpired)
+    ));
+    let queued_admin_action = client.queued_admin_action(&action).await.unwrap();
+    wait_for_slot(queued_admin_action.eta_slot).await;
+    client
+        .freeze_zk_verifier_program(zkvm_selector, Some(*risc0_program_id), false)
+        .await
+        .unwrap();
+
+    // Guardians can pause the registry, refusing every verification until an admin unpauses it
+    guardian_client.pause_registry().await.unwrap();
+    let proof_bytes = hex::decode(RISC0_PROOF_HEX).unwrap();
+    let err = client
+        .verify_zkvm_proof(
+            zkvm_selector,
+            Some(*risc0_program_id),
+            RISC0_PROGRAM_VKEY,
+            RISC0_OUTPUT_DIGEST,
+            proof_bytes.as_slice(),
+        )
+        .await
+        .expect_err("Verifications should be refused while the registry is paused");
+    assert!(matches!(
+        err,
+        SolanaZkClientError::Registry(ZkError::RegistryPaused)
+    ));
+    guardian_client
+        .unpause_registry()
+        .await
+        .expect_err("Guardians should not be able to unpause");
+
+    let unpause = AdminAction::UnpauseRegistry {
+        registry_id: DEFAULT_REGISTRY_ID,
+    };
+    client.queue_admin_action(unpause.clone()).await.unwrap();
+    let queued_admin_action = client.queued_admin_action(&unpause).await.unwrap();
+    wait_for_slot(queued_admin_action.eta_slot).await;
+    client.unpause_registry().await.unwrap();
+    assert!(!client.registry_account().await.unwrap().paused);
+
+    client.revoke_role(guardian.pubkey()).await.unwrap();
+    guardian_client
+        .emergency_freeze_zk_verifier(zkvm_selector, Some(*risc0_program_id))
+        .await
+        .expect_err("Revoked guardians should no longer be able to freeze");
+}
+
+async fn test_audit_log(
+    client: &SolanaZkClient<&Keypair>,
+    payer: &Keypair,
+    risc0_program_id: &Pubkey,
+) {
+    let entries = client.audit_log().await.expect("Failed to fetch audit log account");
+    client.print_audit_log().await.unwrap();
+
+    let first = entries.first().unwrap();
+    assert_eq!(first.action, AuditAction::AddZkVerifier as u8);
+    assert_eq!(first.actor, payer.pubkey());
+    assert_eq!(first.subject, *risc0_program_id);
+    assert_eq!(first.zkvm_selector, ZkvmSelectorType::RiscZero.to_u64());
+
+    // The role changes, freezes and pauses of `test_roles` are the most recent entries
+    let recent: Vec<u8> = entries[entries.len() - 6..]
+        .iter()
+        .map(|entry| entry.action)
+        .collect();
+    assert_eq!(
+        recent,
+        vec![
+            AuditAction::GrantRole as u8,
+            AuditAction::EmergencyFreezeZkVerifier as u8,
+            AuditAction::FreezeZkVerifier as u8,
+            AuditAction::SetRegistryPaused as u8,
+            AuditAction::SetRegistryPaused as u8,
+            AuditAction::RevokeRole as u8,
+        ]
+    );
+}
+
+async fn test_registries(payer: &Keypair, risc0_program_id: &Pubkey) {
+    let zkvm_selector = ZkvmSelectorType::RiscZero;
+    let tenant_admin = Keypair::new();
+    let rpc_client = get_rpc_client();
+    let airdrop = rpc_client
+        .request_airdrop(&tenant_admin.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    rpc_client.poll_for_signature(&airdrop).await.unwrap();
+
+    let client = setup(payer);
+    let action = AdminAction::CreateRegistry {
+        registry_id: 1,
+        admin: tenant_admin.pubkey(),
+    };
+    client.queue_admin_action(action.clone()).await.unwrap();
+    let queued_admin_action = client.queued_admin_action(&action).await.unwrap();
+    wait_for_slot(queued_admin_action.eta_slot).await;
+    client.create_registry(1, tenant_admin.pubkey()).await.unwrap();
+    let tenant_client = setup(&tenant_admin).with_registry(1);
+
+    // The tenant admin configures the selector independently of the default registry, behind the
+    // timelock of the tenant registry
+    let action = AdminAction::AddZkVerifier {
+        zkvm_selector: zkvm_selector.to_u64(),
+        zkvm_program_id: *risc0_program_id,
+        journal_digest: zkvm_selector.journal_digest(),
+        dcap: zkvm_selector.is_dcap(),
+    };
+    let err = tenant_client
+        .add_zk_verifier_program(zkvm_selector, Some(*risc0_program_id))
+        .await
+        .expect_err("Tenant verifiers should not be added before they are queued");
+    assert!(matches!(
+        err,
+        SolanaZkClientError::Registry(ZkError::AdminActionNotQueued)
+    ));
+    tenant_client.queue_admin_action(action.clone()).await.unwrap();
+    let queued_admin_action = tenant_client.queued_admin_action(&action).await.unwrap();
+    assert_eq!(queued_admin_action.registry, tenant_client.registry());
+    wait_for_slot(queued_admin_action.eta_slot).await;
+
+    tenant_client
+        .add_zk_verifier_program(zkvm_selector, Some(*risc0_program_id))
+        .await
+        .unwrap();
+
+    // Tenant admin actions are recorded in the audit log of the tenant registry only
+    let entries = tenant_client
+        .audit_log()
+        .await
+        .expect("Failed to fetch tenant audit log account");
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].action, AuditAction::AddZkVerifier as u8);
+    assert_eq!(entries[0].actor, tenant_admin.pubkey());
+    assert_eq!(entries[0].registry_id, 1);
+
+    let registry_account = tenant_client
+        .registry_account()
+        .await
+        .expect("Failed to fetch registry account");
+    assert_eq!(registry_account.registry_id, 1);
+    assert_eq!(registry_account.admin, tenant_admin.pubkey());
+    assert_eq!(registry_account.count, 1);
+
+    let (zkvm_verifier_config_pda_id, _) = derive_zkvm_verifier_pda(
+        &tenant_client.registry(),
+        zkvm_selector.to_u64(),
+        risc0_program_id,
+    );
+    let zkvm_verifier_config_pda = tenant_client
+        .program()
+        .account::<solana_zk::state::ZkvmVerifier>(zkvm_verifier_config_pda_id)
+        .await
+        .expect("Failed to fetch zkvm verifier config account");
+    assert_eq!(zkvm_verifier_config_pda.registry, tenant_client.registry());
+    assert!(!zkvm_verifier_config_pda.deprecated);
+
+    test_canary_risc0(&tenant_client, risc0_program_id).await;
+    test_verify_risc0_proof(&tenant_client, risc0_program_id).await;
+
+    // Program admins do not administer tenant registries
+    let err = setup(payer)
+        .with_registry(1)
+        .freeze_zk_verifier_program(zkvm_selector, Some(*risc0_program_id), true)
+        .await
+        .expect_err("Only the tenant admin should configure the registry");
+    assert!(matches!(
+        err,
+        SolanaZkClientError::Registry(ZkError::NotRegistryAdmin)
+    ));
+
+    // Program guardians do not freeze tenant verifiers either, only the guardian the tenant admin
+    // appointed does
+    let err = setup(payer)
+        .with_registry(1)
+        .emergency_freeze_zk_verifier(zkvm_selector, Some(*risc0_program_id))
+        .await
+        .expect_err("Only the tenant admin and guardian should freeze tenant verifiers");
+    assert!(matches!(
+        err,
+        SolanaZkClientError::Registry(ZkError::NotRegistryGuardian)
+    ));
+
+    let tenant_guardian = Keypair::new();
+    let airdrop = rpc_client
+        .request_airdrop(&tenant_guardian.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    rpc_client.poll_for_signature(&airdrop).await.unwrap();
+    tenant_client
+        .set_registry_guardian(tenant_guardian.pubkey())
+        .await
+        .unwrap();
+    setup(&tenant_guardian)
+        .with_registry(1)
+        .emergency_freeze_zk_verifier(zkvm_selector, Some(*risc0_program_id))
+        .await
+        .unwrap();
+
+    let zkvm_verifier_config_pda = tenant_client
+        .program()
+        .account::<solana_zk::state::ZkvmVerifier>(zkvm_verifier_config_pda_id)
+        .await
+        .expect("Failed to fetch zkvm verifier config account");
+    assert!(zkvm_verifier_config_pda.frozen);
+}
+
+async fn test_admin_multisig(
+    client: &SolanaZkClient<&Keypair>,
+    payer: &Keypair,
+    risc0_program_id: &Pubkey,
+) {
+    let zkvm_selector = ZkvmSelectorType::RiscZero;
+    let (zkvm_verifier_config_pda_id, _) =
+        derive_zkvm_verifier_pda(&client.registry(), zkvm_selector.to_u64(), risc0_program_id);
+
+    // Freezing through a proposal waits 1000 slots, every other action the minimum delay
+    let mut delay_slots = [MIN_TIMELOCK_DELAY_SLOTS; ADMIN_ACTION_KINDS];
+    delay_slots[AdminActionKind::FreezeZkVerifier as usize] = 1_000;
+
+    // Delays below the minimum are rejected
+    let mut short_delay_slots = delay_slots;
+    short_delay_slots[AdminActionKind::UpdateZkVerifier as usize] = 0;
+    let err = client
+        .init_admin_multisig(vec![payer.pubkey()], 1, short_delay_slots)
+        .await
+        .expect_err("Updating a verifier should not execute immediately");
+    assert!(matches!(
+        err,
+        SolanaZkClientError::Registry(ZkError::InvalidTimelockDelay)
+    ));
+
+    client
+        .init_admin_multisig(vec![payer.pubkey(), Pubkey::new_unique()], 1, delay_slots)
+        .await
+        .unwrap();
+
+    // The upgrade authority alone can no longer freeze the verifier
+    client
+        .freeze_zk_verifier_program(zkvm_selector, Some(*risc0_program_id), true)
+        .await
+        .expect_err("Admin actions should require a multisig proposal");
+
+    // Roles are no longer granted by a single admin either
+    let member = Pubkey::new_unique();
+    let err = client
+        .grant_role(member, Role::Operator)
+        .await
+        .expect_err("Granting a role should require a multisig proposal");
+    assert!(matches!(
+        err,
+        SolanaZkClientError::Registry(ZkError::MultisigApprovalRequired)
+    ));
+
+    let (_, proposal_id) = client
+        .create_admin_proposal(AdminAction::FreezeZkVerifier {
+            zkvm_selector: zkvm_selector.to_u64(),
+            zkvm_program_id: *risc0_program_id,
+            freeze: true,
+        })
+        .await
+        .unwrap();
+    let err = client
+        .execute_admin_proposal(proposal_id)
+        .await
+        .expect_err("Proposals should not execute before their timelock expires");
+    assert!(matches!(
+        err,
+        SolanaZkClientError::Registry(ZkError::TimelockNotExpired)
+    ));
+    client.cancel_admin_proposal(proposal_id).await.unwrap();
+
+    // Any signer can freeze immediately through the emergency path
+    client
+        .emergency_freeze_zk_verifier(zkvm_selector, Some(*risc0_program_id))
+        .await
+        .unwrap();
+
+    let zkvm_verifier_config_pda = client
+        .program()
+        .account::<solana_zk::state::ZkvmVerifier>(zkvm_verifier_config_pda_id)
+        .await
+        .expect("Failed to fetch zkvm verifier config account");
+    assert!(zkvm_verifier_config_pda.frozen);
+
+    let (_, grant_proposal_id) = client
+        .create_admin_proposal(AdminAction::GrantRole {
+            member,
+            role: Role::Operator,
+        })
+        .await
+        .unwrap();
+    let (_, proposal_id) = client
+        .create_admin_proposal(AdminAction::UpdateZkVerifier {
+            zkvm_selector: zkvm_selector.to_u64(),
+            zkvm_program_id: *risc0_program_id,
+        })
+        .await
+        .unwrap();
+    let err = client
+        .execute_admin_proposal(proposal_id)
+        .await
+        .expect_err("Updating a verifier should wait for the timelock");
+    assert!(matches!(
+        err,
+        SolanaZkClientError::Registry(ZkError::TimelockNotExpired)
+    ));
+    let admin_proposal = client.admin_proposal(proposal_id).await.unwrap();
+    wait_for_slot(admin_proposal.eta_slot).await;
+    client.execute_admin_proposal(proposal_id).await.unwrap();
+
+    // The updated program is pending until it accepts the canary proof again
+    let zkvm_verifier_config_pda = client
+        .program()
+        .account::<solana_zk::state::ZkvmVerifier>(zkvm_verifier_config_pda_id)
+        .await
+        .expect("Failed to fetch zkvm verifier config account");
+    assert!(zkvm_verifier_config_pda.pending);
+
+    // An executed proposal cannot be replayed
+    client
+        .execute_admin_proposal(proposal_id)
+        .await
+        .expect_err("Executed proposals should not execute again");
+
+    // Any payer executes the approved role grant through the admin instruction
+    setup(payer)
+        .with_admin_proposal(grant_proposal_id)
+        .grant_role(member, Role::Operator)
+        .await
+        .unwrap();
+    let role_assignment = client
+        .program()
+        .account::<RoleAssignment>(derive_role_pda(&member).0)
+        .await
+        .expect("Failed to fetch role assignment account");
+    assert_eq!(role_assignment.role, Role::Operator);
 }